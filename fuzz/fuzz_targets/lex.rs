@@ -0,0 +1,16 @@
+//! Feeds arbitrary bytes straight to the lexer. `Lexer::scan_tokens` must
+//! turn any input -- valid UTF-8 or not, truncated escapes, unterminated
+//! strings and comments, degenerate numeric literals -- into a token
+//! stream ending in `Eof`, and never panic doing it; malformed input
+//! belongs in an `Error` token, not an index-out-of-bounds or `unwrap`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use matcha::lexer::Lexer;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(source) = std::str::from_utf8(data) else { return };
+    let mut lexer = Lexer::new(source);
+    let _ = lexer.scan_tokens();
+});
@@ -0,0 +1,20 @@
+//! Runs the full lex-then-parse pipeline against arbitrary bytes. The
+//! parser is meant to recover from any malformed token stream by reporting
+//! diagnostics and making forward progress (see `Parser::consume`), so no
+//! input should ever make it panic, however broken the source is.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use matcha::errors::DiagnosticBag;
+use matcha::lexer::Lexer;
+use matcha::parser::Parser;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(source) = std::str::from_utf8(data) else { return };
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.scan_tokens();
+    let mut bag = DiagnosticBag::new();
+    let mut parser = Parser::new(tokens, "<fuzz>", &mut bag);
+    let _ = parser.parse();
+});
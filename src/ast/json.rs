@@ -0,0 +1,568 @@
+//! Renders a [`Module`] as JSON, for tooling that wants the AST in a
+//! machine-readable form (editor plugins, external analyzers) without
+//! linking against this crate. Every node is emitted as an object tagged
+//! with a `"kind"` field naming its variant, so the shape mirrors the
+//! `enum`s in this module one-for-one.
+//!
+//! There's no `serde` (or any other dependency) in this crate, so this is
+//! a small hand-rolled writer rather than a derive — it only needs to
+//! produce valid JSON, not parse it back.
+
+use super::{
+    Enum, Export, Expression, ExpressionKind, Field, Function, Impl, Import, Interface,
+    InterfaceMethodSig, LiteralValue, MatchArm, Module, ModuleBlock, Param, Pattern, Statement,
+    StatementKind, Struct, Type, TypeKind, TypeParam, UserTypeKind,
+};
+use crate::common::Position;
+
+pub fn to_json(module: &Module) -> String {
+    let mut out = String::new();
+    out.push_str("{\"statements\":[");
+    join(&mut out, &module.statements, statement_json);
+    out.push_str("]}");
+    out
+}
+
+fn join<T>(out: &mut String, items: &[T], mut render: impl FnMut(&T) -> String) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&render(item));
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn optional_string_json(value: &Option<String>) -> String {
+    match value {
+        Some(s) => escape(s),
+        None => "null".to_string(),
+    }
+}
+
+fn position_json(position: Position) -> String {
+    format!("{{\"line\":{},\"column\":{}}}", position.line, position.column)
+}
+
+fn param_json(param: &Param) -> String {
+    format!("{{\"name\":{},\"type\":{}}}", escape(&param.name), type_json(&param.ty))
+}
+
+fn type_param_json(param: &TypeParam) -> String {
+    let mut bounds_json = String::new();
+    join(&mut bounds_json, &param.bounds, |bound: &String| escape(bound));
+    format!(
+        "{{\"name\":{},\"bounds\":[{}],\"position\":{}}}",
+        escape(&param.name),
+        bounds_json,
+        position_json(param.position)
+    )
+}
+
+fn field_json(field: &Field) -> String {
+    format!(
+        "{{\"name\":{},\"type\":{},\"isPub\":{}}}",
+        escape(&field.name),
+        type_json(&field.ty),
+        field.is_pub
+    )
+}
+
+fn type_json(ty: &Type) -> String {
+    format!("{{{},\"position\":{}}}", type_kind_body(&ty.kind), position_json(ty.position))
+}
+
+fn type_kind_body(kind: &TypeKind) -> String {
+    match kind {
+        TypeKind::Int8 => "\"kind\":\"Int8\"".to_string(),
+        TypeKind::Int16 => "\"kind\":\"Int16\"".to_string(),
+        TypeKind::Int32 => "\"kind\":\"Int32\"".to_string(),
+        TypeKind::Int64 => "\"kind\":\"Int64\"".to_string(),
+        TypeKind::UInt8 => "\"kind\":\"UInt8\"".to_string(),
+        TypeKind::UInt16 => "\"kind\":\"UInt16\"".to_string(),
+        TypeKind::UInt32 => "\"kind\":\"UInt32\"".to_string(),
+        TypeKind::UInt64 => "\"kind\":\"UInt64\"".to_string(),
+        TypeKind::Float32 => "\"kind\":\"Float32\"".to_string(),
+        TypeKind::Float64 => "\"kind\":\"Float64\"".to_string(),
+        TypeKind::Bool => "\"kind\":\"Bool\"".to_string(),
+        TypeKind::String => "\"kind\":\"String\"".to_string(),
+        TypeKind::Char => "\"kind\":\"Char\"".to_string(),
+        TypeKind::Void => "\"kind\":\"Void\"".to_string(),
+        TypeKind::Error => "\"kind\":\"Error\"".to_string(),
+        TypeKind::Array(inner, size) => format!(
+            "\"kind\":\"Array\",\"element\":{},\"size\":{}",
+            type_json(inner),
+            size.map(|n| n.to_string()).unwrap_or_else(|| "null".to_string())
+        ),
+        TypeKind::UserType(name, user_kind, args) => {
+            let mut args_json = String::new();
+            join(&mut args_json, args, type_json);
+            format!(
+                "\"kind\":\"UserType\",\"name\":{},\"userKind\":{},\"typeArgs\":[{}]",
+                escape(name),
+                escape(user_type_kind_str(user_kind)),
+                args_json
+            )
+        }
+        TypeKind::Function(params, ret) => {
+            let mut params_json = String::new();
+            join(&mut params_json, params, type_json);
+            format!("\"kind\":\"Function\",\"params\":[{}],\"return\":{}", params_json, type_json(ret))
+        }
+        TypeKind::Result(ok, err) => {
+            format!("\"kind\":\"Result\",\"ok\":{},\"err\":{}", type_json(ok), type_json(err))
+        }
+        TypeKind::Tuple(items) => {
+            let mut items_json = String::new();
+            join(&mut items_json, items, type_json);
+            format!("\"kind\":\"Tuple\",\"items\":[{}]", items_json)
+        }
+        TypeKind::Map(key, value) => {
+            format!("\"kind\":\"Map\",\"key\":{},\"value\":{}", type_json(key), type_json(value))
+        }
+    }
+}
+
+/// The literal type-suffix carried by an `Int`/`Float` literal
+/// ([`LiteralValue::Int`]/[`LiteralValue::Float`]'s second field), or
+/// `null` when it had none.
+fn suffix_json(suffix: &Option<TypeKind>) -> String {
+    match suffix {
+        Some(kind) => format!("{{{}}}", type_kind_body(kind)),
+        None => "null".to_string(),
+    }
+}
+
+fn user_type_kind_str(kind: &UserTypeKind) -> &'static str {
+    match kind {
+        UserTypeKind::Struct => "Struct",
+        UserTypeKind::Enum => "Enum",
+        UserTypeKind::Module => "Module",
+        UserTypeKind::Unknown => "Unknown",
+    }
+}
+
+fn literal_json(value: &LiteralValue) -> String {
+    match value {
+        LiteralValue::Int(n, suffix) => format!(
+            "{{\"kind\":\"Int\",\"value\":{},\"suffix\":{}}}",
+            n,
+            suffix_json(suffix)
+        ),
+        LiteralValue::Float(n, suffix) => format!(
+            "{{\"kind\":\"Float\",\"value\":{},\"suffix\":{}}}",
+            n,
+            suffix_json(suffix)
+        ),
+        LiteralValue::String(s) => format!("{{\"kind\":\"String\",\"value\":{}}}", escape(s)),
+        LiteralValue::Char(c) => format!("{{\"kind\":\"Char\",\"value\":{}}}", escape(&c.to_string())),
+        LiteralValue::Bool(b) => format!("{{\"kind\":\"Bool\",\"value\":{}}}", b),
+    }
+}
+
+fn pattern_json(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => "{\"kind\":\"Wildcard\"}".to_string(),
+        Pattern::Literal(value) => format!("{{\"kind\":\"Literal\",\"value\":{}}}", literal_json(value)),
+        Pattern::Identifier(name) => format!("{{\"kind\":\"Identifier\",\"name\":{}}}", escape(name)),
+        Pattern::EnumVariant { enum_name, variant } => format!(
+            "{{\"kind\":\"EnumVariant\",\"enumName\":{},\"variant\":{}}}",
+            escape(enum_name),
+            escape(variant)
+        ),
+    }
+}
+
+fn match_arm_json(arm: &MatchArm) -> String {
+    let mut body_json = String::new();
+    join(&mut body_json, &arm.body, statement_json);
+    format!(
+        "{{\"pattern\":{},\"body\":[{}],\"position\":{}}}",
+        pattern_json(&arm.pattern),
+        body_json,
+        position_json(arm.position)
+    )
+}
+
+fn expression_json(expr: &Expression) -> String {
+    let body = match &expr.kind {
+        ExpressionKind::Literal(value) => format!("\"kind\":\"Literal\",\"value\":{}", literal_json(value)),
+        ExpressionKind::Identifier(name) => format!("\"kind\":\"Identifier\",\"name\":{}", escape(name)),
+        ExpressionKind::Binary { left, op, right } => format!(
+            "\"kind\":\"Binary\",\"op\":{},\"left\":{},\"right\":{}",
+            escape(op),
+            expression_json(left),
+            expression_json(right)
+        ),
+        ExpressionKind::Unary { op, operand } => {
+            format!("\"kind\":\"Unary\",\"op\":{},\"operand\":{}", escape(op), expression_json(operand))
+        }
+        ExpressionKind::Postfix { op, operand } => {
+            format!("\"kind\":\"Postfix\",\"op\":{},\"operand\":{}", escape(op), expression_json(operand))
+        }
+        ExpressionKind::Grouping(inner) => format!("\"kind\":\"Grouping\",\"inner\":{}", expression_json(inner)),
+        ExpressionKind::Call { callee, args } => {
+            let mut args_json = String::new();
+            join(&mut args_json, args, expression_json);
+            format!("\"kind\":\"Call\",\"callee\":{},\"args\":[{}]", expression_json(callee), args_json)
+        }
+        ExpressionKind::Get { object, name } => {
+            format!("\"kind\":\"Get\",\"object\":{},\"name\":{}", expression_json(object), escape(name))
+        }
+        ExpressionKind::Index { object, index } => format!(
+            "\"kind\":\"Index\",\"object\":{},\"index\":{}",
+            expression_json(object),
+            expression_json(index)
+        ),
+        ExpressionKind::Assignment { target, op, value } => format!(
+            "\"kind\":\"Assignment\",\"target\":{},\"op\":{},\"value\":{}",
+            expression_json(target),
+            escape(op),
+            expression_json(value)
+        ),
+        ExpressionKind::ArrayLiteral(items) => {
+            let mut items_json = String::new();
+            join(&mut items_json, items, expression_json);
+            format!("\"kind\":\"ArrayLiteral\",\"items\":[{}]", items_json)
+        }
+        ExpressionKind::Tuple(items) => {
+            let mut items_json = String::new();
+            join(&mut items_json, items, expression_json);
+            format!("\"kind\":\"Tuple\",\"items\":[{}]", items_json)
+        }
+        ExpressionKind::MapLiteral(pairs) => {
+            let mut pairs_json = String::new();
+            for (i, (key, value)) in pairs.iter().enumerate() {
+                if i > 0 {
+                    pairs_json.push(',');
+                }
+                pairs_json.push_str(&format!("{{\"key\":{},\"value\":{}}}", expression_json(key), expression_json(value)));
+            }
+            format!("\"kind\":\"MapLiteral\",\"pairs\":[{}]", pairs_json)
+        }
+        ExpressionKind::StructInit { name, fields } => {
+            let mut fields_json = String::new();
+            for (i, (fname, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    fields_json.push(',');
+                }
+                fields_json.push_str(&format!("{{\"name\":{},\"value\":{}}}", escape(fname), expression_json(value)));
+            }
+            format!("\"kind\":\"StructInit\",\"name\":{},\"fields\":[{}]", escape(name), fields_json)
+        }
+        ExpressionKind::Lambda { params, return_type, body } => {
+            let mut params_json = String::new();
+            join(&mut params_json, params, param_json);
+            let mut body_json = String::new();
+            join(&mut body_json, body, statement_json);
+            format!(
+                "\"kind\":\"Lambda\",\"params\":[{}],\"returnType\":{},\"body\":[{}]",
+                params_json,
+                type_json(return_type),
+                body_json
+            )
+        }
+        ExpressionKind::Try(inner) => format!("\"kind\":\"Try\",\"inner\":{}", expression_json(inner)),
+        ExpressionKind::Match { subject, arms } => {
+            let mut arms_json = String::new();
+            join(&mut arms_json, arms, match_arm_json);
+            format!("\"kind\":\"Match\",\"subject\":{},\"arms\":[{}]", expression_json(subject), arms_json)
+        }
+        ExpressionKind::Error => "\"kind\":\"Error\"".to_string(),
+    };
+    format!("{{{},\"position\":{}}}", body, position_json(expr.position))
+}
+
+fn function_json(function: &Function) -> String {
+    let mut params_json = String::new();
+    join(&mut params_json, &function.params, param_json);
+    let mut body_json = String::new();
+    join(&mut body_json, &function.body, statement_json);
+    let mut type_params_json = String::new();
+    join(&mut type_params_json, &function.type_params, type_param_json);
+    format!(
+        "{{\"name\":{},\"typeParams\":[{}],\"params\":[{}],\"returnType\":{},\"body\":[{}],\"isPub\":{},\"position\":{},\"doc\":{},\"receiver\":{},\"receiverName\":{},\"externInfo\":{},\"deprecated\":{}}}",
+        escape(&function.name),
+        type_params_json,
+        params_json,
+        type_json(&function.return_type),
+        body_json,
+        function.is_pub,
+        position_json(function.position),
+        optional_string_json(&function.doc),
+        optional_string_json(&function.receiver),
+        optional_string_json(&function.receiver_name),
+        extern_info_json(&function.extern_info),
+        optional_string_json(&function.deprecated)
+    )
+}
+
+fn extern_info_json(extern_info: &Option<crate::ast::ExternInfo>) -> String {
+    match extern_info {
+        Some(info) => format!(
+            "{{\"abi\":{},\"library\":{},\"symbol\":{}}}",
+            escape(&info.abi),
+            optional_string_json(&info.library),
+            optional_string_json(&info.symbol)
+        ),
+        None => "null".to_string(),
+    }
+}
+
+fn struct_json(strukt: &Struct) -> String {
+    let mut fields_json = String::new();
+    join(&mut fields_json, &strukt.fields, field_json);
+    let mut type_params_json = String::new();
+    join(&mut type_params_json, &strukt.type_params, type_param_json);
+    format!(
+        "{{\"name\":{},\"typeParams\":[{}],\"fields\":[{}],\"isPub\":{},\"position\":{},\"doc\":{}}}",
+        escape(&strukt.name),
+        type_params_json,
+        fields_json,
+        strukt.is_pub,
+        position_json(strukt.position),
+        optional_string_json(&strukt.doc)
+    )
+}
+
+fn enum_json(enm: &Enum) -> String {
+    let mut variants_json = String::new();
+    for (i, variant) in enm.variants.iter().enumerate() {
+        if i > 0 {
+            variants_json.push(',');
+        }
+        let value_json = match &variant.value {
+            Some(value) => expression_json(value),
+            None => "null".to_string(),
+        };
+        variants_json.push_str(&format!("{{\"name\":{},\"value\":{}}}", escape(&variant.name), value_json));
+    }
+    let underlying_type_json = match &enm.underlying_type {
+        Some(ty) => type_json(ty),
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"name\":{},\"variants\":[{}],\"isPub\":{},\"position\":{},\"doc\":{},\"underlyingType\":{}}}",
+        escape(&enm.name),
+        variants_json,
+        enm.is_pub,
+        position_json(enm.position),
+        optional_string_json(&enm.doc),
+        underlying_type_json
+    )
+}
+
+fn interface_method_json(method: &InterfaceMethodSig) -> String {
+    let mut params_json = String::new();
+    join(&mut params_json, &method.params, param_json);
+    let default_body_json = match &method.default_body {
+        Some(body) => {
+            let mut body_json = String::new();
+            join(&mut body_json, body, statement_json);
+            format!("[{}]", body_json)
+        }
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"name\":{},\"params\":[{}],\"returnType\":{},\"position\":{},\"defaultBody\":{}}}",
+        escape(&method.name),
+        params_json,
+        type_json(&method.return_type),
+        position_json(method.position),
+        default_body_json
+    )
+}
+
+fn interface_json(interface: &Interface) -> String {
+    let mut methods_json = String::new();
+    join(&mut methods_json, &interface.methods, interface_method_json);
+    format!(
+        "{{\"name\":{},\"methods\":[{}],\"isPub\":{},\"position\":{}}}",
+        escape(&interface.name),
+        methods_json,
+        interface.is_pub,
+        position_json(interface.position)
+    )
+}
+
+fn impl_json(imp: &Impl) -> String {
+    let mut methods_json = String::new();
+    join(&mut methods_json, &imp.methods, |m| function_json(m));
+    format!(
+        "{{\"interfaceName\":{},\"targetName\":{},\"methods\":[{}],\"position\":{}}}",
+        escape(&imp.interface_name),
+        escape(&imp.target_name),
+        methods_json,
+        position_json(imp.position)
+    )
+}
+
+fn module_block_json(block: &ModuleBlock) -> String {
+    let mut statements_json = String::new();
+    join(&mut statements_json, &block.statements, statement_json);
+    format!(
+        "{{\"name\":{},\"statements\":[{}],\"position\":{},\"doc\":{}}}",
+        escape(&block.name),
+        statements_json,
+        position_json(block.position),
+        optional_string_json(&block.doc)
+    )
+}
+
+fn import_json(import: &Import) -> String {
+    let mut path_json = String::new();
+    for (i, segment) in import.path.iter().enumerate() {
+        if i > 0 {
+            path_json.push(',');
+        }
+        path_json.push_str(&escape(segment));
+    }
+    let alias_json = match &import.alias {
+        Some(alias) => escape(alias),
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"path\":[{}],\"alias\":{},\"position\":{}}}",
+        path_json,
+        alias_json,
+        position_json(import.position)
+    )
+}
+
+fn export_json(export: &Export) -> String {
+    let mut names_json = String::new();
+    for (i, name) in export.names.iter().enumerate() {
+        if i > 0 {
+            names_json.push(',');
+        }
+        names_json.push_str(&escape(name));
+    }
+    format!("{{\"names\":[{}],\"position\":{}}}", names_json, position_json(export.position))
+}
+
+fn statement_json(statement: &Statement) -> String {
+    let body = match &statement.kind {
+        StatementKind::Expression(expr) => format!("\"kind\":\"Expression\",\"expr\":{}", expression_json(expr)),
+        StatementKind::Let { name, ty, value, is_const } => {
+            let ty_json = match ty {
+                Some(ty) => type_json(ty),
+                None => "null".to_string(),
+            };
+            let value_json = match value {
+                Some(value) => expression_json(value),
+                None => "null".to_string(),
+            };
+            format!(
+                "\"kind\":\"Let\",\"name\":{},\"type\":{},\"value\":{},\"isConst\":{}",
+                escape(name),
+                ty_json,
+                value_json,
+                is_const
+            )
+        }
+        StatementKind::Return(value) => {
+            let value_json = match value {
+                Some(value) => expression_json(value),
+                None => "null".to_string(),
+            };
+            format!("\"kind\":\"Return\",\"value\":{}", value_json)
+        }
+        StatementKind::If { condition, then_branch, else_branch } => {
+            let mut then_json = String::new();
+            join(&mut then_json, then_branch, statement_json);
+            let else_json = match else_branch {
+                Some(else_branch) => {
+                    let mut s = String::new();
+                    join(&mut s, else_branch, statement_json);
+                    format!("[{}]", s)
+                }
+                None => "null".to_string(),
+            };
+            format!(
+                "\"kind\":\"If\",\"condition\":{},\"then\":[{}],\"else\":{}",
+                expression_json(condition),
+                then_json,
+                else_json
+            )
+        }
+        StatementKind::While { condition, body } => {
+            let mut body_json = String::new();
+            join(&mut body_json, body, statement_json);
+            format!("\"kind\":\"While\",\"condition\":{},\"body\":[{}]", expression_json(condition), body_json)
+        }
+        StatementKind::For { init, condition, update, body } => {
+            let init_json = match init {
+                Some(init) => statement_json(init),
+                None => "null".to_string(),
+            };
+            let condition_json = match condition {
+                Some(condition) => expression_json(condition),
+                None => "null".to_string(),
+            };
+            let update_json = match update {
+                Some(update) => expression_json(update),
+                None => "null".to_string(),
+            };
+            let mut body_json = String::new();
+            join(&mut body_json, body, statement_json);
+            format!(
+                "\"kind\":\"For\",\"init\":{},\"condition\":{},\"update\":{},\"body\":[{}]",
+                init_json,
+                condition_json,
+                update_json,
+                body_json
+            )
+        }
+        StatementKind::ForEach { variable, iterable, body } => {
+            let mut body_json = String::new();
+            join(&mut body_json, body, statement_json);
+            format!(
+                "\"kind\":\"ForEach\",\"variable\":{},\"iterable\":{},\"body\":[{}]",
+                escape(variable),
+                expression_json(iterable),
+                body_json
+            )
+        }
+        StatementKind::Block(statements) => {
+            let mut statements_json = String::new();
+            join(&mut statements_json, statements, statement_json);
+            format!("\"kind\":\"Block\",\"statements\":[{}]", statements_json)
+        }
+        StatementKind::FunctionDecl(function) => format!("\"kind\":\"FunctionDecl\",\"function\":{}", function_json(function)),
+        StatementKind::StructDecl(strukt) => format!("\"kind\":\"StructDecl\",\"struct\":{}", struct_json(strukt)),
+        StatementKind::EnumDecl(enm) => format!("\"kind\":\"EnumDecl\",\"enum\":{}", enum_json(enm)),
+        StatementKind::InterfaceDecl(interface) => {
+            format!("\"kind\":\"InterfaceDecl\",\"interface\":{}", interface_json(interface))
+        }
+        StatementKind::ImplBlock(imp) => format!("\"kind\":\"ImplBlock\",\"impl\":{}", impl_json(imp)),
+        StatementKind::ModuleDecl(block) => format!("\"kind\":\"ModuleDecl\",\"module\":{}", module_block_json(block)),
+        StatementKind::Import(import) => format!("\"kind\":\"Import\",\"import\":{}", import_json(import)),
+        StatementKind::Export(export) => format!("\"kind\":\"Export\",\"export\":{}", export_json(export)),
+        StatementKind::Match { subject, arms } => {
+            let mut arms_json = String::new();
+            join(&mut arms_json, arms, match_arm_json);
+            format!("\"kind\":\"Match\",\"subject\":{},\"arms\":[{}]", expression_json(subject), arms_json)
+        }
+        StatementKind::Break => "\"kind\":\"Break\"".to_string(),
+        StatementKind::Continue => "\"kind\":\"Continue\"".to_string(),
+        StatementKind::Error => "\"kind\":\"Error\"".to_string(),
+    };
+    format!("{{{},\"position\":{}}}", body, position_json(statement.position))
+}
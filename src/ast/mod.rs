@@ -0,0 +1,582 @@
+//! The abstract syntax tree produced by the parser and consumed by the
+//! semantic phases.
+
+use crate::common::Position;
+use std::sync::Arc;
+
+#[cfg(debug_assertions)]
+pub mod validate;
+pub mod json;
+pub mod printer;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserTypeKind {
+    Struct,
+    Enum,
+    /// The name bound by `import ... as name` (or the last path segment,
+    /// if no alias was given): a namespace value only ever used on the
+    /// left of a [`ExpressionKind::Get`], never a value in its own right.
+    Module,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeKind {
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float32,
+    Float64,
+    Bool,
+    String,
+    Char,
+    Void,
+    /// `T[]` (`None`, a dynamically-sized array) or `T[N]` (`Some(N)`, a
+    /// fixed-size array whose length `N` was a constant integer
+    /// expression the parser could evaluate on the spot -- see
+    /// [`crate::parser::Parser::type_`]. A fixed size is only ever
+    /// checked against an array *literal*'s length
+    /// ([`crate::semantic::Typechecker::check_statement`]'s `Let` arm);
+    /// nothing tracks or bounds-checks it through an array built up any
+    /// other way (a variable, a function return, `Array.push`, ...).
+    Array(Box<Type>, Option<usize>),
+    /// A named struct/enum/module reference, plus any `<...>` type
+    /// arguments written at the use site (`List<Int32>` carries
+    /// `vec![Int32]`; a bare, non-generic name like `Point` carries an
+    /// empty vec). See [`Struct::type_params`] for the same
+    /// arity-only-checking caveat that already applies to
+    /// [`Function::type_params`] -- there's no substitution step, so a
+    /// field typed `T` on a `List<Int32>` still reads back as `T`.
+    UserType(String, UserTypeKind, Vec<Type>),
+    Function(Vec<Type>, Box<Type>),
+    /// `Result<Ok, Err>`: a fallible value, propagated with the postfix
+    /// `?` operator ([`ExpressionKind::Try`]).
+    Result(Box<Type>, Box<Type>),
+    /// `(T1, T2, ...)`: a fixed-size heterogeneous grouping, accessed with
+    /// `.0`, `.1`, ... via [`ExpressionKind::Get`].
+    Tuple(Vec<Type>),
+    /// `Map<K, V>`: a dictionary keyed by `K`, indexed like an array with
+    /// [`ExpressionKind::Index`].
+    Map(Box<Type>, Box<Type>),
+    /// Placeholder produced when a type could not be resolved; downstream
+    /// checks silently skip anything touching it so one bad annotation
+    /// doesn't cascade into a wall of diagnostics.
+    Error,
+}
+
+impl TypeKind {
+    /// Numeric widening rank used to decide which side of a binary
+    /// expression a mixed-numeric operation should be promoted to. Signed
+    /// and unsigned types of the same width share a rank -- this doesn't
+    /// try to model signedness, just size, the same simplification the
+    /// original `Int32`/`Int64`/`Float32`/`Float64` ranking already made.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            TypeKind::Bool => 0,
+            TypeKind::Int8 | TypeKind::UInt8 => 1,
+            TypeKind::Int16 | TypeKind::UInt16 => 2,
+            TypeKind::Int32 | TypeKind::UInt32 => 3,
+            TypeKind::Int64 | TypeKind::UInt64 => 4,
+            TypeKind::Float32 => 5,
+            TypeKind::Float64 => 6,
+            _ => 0,
+        }
+    }
+
+    /// True once a value has been poisoned by an earlier failure (an
+    /// undefined symbol, a bad annotation, ...). Checks that consume a
+    /// poisoned type should silently skip rather than emit a second,
+    /// derivative diagnostic.
+    pub fn is_error(&self) -> bool {
+        matches!(self, TypeKind::Error)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            TypeKind::Int8
+                | TypeKind::Int16
+                | TypeKind::Int32
+                | TypeKind::Int64
+                | TypeKind::UInt8
+                | TypeKind::UInt16
+                | TypeKind::UInt32
+                | TypeKind::UInt64
+                | TypeKind::Float32
+                | TypeKind::Float64
+        )
+    }
+
+    /// True for the eight fixed-width integer kinds `is_numeric` also
+    /// covers, excluding `Float32`/`Float64` -- for checks (bit-shift
+    /// operands) that only make sense on whole numbers.
+    pub fn is_integer(&self) -> bool {
+        self.is_numeric() && !matches!(self, TypeKind::Float32 | TypeKind::Float64)
+    }
+
+    /// True for the unsigned fixed-width integer types -- callers that
+    /// need to reject a negative literal or warn about a signedness
+    /// boundary (e.g. an eventual lossy-conversion lint) check this
+    /// instead of matching all four variants themselves.
+    pub fn is_unsigned(&self) -> bool {
+        matches!(
+            self,
+            TypeKind::UInt8 | TypeKind::UInt16 | TypeKind::UInt32 | TypeKind::UInt64
+        )
+    }
+
+    /// Whether a value of this type has a well-defined layout on the
+    /// other side of an `extern` boundary: the fixed-width numeric types,
+    /// `Bool`, `Char` and `Void` (return position only). Everything else
+    /// -- `String`, `Array`, structs/enums, closures, `Result`, tuples,
+    /// `Map` -- is a managed representation this compiler doesn't define a
+    /// C-compatible layout for, so it can't appear in an `extern`
+    /// function's signature.
+    pub fn is_ffi_safe(&self) -> bool {
+        self.is_numeric() || matches!(self, TypeKind::Bool | TypeKind::Char | TypeKind::Void)
+    }
+
+    /// Whether a value of this type can be converted to `to` with an `as`
+    /// expression: any numeric type to any other numeric type (widening or
+    /// narrowing), or a type to itself. Two non-numeric types are never
+    /// castable -- this compiler has no notion of a user-defined
+    /// conversion.
+    pub fn castable(&self, to: &TypeKind) -> bool {
+        self == to || (self.is_numeric() && to.is_numeric())
+    }
+
+    /// Whether converting `self` to `to` is a lossless implicit widening:
+    /// same signedness (or a float destination) and no loss of range.
+    /// Anything [`Self::castable`] but not widening -- narrowing a
+    /// precision, converting float to int, or crossing a signedness
+    /// boundary at the same width -- is only safe behind an explicit `as`,
+    /// even though [`crate::semantic::unify`] still resolves it silently
+    /// today by picking the higher-[`Self::precedence`] side; that gap is
+    /// exactly what [`crate::semantic::lint::Lint::LossyConversion`] warns
+    /// about.
+    pub fn is_widening(&self, to: &TypeKind) -> bool {
+        if self == to {
+            return true;
+        }
+        if !self.is_numeric() || !to.is_numeric() {
+            return false;
+        }
+        if to.precedence() < self.precedence() {
+            return false;
+        }
+        matches!(to, TypeKind::Float32 | TypeKind::Float64) || self.is_unsigned() == to.is_unsigned()
+    }
+
+    pub fn from_string(name: &str) -> Self {
+        match name {
+            "Int8" => TypeKind::Int8,
+            "Int16" => TypeKind::Int16,
+            "Int32" => TypeKind::Int32,
+            "Int64" => TypeKind::Int64,
+            "UInt8" => TypeKind::UInt8,
+            "UInt16" => TypeKind::UInt16,
+            "UInt32" => TypeKind::UInt32,
+            "UInt64" => TypeKind::UInt64,
+            "Float32" => TypeKind::Float32,
+            "Float64" => TypeKind::Float64,
+            "Bool" => TypeKind::Bool,
+            "String" => TypeKind::String,
+            "Char" => TypeKind::Char,
+            "Void" => TypeKind::Void,
+            other => TypeKind::UserType(other.to_string(), UserTypeKind::Unknown, Vec::new()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Type {
+    pub kind: TypeKind,
+    pub position: Position,
+}
+
+impl PartialEq for Type {
+    /// Two types are equal if their `kind`s match, regardless of where
+    /// each was written. Comparing positions here would make every
+    /// container type (`Array`, `Tuple`, `Function`, `Result`, ...) fail to
+    /// unify with itself as soon as its element types came from different
+    /// source locations.
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl Type {
+    pub fn new(kind: TypeKind, position: Position) -> Self {
+        Self { kind, position }
+    }
+
+    pub fn error(position: Position) -> Self {
+        Self {
+            kind: TypeKind::Error,
+            position,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    /// Stored as `i128` -- wider than any integer type the language has
+    /// (`Int64` is the widest) -- so a literal that overflows every real
+    /// target type still parses into an exact value instead of wrapping,
+    /// and [`crate::semantic::Typechecker::check_int_range`] can report
+    /// `E210` instead of silently compiling a truncated number. The
+    /// second field is the type an `i32`/`u8`/... suffix on the source
+    /// literal named explicitly (e.g. `10i64` carries `Some(Int64)`);
+    /// `None` when it had none, leaving the type to be inferred or read
+    /// off a declared annotation the usual way.
+    Int(i128, Option<TypeKind>),
+    /// See [`LiteralValue::Int`] -- `2.5f32` carries `Some(Float32)`.
+    Float(f64, Option<TypeKind>),
+    String(String),
+    Char(char),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionKind {
+    Literal(LiteralValue),
+    Identifier(String),
+    Binary {
+        left: Box<Expression>,
+        op: String,
+        right: Box<Expression>,
+    },
+    Unary {
+        op: String,
+        operand: Box<Expression>,
+    },
+    Postfix {
+        op: String,
+        operand: Box<Expression>,
+    },
+    Grouping(Box<Expression>),
+    Call {
+        callee: Box<Expression>,
+        args: Vec<Expression>,
+    },
+    Get {
+        object: Box<Expression>,
+        name: String,
+    },
+    Index {
+        object: Box<Expression>,
+        index: Box<Expression>,
+    },
+    Assignment {
+        target: Box<Expression>,
+        op: String,
+        value: Box<Expression>,
+    },
+    ArrayLiteral(Vec<Expression>),
+    Tuple(Vec<Expression>),
+    MapLiteral(Vec<(Expression, Expression)>),
+    StructInit {
+        name: String,
+        fields: Vec<(String, Expression)>,
+    },
+    Lambda {
+        params: Vec<Param>,
+        return_type: Type,
+        body: Vec<Statement>,
+    },
+    /// Postfix `expr?`: unwraps a `Result`'s ok value, or propagates the
+    /// error out of the enclosing function.
+    Try(Box<Expression>),
+    /// `match (subject) { ... }` used as an expression: each arm's last
+    /// statement, if it's a bare expression, supplies that arm's value.
+    Match {
+        subject: Box<Expression>,
+        arms: Vec<MatchArm>,
+    },
+    /// Produced by parser error recovery. Never appears in a tree for which
+    /// `had_error` is false.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub position: Position,
+}
+
+impl Expression {
+    pub fn new(kind: ExpressionKind, position: Position) -> Self {
+        Self { kind, position }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub ty: Type,
+}
+
+/// One name in a function's `<T: Bound1 + Bound2, U>` type-parameter
+/// list. `bounds` names the interfaces `T` is constrained to implement --
+/// empty for an unconstrained parameter (`<T>`).
+///
+/// This compiler has no generic instantiation mechanism (no monomorphization,
+/// no call-site type arguments, no inference) to actually check a bound
+/// against the concrete type a call ends up using -- see
+/// [`crate::semantic::Resolver::check_type_params`], which only validates
+/// that each named bound is itself a real interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParam {
+    pub name: String,
+    pub bounds: Vec<String>,
+    pub position: Position,
+}
+
+/// Native-linkage metadata for a function declared `extern "ABI"` instead
+/// of with a Matcha body -- `body` is always empty for one of these; the
+/// real implementation lives in a native library, loaded and called at
+/// the point named by `library`/`symbol` instead of interpreted or
+/// compiled from statements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternInfo {
+    /// The calling convention, e.g. `"C"`. Not validated against a fixed
+    /// set -- there's only ever going to be one ABI this compiler can
+    /// actually generate calls for, but the string is kept around
+    /// verbatim so it round-trips through the AST printer/JSON dump.
+    pub abi: String,
+    /// The library to load the symbol from (`from "libc.so.6"`), if
+    /// named explicitly. `None` means "resolved by the linker/loader
+    /// without an explicit path" (e.g. already linked into the process).
+    pub library: Option<String>,
+    /// The symbol's real name in the library (`as "read"`), if it
+    /// differs from `name`. `None` means the two are the same.
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub return_type: Type,
+    pub body: Vec<Statement>,
+    pub is_pub: bool,
+    pub position: Position,
+    /// Text of the `///` doc comment immediately preceding this
+    /// declaration, if any, with the leading `///` (and one following
+    /// space) stripped from each line. `None` if undocumented.
+    pub doc: Option<String>,
+    /// The struct this is a static/associated function of, for a
+    /// declaration written `func (Point) new(...): Point { ... }`.
+    /// `None` for an ordinary top-level function. A receiver function is
+    /// kept out of the plain function namespace entirely -- it's only
+    /// callable as `Point.new(...)`, never bare `new(...)`.
+    pub receiver: Option<String>,
+    /// The local name a `func (Type name) method(...)` receiver clause
+    /// binds its receiver value to, for an *extension* method written
+    /// `func (Int32 n) abs(): Int32 { ... n ... }` -- as opposed to the
+    /// type-only static-method form (`func (Point) new(...)`), which has
+    /// no implicit receiver value and leaves this `None`. Always `None`
+    /// when `receiver` itself is `None`.
+    pub receiver_name: Option<String>,
+    /// `Some` for a declaration written `extern "ABI" func ...(...);` --
+    /// `None` for an ordinary function with a Matcha body.
+    pub extern_info: Option<ExternInfo>,
+    /// `Some` for a declaration written `@deprecated` (empty string) or
+    /// `@deprecated("message")` (the message) immediately above it --
+    /// `None` if the declaration carries no such attribute. Consulted by
+    /// [`crate::semantic::lint`] to warn at each call site.
+    pub deprecated: Option<String>,
+    /// `<T: Bound, U>` written between the function name and its
+    /// parameter list. Empty for an ordinary, non-generic function.
+    pub type_params: Vec<TypeParam>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub ty: Type,
+    pub is_pub: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub is_pub: bool,
+    pub position: Position,
+    /// See [`Function::doc`].
+    pub doc: Option<String>,
+    /// `<T: Bound, U>` written between the struct name and its body, as
+    /// for [`Function::type_params`]. A reference to this struct
+    /// (`List<Int32>`) is checked for arity against this list --
+    /// see [`crate::semantic::Resolver::check_type`] -- but, same as
+    /// function generics, there's no monomorphization: a field typed `T`
+    /// is never substituted with the concrete argument a use site named.
+    pub type_params: Vec<TypeParam>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    pub name: String,
+    pub value: Option<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub name: String,
+    pub variants: Vec<EnumVariant>,
+    pub is_pub: bool,
+    pub position: Position,
+    /// See [`Function::doc`].
+    pub doc: Option<String>,
+    /// The integer type backing each variant's discriminant, written
+    /// `enum Name: Int8 { ... }`. Defaults to `Int32` when omitted, the
+    /// same as a bare integer literal's inferred type.
+    pub underlying_type: Option<Type>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceMethodSig {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub return_type: Type,
+    pub position: Position,
+    /// `Some` for a method written with a `{ ... }` body right in the
+    /// interface instead of a bare `;` -- the default an implementing
+    /// `impl` inherits when it doesn't provide its own override. `None`
+    /// for an ordinary signature-only method, which every `impl` must
+    /// supply itself.
+    pub default_body: Option<Vec<Statement>>,
+}
+
+/// `module Name { ... }`: a nested namespace for the functions, structs,
+/// enums and (further nested) modules it declares, addressable from
+/// outside as `Name.member` and, for something declared inside another
+/// module, `Outer.Name.member`. Unlike [`Import`], this names no
+/// separate file -- it's a grouping within the one it's written in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleBlock {
+    pub name: String,
+    pub statements: Vec<Statement>,
+    pub position: Position,
+    /// See [`Function::doc`].
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub name: String,
+    pub methods: Vec<InterfaceMethodSig>,
+    pub is_pub: bool,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impl {
+    pub interface_name: String,
+    pub target_name: String,
+    pub methods: Vec<Arc<Function>>,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub path: Vec<String>,
+    pub alias: Option<String>,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Export {
+    pub names: Vec<String>,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Wildcard,
+    Literal(LiteralValue),
+    /// A bare name: either a catch-all binding, or (once resolved) an
+    /// enum variant name.
+    Identifier(String),
+    /// `EnumName.Variant`.
+    EnumVariant { enum_name: String, variant: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Vec<Statement>,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    Expression(Expression),
+    Let {
+        name: String,
+        ty: Option<Type>,
+        value: Option<Expression>,
+        is_const: bool,
+    },
+    Return(Option<Expression>),
+    If {
+        condition: Expression,
+        then_branch: Vec<Statement>,
+        else_branch: Option<Vec<Statement>>,
+    },
+    While {
+        condition: Expression,
+        body: Vec<Statement>,
+    },
+    For {
+        init: Option<Box<Statement>>,
+        condition: Option<Expression>,
+        update: Option<Expression>,
+        body: Vec<Statement>,
+    },
+    ForEach {
+        variable: String,
+        iterable: Expression,
+        body: Vec<Statement>,
+    },
+    Block(Vec<Statement>),
+    FunctionDecl(Arc<Function>),
+    StructDecl(Arc<Struct>),
+    EnumDecl(Arc<Enum>),
+    InterfaceDecl(Arc<Interface>),
+    ImplBlock(Arc<Impl>),
+    ModuleDecl(Arc<ModuleBlock>),
+    Import(Import),
+    Export(Export),
+    Match {
+        subject: Expression,
+        arms: Vec<MatchArm>,
+    },
+    Break,
+    Continue,
+    /// Produced by parser error recovery.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub position: Position,
+}
+
+impl Statement {
+    pub fn new(kind: StatementKind, position: Position) -> Self {
+        Self { kind, position }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Module {
+    pub statements: Vec<Statement>,
+}
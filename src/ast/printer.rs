@@ -0,0 +1,520 @@
+//! Renders an AST back into syntactically valid Matcha source, the
+//! inverse of [`crate::parser::Parser`]. Useful for desugaring debugging
+//! (print the tree after a lowering pass ran over it), for a future
+//! formatter, and for round-tripping a parse (`parse -> print -> parse`)
+//! to sanity-check the parser and printer agree on what a tree means.
+//!
+//! This is not a formatter: it doesn't preserve blank lines, comments, or
+//! the original line-wrapping, and it always parenthesizes binary/unary
+//! operands rather than tracking precedence to omit redundant parens. The
+//! goal is a tree that reparses to the same shape, not pretty output.
+
+use super::{
+    Enum, Expression, ExpressionKind, Field, Function, Impl, Import, Interface,
+    InterfaceMethodSig, LiteralValue, MatchArm, Module, ModuleBlock, Param, Pattern, Statement,
+    StatementKind, Struct, Type, TypeKind, TypeParam, UserTypeKind,
+};
+
+pub fn print(module: &Module) -> String {
+    let mut out = String::new();
+    for statement in &module.statements {
+        print_statement(&mut out, statement, 0);
+    }
+    out
+}
+
+fn indent(out: &mut String, level: usize) {
+    out.push_str(&"    ".repeat(level));
+}
+
+fn print_block(out: &mut String, body: &[Statement], level: usize) {
+    out.push_str("{\n");
+    for statement in body {
+        print_statement(out, statement, level + 1);
+    }
+    indent(out, level);
+    out.push('}');
+}
+
+fn print_doc(out: &mut String, doc: &Option<String>, level: usize) {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            indent(out, level);
+            out.push_str("/// ");
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+}
+
+fn print_type(ty: &Type) -> String {
+    match &ty.kind {
+        TypeKind::Int8 => "Int8".to_string(),
+        TypeKind::Int16 => "Int16".to_string(),
+        TypeKind::Int32 => "Int32".to_string(),
+        TypeKind::Int64 => "Int64".to_string(),
+        TypeKind::UInt8 => "UInt8".to_string(),
+        TypeKind::UInt16 => "UInt16".to_string(),
+        TypeKind::UInt32 => "UInt32".to_string(),
+        TypeKind::UInt64 => "UInt64".to_string(),
+        TypeKind::Float32 => "Float32".to_string(),
+        TypeKind::Float64 => "Float64".to_string(),
+        TypeKind::Bool => "Bool".to_string(),
+        TypeKind::String => "String".to_string(),
+        TypeKind::Char => "Char".to_string(),
+        TypeKind::Void => "Void".to_string(),
+        TypeKind::Error => "Void".to_string(),
+        TypeKind::Array(inner, None) => format!("{}[]", print_type(inner)),
+        TypeKind::Array(inner, Some(size)) => format!("{}[{}]", print_type(inner), size),
+        TypeKind::UserType(name, UserTypeKind::Module, _) => name.clone(),
+        TypeKind::UserType(name, _, args) if args.is_empty() => name.clone(),
+        TypeKind::UserType(name, _, args) => {
+            format!("{}<{}>", name, args.iter().map(print_type).collect::<Vec<_>>().join(", "))
+        }
+        TypeKind::Result(ok, err) => format!("Result<{}, {}>", print_type(ok), print_type(err)),
+        TypeKind::Map(key, value) => format!("Map<{}, {}>", print_type(key), print_type(value)),
+        TypeKind::Tuple(items) => {
+            format!("({})", items.iter().map(print_type).collect::<Vec<_>>().join(", "))
+        }
+        // No literal syntax parses to this today (it's only ever produced
+        // internally); `func(...)`  mirrors the lambda/function header
+        // syntax closely enough to round-trip through `type_()` unchanged
+        // if that ever changes.
+        TypeKind::Function(params, ret) => {
+            format!(
+                "func({}): {}",
+                params.iter().map(print_type).collect::<Vec<_>>().join(", "),
+                print_type(ret)
+            )
+        }
+    }
+}
+
+fn print_params(params: &[Param]) -> String {
+    params
+        .iter()
+        .map(|param| format!("{}: {}", param.name, print_type(&param.ty)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `<T: Bound1 + Bound2, U>`, or an empty string if `params` is empty.
+fn print_type_params(params: &[TypeParam]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let rendered: Vec<String> = params
+        .iter()
+        .map(|param| {
+            if param.bounds.is_empty() {
+                param.name.clone()
+            } else {
+                format!("{}: {}", param.name, param.bounds.join(" + "))
+            }
+        })
+        .collect();
+    format!("<{}>", rendered.join(", "))
+}
+
+fn print_function(out: &mut String, function: &Function, level: usize, is_lambda: bool) {
+    if !is_lambda {
+        print_doc(out, &function.doc, level);
+        if let Some(message) = &function.deprecated {
+            indent(out, level);
+            if message.is_empty() {
+                out.push_str("@deprecated\n");
+            } else {
+                out.push_str(&format!("@deprecated(\"{}\")\n", message));
+            }
+        }
+        indent(out, level);
+        if function.is_pub {
+            out.push_str("pub ");
+        }
+        if let Some(extern_info) = &function.extern_info {
+            out.push_str(&format!("extern \"{}\" ", extern_info.abi));
+        }
+    }
+    out.push_str("func ");
+    if !is_lambda {
+        if let Some(receiver) = &function.receiver {
+            out.push('(');
+            out.push_str(receiver);
+            if let Some(receiver_name) = &function.receiver_name {
+                out.push(' ');
+                out.push_str(receiver_name);
+            }
+            out.push_str(") ");
+        }
+        out.push_str(&function.name);
+    }
+    out.push_str(&print_type_params(&function.type_params));
+    out.push('(');
+    out.push_str(&print_params(&function.params));
+    out.push_str("): ");
+    out.push_str(&print_type(&function.return_type));
+    if let Some(extern_info) = &function.extern_info {
+        if let Some(library) = &extern_info.library {
+            out.push_str(&format!(" from \"{}\"", library));
+        }
+        if let Some(symbol) = &extern_info.symbol {
+            out.push_str(&format!(" as \"{}\"", symbol));
+        }
+        out.push(';');
+    } else {
+        out.push(' ');
+        print_block(out, &function.body, level);
+    }
+    if !is_lambda {
+        out.push('\n');
+    }
+}
+
+fn print_struct(out: &mut String, strukt: &Struct, level: usize) {
+    print_doc(out, &strukt.doc, level);
+    indent(out, level);
+    if strukt.is_pub {
+        out.push_str("pub ");
+    }
+    out.push_str(&format!("struct {}{} {{\n", strukt.name, print_type_params(&strukt.type_params)));
+    for field in &strukt.fields {
+        print_field(out, field, level + 1);
+    }
+    indent(out, level);
+    out.push_str("}\n");
+}
+
+fn print_field(out: &mut String, field: &Field, level: usize) {
+    indent(out, level);
+    if field.is_pub {
+        out.push_str("pub ");
+    }
+    out.push_str(&format!("{}: {},\n", field.name, print_type(&field.ty)));
+}
+
+fn print_enum(out: &mut String, enm: &Enum, level: usize) {
+    print_doc(out, &enm.doc, level);
+    indent(out, level);
+    if enm.is_pub {
+        out.push_str("pub ");
+    }
+    out.push_str(&format!("enum {}", enm.name));
+    if let Some(underlying_type) = &enm.underlying_type {
+        out.push_str(&format!(": {}", print_type(underlying_type)));
+    }
+    out.push_str(" {\n");
+    for variant in &enm.variants {
+        indent(out, level + 1);
+        out.push_str(&variant.name);
+        if let Some(value) = &variant.value {
+            out.push_str(" = ");
+            out.push_str(&print_expression(value));
+        }
+        out.push_str(",\n");
+    }
+    indent(out, level);
+    out.push_str("}\n");
+}
+
+fn print_interface(out: &mut String, interface: &Interface, level: usize) {
+    indent(out, level);
+    if interface.is_pub {
+        out.push_str("pub ");
+    }
+    out.push_str(&format!("interface {} {{\n", interface.name));
+    for method in &interface.methods {
+        print_interface_method(out, method, level + 1);
+    }
+    indent(out, level);
+    out.push_str("}\n");
+}
+
+fn print_interface_method(out: &mut String, method: &InterfaceMethodSig, level: usize) {
+    indent(out, level);
+    out.push_str(&format!(
+        "func {}({}): {}",
+        method.name,
+        print_params(&method.params),
+        print_type(&method.return_type)
+    ));
+    match &method.default_body {
+        Some(body) => {
+            out.push(' ');
+            print_block(out, body, level);
+            out.push('\n');
+        }
+        None => out.push_str(";\n"),
+    }
+}
+
+fn print_impl(out: &mut String, imp: &Impl, level: usize) {
+    indent(out, level);
+    out.push_str(&format!("impl {} for {} {{\n", imp.interface_name, imp.target_name));
+    for method in &imp.methods {
+        print_function(out, method, level + 1, false);
+    }
+    indent(out, level);
+    out.push_str("}\n");
+}
+
+fn print_module_block(out: &mut String, block: &ModuleBlock, level: usize) {
+    print_doc(out, &block.doc, level);
+    indent(out, level);
+    out.push_str(&format!("module {} {{\n", block.name));
+    for statement in &block.statements {
+        print_statement(out, statement, level + 1);
+    }
+    indent(out, level);
+    out.push_str("}\n");
+}
+
+fn print_import(out: &mut String, import: &Import, level: usize) {
+    indent(out, level);
+    out.push_str("import ");
+    out.push_str(&import.path.join("."));
+    if let Some(alias) = &import.alias {
+        out.push_str(" as ");
+        out.push_str(alias);
+    }
+    out.push_str(";\n");
+}
+
+fn print_statement(out: &mut String, statement: &Statement, level: usize) {
+    match &statement.kind {
+        StatementKind::Expression(expr) => {
+            indent(out, level);
+            out.push_str(&print_expression(expr));
+            out.push_str(";\n");
+        }
+        StatementKind::Let { name, ty, value, is_const } => {
+            indent(out, level);
+            out.push_str(if *is_const { "const " } else { "let " });
+            out.push_str(name);
+            if let Some(ty) = ty {
+                out.push_str(": ");
+                out.push_str(&print_type(ty));
+            }
+            if let Some(value) = value {
+                out.push_str(" = ");
+                out.push_str(&print_expression(value));
+            }
+            out.push_str(";\n");
+        }
+        StatementKind::Return(value) => {
+            indent(out, level);
+            out.push_str("return");
+            if let Some(value) = value {
+                out.push(' ');
+                out.push_str(&print_expression(value));
+            }
+            out.push_str(";\n");
+        }
+        StatementKind::If { condition, then_branch, else_branch } => {
+            indent(out, level);
+            out.push_str(&format!("if ({}) ", print_expression(condition)));
+            print_block(out, then_branch, level);
+            if let Some(else_branch) = else_branch {
+                out.push_str(" else ");
+                print_block(out, else_branch, level);
+            }
+            out.push('\n');
+        }
+        StatementKind::While { condition, body } => {
+            indent(out, level);
+            out.push_str(&format!("while ({}) ", print_expression(condition)));
+            print_block(out, body, level);
+            out.push('\n');
+        }
+        StatementKind::For { init, condition, update, body } => {
+            indent(out, level);
+            out.push_str("for (");
+            match init {
+                Some(init) => {
+                    let mut init_str = String::new();
+                    print_statement(&mut init_str, init, 0);
+                    out.push_str(init_str.trim_end_matches('\n'));
+                    out.push(' ');
+                }
+                None => out.push_str("; "),
+            }
+            if let Some(condition) = condition {
+                out.push_str(&print_expression(condition));
+            }
+            out.push_str("; ");
+            if let Some(update) = update {
+                out.push_str(&print_expression(update));
+            }
+            out.push_str(") ");
+            print_block(out, body, level);
+            out.push('\n');
+        }
+        StatementKind::ForEach { variable, iterable, body } => {
+            indent(out, level);
+            out.push_str(&format!("for ({} in {}) ", variable, print_expression(iterable)));
+            print_block(out, body, level);
+            out.push('\n');
+        }
+        StatementKind::Block(body) => {
+            indent(out, level);
+            print_block(out, body, level);
+            out.push('\n');
+        }
+        StatementKind::FunctionDecl(function) => print_function(out, function, level, false),
+        StatementKind::StructDecl(strukt) => print_struct(out, strukt, level),
+        StatementKind::EnumDecl(enm) => print_enum(out, enm, level),
+        StatementKind::InterfaceDecl(interface) => print_interface(out, interface, level),
+        StatementKind::ImplBlock(imp) => print_impl(out, imp, level),
+        StatementKind::ModuleDecl(block) => print_module_block(out, block, level),
+        StatementKind::Import(import) => print_import(out, import, level),
+        StatementKind::Export(export) => {
+            indent(out, level);
+            out.push_str(&format!("export {{ {} }};\n", export.names.join(", ")));
+        }
+        StatementKind::Match { subject, arms } => {
+            indent(out, level);
+            out.push_str(&format!("match ({}) ", print_expression(subject)));
+            print_match_arms(out, arms, level);
+            out.push('\n');
+        }
+        StatementKind::Break => {
+            indent(out, level);
+            out.push_str("break;\n");
+        }
+        StatementKind::Continue => {
+            indent(out, level);
+            out.push_str("continue;\n");
+        }
+        StatementKind::Error => {
+            indent(out, level);
+            out.push_str("/* <parse error> */\n");
+        }
+    }
+}
+
+fn print_match_arms(out: &mut String, arms: &[MatchArm], level: usize) {
+    out.push_str("{\n");
+    for arm in arms {
+        indent(out, level + 1);
+        out.push_str(&print_pattern(&arm.pattern));
+        out.push_str(" => ");
+        print_block(out, &arm.body, level + 1);
+        out.push_str(",\n");
+    }
+    indent(out, level);
+    out.push('}');
+}
+
+fn print_pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => "_".to_string(),
+        Pattern::Literal(value) => print_literal(value),
+        Pattern::Identifier(name) => name.clone(),
+        Pattern::EnumVariant { enum_name, variant } => format!("{}.{}", enum_name, variant),
+    }
+}
+
+fn print_literal(value: &LiteralValue) -> String {
+    match value {
+        LiteralValue::Int(n, suffix) => format!("{}{}", n, suffix_str(suffix)),
+        LiteralValue::Float(n, suffix) => format!("{}{}", n, suffix_str(suffix)),
+        LiteralValue::String(s) => format!("\"{}\"", escape(s)),
+        LiteralValue::Char(c) => format!("'{}'", escape(&c.to_string())),
+        LiteralValue::Bool(b) => b.to_string(),
+    }
+}
+
+/// The source-level suffix (`i64`, `u8`, `f32`, ...) that would re-parse
+/// into `suffix`, or `""` when there was none -- so a literal that named
+/// one prints back exactly as written instead of losing its explicit type.
+fn suffix_str(suffix: &Option<TypeKind>) -> &'static str {
+    match suffix {
+        Some(TypeKind::Int8) => "i8",
+        Some(TypeKind::Int16) => "i16",
+        Some(TypeKind::Int32) => "i32",
+        Some(TypeKind::Int64) => "i64",
+        Some(TypeKind::UInt8) => "u8",
+        Some(TypeKind::UInt16) => "u16",
+        Some(TypeKind::UInt32) => "u32",
+        Some(TypeKind::UInt64) => "u64",
+        Some(TypeKind::Float32) => "f32",
+        Some(TypeKind::Float64) => "f64",
+        _ => "",
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn print_expression(expr: &Expression) -> String {
+    match &expr.kind {
+        ExpressionKind::Literal(value) => print_literal(value),
+        ExpressionKind::Identifier(name) => name.clone(),
+        ExpressionKind::Binary { left, op, right } => {
+            format!("({} {} {})", print_expression(left), op, print_expression(right))
+        }
+        ExpressionKind::Unary { op, operand } => format!("({}{})", op, print_expression(operand)),
+        ExpressionKind::Postfix { op, operand } => format!("({}{})", print_expression(operand), op),
+        ExpressionKind::Grouping(inner) => format!("({})", print_expression(inner)),
+        ExpressionKind::Call { callee, args } => {
+            format!(
+                "{}({})",
+                print_expression(callee),
+                args.iter().map(print_expression).collect::<Vec<_>>().join(", ")
+            )
+        }
+        ExpressionKind::Get { object, name } => format!("{}.{}", print_expression(object), name),
+        ExpressionKind::Index { object, index } => {
+            format!("{}[{}]", print_expression(object), print_expression(index))
+        }
+        ExpressionKind::Assignment { target, op, value } => {
+            format!("{} {} {}", print_expression(target), op, print_expression(value))
+        }
+        ExpressionKind::ArrayLiteral(items) => {
+            format!("[{}]", items.iter().map(print_expression).collect::<Vec<_>>().join(", "))
+        }
+        ExpressionKind::Tuple(items) => {
+            format!("({})", items.iter().map(print_expression).collect::<Vec<_>>().join(", "))
+        }
+        ExpressionKind::MapLiteral(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", print_expression(key), print_expression(value)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        ExpressionKind::StructInit { name, fields } => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(fname, value)| format!("{}: {}", fname, print_expression(value)))
+                .collect();
+            format!("{} {{ {} }}", name, fields.join(", "))
+        }
+        ExpressionKind::Lambda { params, return_type, body } => {
+            let function = Function {
+                name: String::new(),
+                params: params.clone(),
+                return_type: return_type.clone(),
+                body: body.clone(),
+                is_pub: false,
+                position: expr.position,
+                doc: None,
+                receiver: None,
+                receiver_name: None,
+                extern_info: None,
+                deprecated: None,
+                type_params: Vec::new(),
+            };
+            let mut lambda_str = String::new();
+            print_function(&mut lambda_str, &function, 0, true);
+            lambda_str
+        }
+        ExpressionKind::Try(inner) => format!("{}?", print_expression(inner)),
+        ExpressionKind::Match { subject, arms } => {
+            let mut match_str = format!("match ({}) ", print_expression(subject));
+            print_match_arms(&mut match_str, arms, 0);
+            match_str
+        }
+        ExpressionKind::Error => "/* <parse error> */".to_string(),
+    }
+}
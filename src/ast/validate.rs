@@ -0,0 +1,178 @@
+//! Debug-only structural sanity checks run right after parsing.
+//!
+//! These are internal-compiler-error checks, not user diagnostics: if the
+//! parser reported no errors, the tree it produced should contain no
+//! `Error` nodes and every position should be well-formed. Tripping one of
+//! these means the parser itself has a bug.
+
+use super::{Expression, ExpressionKind, MatchArm, Module, Statement, StatementKind};
+
+pub fn validate(module: &Module, had_error: bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    for statement in &module.statements {
+        validate_statement(statement, had_error, &mut problems);
+    }
+    problems
+}
+
+fn validate_statement(statement: &Statement, had_error: bool, problems: &mut Vec<String>) {
+    if statement.position.line == 0 {
+        problems.push(format!(
+            "ICE: statement has an unset position: {:?}",
+            statement.kind
+        ));
+    }
+    match &statement.kind {
+        StatementKind::Error if !had_error => {
+            problems.push("ICE: StatementKind::Error present but parser reported no errors".into());
+        }
+        StatementKind::Expression(expr) => validate_expression(expr, had_error, problems),
+        StatementKind::Let { value: Some(value), .. } => {
+            validate_expression(value, had_error, problems);
+        }
+        StatementKind::Return(Some(expr)) => validate_expression(expr, had_error, problems),
+        StatementKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            validate_expression(condition, had_error, problems);
+            for s in then_branch {
+                validate_statement(s, had_error, problems);
+            }
+            if let Some(else_branch) = else_branch {
+                for s in else_branch {
+                    validate_statement(s, had_error, problems);
+                }
+            }
+        }
+        StatementKind::While { condition, body } => {
+            validate_expression(condition, had_error, problems);
+            for s in body {
+                validate_statement(s, had_error, problems);
+            }
+        }
+        StatementKind::For {
+            init,
+            condition,
+            update,
+            body,
+        } => {
+            if let Some(init) = init {
+                validate_statement(init, had_error, problems);
+            }
+            if let Some(condition) = condition {
+                validate_expression(condition, had_error, problems);
+            }
+            if let Some(update) = update {
+                validate_expression(update, had_error, problems);
+            }
+            for s in body {
+                validate_statement(s, had_error, problems);
+            }
+        }
+        StatementKind::ForEach { iterable, body, .. } => {
+            validate_expression(iterable, had_error, problems);
+            for s in body {
+                validate_statement(s, had_error, problems);
+            }
+        }
+        StatementKind::Block(stmts) => {
+            for s in stmts {
+                validate_statement(s, had_error, problems);
+            }
+        }
+        StatementKind::FunctionDecl(func) => {
+            for s in &func.body {
+                validate_statement(s, had_error, problems);
+            }
+        }
+        StatementKind::ImplBlock(imp) => {
+            for method in &imp.methods {
+                for s in &method.body {
+                    validate_statement(s, had_error, problems);
+                }
+            }
+        }
+        StatementKind::Match { subject, arms } => {
+            validate_expression(subject, had_error, problems);
+            validate_arms(arms, had_error, problems);
+        }
+        _ => {}
+    }
+}
+
+fn validate_expression(expr: &Expression, had_error: bool, problems: &mut Vec<String>) {
+    if expr.position.line == 0 {
+        problems.push(format!(
+            "ICE: expression has an unset position: {:?}",
+            expr.kind
+        ));
+    }
+    match &expr.kind {
+        ExpressionKind::Error if !had_error => {
+            problems.push("ICE: ExpressionKind::Error present but parser reported no errors".into());
+        }
+        ExpressionKind::Identifier(name) if name.is_empty() => {
+            problems.push("ICE: empty identifier name".into());
+        }
+        ExpressionKind::Binary { left, right, .. } => {
+            validate_expression(left, had_error, problems);
+            validate_expression(right, had_error, problems);
+        }
+        ExpressionKind::Unary { operand, .. } | ExpressionKind::Postfix { operand, .. } => {
+            validate_expression(operand, had_error, problems);
+        }
+        ExpressionKind::Grouping(inner) => validate_expression(inner, had_error, problems),
+        ExpressionKind::Call { callee, args } => {
+            validate_expression(callee, had_error, problems);
+            for arg in args {
+                validate_expression(arg, had_error, problems);
+            }
+        }
+        ExpressionKind::Get { object, .. } => validate_expression(object, had_error, problems),
+        ExpressionKind::Index { object, index } => {
+            validate_expression(object, had_error, problems);
+            validate_expression(index, had_error, problems);
+        }
+        ExpressionKind::Assignment { target, value, .. } => {
+            validate_expression(target, had_error, problems);
+            validate_expression(value, had_error, problems);
+        }
+        ExpressionKind::ArrayLiteral(items) | ExpressionKind::Tuple(items) => {
+            for item in items {
+                validate_expression(item, had_error, problems);
+            }
+        }
+        ExpressionKind::StructInit { fields, .. } => {
+            for (_, value) in fields {
+                validate_expression(value, had_error, problems);
+            }
+        }
+        ExpressionKind::Lambda { body, .. } => {
+            for s in body {
+                validate_statement(s, had_error, problems);
+            }
+        }
+        ExpressionKind::Try(inner) => validate_expression(inner, had_error, problems),
+        ExpressionKind::MapLiteral(entries) => {
+            for (key, value) in entries {
+                validate_expression(key, had_error, problems);
+                validate_expression(value, had_error, problems);
+            }
+        }
+        ExpressionKind::Match { subject, arms } => {
+            validate_expression(subject, had_error, problems);
+            validate_arms(arms, had_error, problems);
+        }
+        _ => {}
+    }
+}
+
+fn validate_arms(arms: &[MatchArm], had_error: bool, problems: &mut Vec<String>) {
+    for arm in arms {
+        for s in &arm.body {
+            validate_statement(s, had_error, problems);
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Compact bytecode format lowered from a resolved [`crate::ast::Module`],
+//! and the compiler that produces it.
+//!
+//! Each function gets its own [`Chunk`] (a flat instruction stream plus a
+//! constant pool), the same way most stack-based bytecode VMs (Lua,
+//! CPython) shape a function's compiled form — it avoids threading a
+//! single global address space through jump/call targets. Top-level
+//! statements are lowered into an implicit `Chunk` of their own, `main`,
+//! which the VM runs first.
+//!
+//! Only the subset of the language reachable through arithmetic,
+//! variables, control flow and plain function calls lowers to bytecode
+//! today; constructs the resolver already understands but this backend
+//! doesn't yet (structs, enums, match, arrays, closures, ...) report
+//! `E300` and are skipped rather than panicking.
+
+use crate::ast::{Expression, ExpressionKind, LiteralValue, Module, Statement, StatementKind};
+use crate::common::Position;
+use crate::errors::Diagnostic;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Char(char),
+    Void,
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Char(c) => write!(f, "{}", c),
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+/// One bytecode instruction. Jump targets are absolute offsets into the
+/// same chunk's `code`, patched in after the jump's destination is known
+/// (see [`Compiler::patch_jump`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    Constant(u16),
+    Pop,
+    GetLocal(u16),
+    SetLocal(u16),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Neg,
+    Not,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+    Jump(usize),
+    JumpIfFalse(usize),
+    Call(u16, u8),
+    Print,
+    Return,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    pub code: Vec<Op>,
+    pub constants: Vec<Value>,
+}
+
+impl Chunk {
+    fn add_constant(&mut self, value: Value) -> u16 {
+        self.constants.push(value);
+        (self.constants.len() - 1) as u16
+    }
+
+    fn emit(&mut self, op: Op) -> usize {
+        self.code.push(op);
+        self.code.len() - 1
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionProto {
+    pub name: String,
+    pub arity: usize,
+    pub chunk: Chunk,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub functions: Vec<FunctionProto>,
+    pub main: Chunk,
+}
+
+/// One open loop, so `break`/`continue` know which jumps to patch once
+/// the loop's start and end offsets are known.
+struct LoopContext {
+    start: usize,
+    break_jumps: Vec<usize>,
+}
+
+struct FunctionCompiler<'a> {
+    chunk: Chunk,
+    /// Names in scope, indexed by their stack slot relative to the
+    /// current call frame's base. Scope exit truncates this back to its
+    /// length on entry.
+    locals: Vec<String>,
+    loops: Vec<LoopContext>,
+    functions: &'a [(String, usize)],
+    /// Names of `extern` functions -- present in `functions` for arity
+    /// purposes, but with no bytecode body to call into.
+    externs: &'a std::collections::HashSet<String>,
+}
+
+/// Lowers `module` to bytecode. Errors (unsupported constructs, calls to
+/// undefined functions) are reported the same way the rest of the
+/// compiler reports them, via [`Diagnostic::report`]; `had_error` mirrors
+/// the other phases' convention of a boolean flag rather than a `Result`.
+pub fn compile(module: &Module, file: &str) -> (Program, bool) {
+    let mut had_error = false;
+    let mut function_table: Vec<(String, usize)> = Vec::new();
+    let mut externs: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for statement in &module.statements {
+        if let StatementKind::FunctionDecl(function) = &statement.kind {
+            if function.extern_info.is_some() {
+                // Kept out of `function_table` entirely (it has no
+                // bytecode body to hold an index into `functions`) --
+                // tracked here only so the `Call` arm below can name it
+                // specifically instead of reporting "unknown function".
+                externs.insert(function.name.clone());
+                continue;
+            }
+            function_table.push((function.name.clone(), function.params.len()));
+        }
+    }
+
+    let mut functions = Vec::new();
+    for statement in &module.statements {
+        if let StatementKind::FunctionDecl(function) = &statement.kind {
+            if function.extern_info.is_some() {
+                continue;
+            }
+            let mut compiler = FunctionCompiler {
+                chunk: Chunk::default(),
+                locals: function.params.iter().map(|p| p.name.clone()).collect(),
+                loops: Vec::new(),
+                functions: &function_table,
+                externs: &externs,
+            };
+            for stmt in &function.body {
+                had_error |= compiler.statement(stmt, file);
+            }
+            let void_constant = compiler.chunk.add_constant(Value::Void);
+            compiler.chunk.emit(Op::Constant(void_constant));
+            compiler.chunk.emit(Op::Return);
+            functions.push(FunctionProto {
+                name: function.name.clone(),
+                arity: function.params.len(),
+                chunk: compiler.chunk,
+            });
+        }
+    }
+
+    let mut main_compiler = FunctionCompiler {
+        chunk: Chunk::default(),
+        locals: Vec::new(),
+        loops: Vec::new(),
+        functions: &function_table,
+        externs: &externs,
+    };
+    for statement in &module.statements {
+        if matches!(statement.kind, StatementKind::FunctionDecl(_)) {
+            continue;
+        }
+        had_error |= main_compiler.statement(statement, file);
+    }
+
+    (
+        Program {
+            functions,
+            main: main_compiler.chunk,
+        },
+        had_error,
+    )
+}
+
+impl<'a> FunctionCompiler<'a> {
+    fn unsupported(&self, what: &str, position: Position, file: &str) -> bool {
+        Diagnostic::error("E300", format!("'{}' is not yet supported by the bytecode backend", what), position)
+            .report(file);
+        true
+    }
+
+    fn resolve_local(&self, name: &str) -> Option<u16> {
+        self.locals.iter().rposition(|local| local == name).map(|i| i as u16)
+    }
+
+    fn resolve_function(&self, name: &str) -> Option<u16> {
+        self.functions.iter().position(|(n, _)| n == name).map(|i| i as u16)
+    }
+
+    fn patch_jump(&mut self, offset: usize) {
+        let target = self.chunk.code.len();
+        match &mut self.chunk.code[offset] {
+            Op::Jump(dest) | Op::JumpIfFalse(dest) => *dest = target,
+            _ => unreachable!("patch_jump target is always a jump instruction"),
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement, file: &str) -> bool {
+        let pos = statement.position;
+        match &statement.kind {
+            StatementKind::Expression(expr) => {
+                let had_error = self.expression(expr, file);
+                self.chunk.emit(Op::Pop);
+                had_error
+            }
+            StatementKind::Let { name, value, .. } => {
+                let had_error = match value {
+                    Some(expr) => self.expression(expr, file),
+                    None => {
+                        let index = self.chunk.add_constant(Value::Void);
+                        self.chunk.emit(Op::Constant(index));
+                        false
+                    }
+                };
+                self.locals.push(name.clone());
+                had_error
+            }
+            StatementKind::Return(value) => {
+                let had_error = match value {
+                    Some(expr) => self.expression(expr, file),
+                    None => {
+                        let index = self.chunk.add_constant(Value::Void);
+                        self.chunk.emit(Op::Constant(index));
+                        false
+                    }
+                };
+                self.chunk.emit(Op::Return);
+                had_error
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let mut had_error = self.expression(condition, file);
+                let else_jump = self.chunk.emit(Op::JumpIfFalse(0));
+                let depth = self.locals.len();
+                for stmt in then_branch {
+                    had_error |= self.statement(stmt, file);
+                }
+                self.locals.truncate(depth);
+                let end_jump = self.chunk.emit(Op::Jump(0));
+                self.patch_jump(else_jump);
+                if let Some(else_branch) = else_branch {
+                    for stmt in else_branch {
+                        had_error |= self.statement(stmt, file);
+                    }
+                    self.locals.truncate(depth);
+                }
+                self.patch_jump(end_jump);
+                had_error
+            }
+            StatementKind::While { condition, body } => {
+                let start = self.chunk.code.len();
+                let mut had_error = self.expression(condition, file);
+                let exit_jump = self.chunk.emit(Op::JumpIfFalse(0));
+                self.loops.push(LoopContext {
+                    start,
+                    break_jumps: Vec::new(),
+                });
+                let depth = self.locals.len();
+                for stmt in body {
+                    had_error |= self.statement(stmt, file);
+                }
+                self.locals.truncate(depth);
+                self.chunk.emit(Op::Jump(start));
+                self.patch_jump(exit_jump);
+                let loop_ctx = self.loops.pop().expect("pushed above");
+                for jump in loop_ctx.break_jumps {
+                    self.patch_jump(jump);
+                }
+                had_error
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                let depth = self.locals.len();
+                let mut had_error = false;
+                if let Some(init) = init {
+                    had_error |= self.statement(init, file);
+                }
+                let start = self.chunk.code.len();
+                let exit_jump = if let Some(condition) = condition {
+                    had_error |= self.expression(condition, file);
+                    Some(self.chunk.emit(Op::JumpIfFalse(0)))
+                } else {
+                    None
+                };
+                self.loops.push(LoopContext {
+                    start,
+                    break_jumps: Vec::new(),
+                });
+                let body_depth = self.locals.len();
+                for stmt in body {
+                    had_error |= self.statement(stmt, file);
+                }
+                self.locals.truncate(body_depth);
+                if let Some(update) = update {
+                    had_error |= self.expression(update, file);
+                    self.chunk.emit(Op::Pop);
+                }
+                self.chunk.emit(Op::Jump(start));
+                if let Some(exit_jump) = exit_jump {
+                    self.patch_jump(exit_jump);
+                }
+                let loop_ctx = self.loops.pop().expect("pushed above");
+                for jump in loop_ctx.break_jumps {
+                    self.patch_jump(jump);
+                }
+                self.locals.truncate(depth);
+                had_error
+            }
+            StatementKind::Block(statements) => {
+                let depth = self.locals.len();
+                let mut had_error = false;
+                for stmt in statements {
+                    had_error |= self.statement(stmt, file);
+                }
+                self.locals.truncate(depth);
+                had_error
+            }
+            StatementKind::Break => {
+                let jump = self.chunk.emit(Op::Jump(0));
+                match self.loops.last_mut() {
+                    Some(loop_ctx) => loop_ctx.break_jumps.push(jump),
+                    None => return self.unsupported("break outside a loop", pos, file),
+                }
+                false
+            }
+            StatementKind::Continue => match self.loops.last() {
+                Some(loop_ctx) => {
+                    self.chunk.emit(Op::Jump(loop_ctx.start));
+                    false
+                }
+                None => self.unsupported("continue outside a loop", pos, file),
+            },
+            StatementKind::FunctionDecl(_) => self.unsupported("nested function declarations", pos, file),
+            StatementKind::StructDecl(_)
+            | StatementKind::EnumDecl(_)
+            | StatementKind::InterfaceDecl(_)
+            | StatementKind::ImplBlock(_)
+            | StatementKind::ModuleDecl(_)
+            | StatementKind::Import(_)
+            | StatementKind::Export(_)
+            | StatementKind::ForEach { .. }
+            | StatementKind::Match { .. } => self.unsupported("this statement", pos, file),
+            StatementKind::Error => false,
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression, file: &str) -> bool {
+        let pos = expr.position;
+        match &expr.kind {
+            ExpressionKind::Literal(literal) => {
+                let value = match literal {
+                    LiteralValue::Int(n, _) => Value::Int(*n as i64),
+                    LiteralValue::Float(n, _) => Value::Float(*n),
+                    LiteralValue::String(s) => Value::String(s.clone()),
+                    LiteralValue::Char(c) => Value::Char(*c),
+                    LiteralValue::Bool(b) => Value::Bool(*b),
+                };
+                let index = self.chunk.add_constant(value);
+                self.chunk.emit(Op::Constant(index));
+                false
+            }
+            ExpressionKind::Identifier(name) => match self.resolve_local(name) {
+                Some(slot) => {
+                    self.chunk.emit(Op::GetLocal(slot));
+                    false
+                }
+                None => self.unsupported(&format!("reference to undeclared local '{}'", name), pos, file),
+            },
+            ExpressionKind::Grouping(inner) => self.expression(inner, file),
+            ExpressionKind::Unary { op, operand } => {
+                let had_error = self.expression(operand, file);
+                match op.as_str() {
+                    "-" => self.chunk.emit(Op::Neg),
+                    "!" => self.chunk.emit(Op::Not),
+                    _ => return had_error | self.unsupported(&format!("unary operator '{}'", op), pos, file),
+                };
+                had_error
+            }
+            ExpressionKind::Binary { left, op, right } => {
+                let mut had_error = self.expression(left, file);
+                had_error |= self.expression(right, file);
+                match op.as_str() {
+                    "+" => self.chunk.emit(Op::Add),
+                    "-" => self.chunk.emit(Op::Sub),
+                    "*" => self.chunk.emit(Op::Mul),
+                    "/" => self.chunk.emit(Op::Div),
+                    "%" => self.chunk.emit(Op::Mod),
+                    "==" => self.chunk.emit(Op::Eq),
+                    "!=" => self.chunk.emit(Op::NotEq),
+                    "<" => self.chunk.emit(Op::Lt),
+                    "<=" => self.chunk.emit(Op::LtEq),
+                    ">" => self.chunk.emit(Op::Gt),
+                    ">=" => self.chunk.emit(Op::GtEq),
+                    "&&" => self.chunk.emit(Op::And),
+                    "||" => self.chunk.emit(Op::Or),
+                    _ => return had_error | self.unsupported(&format!("binary operator '{}'", op), pos, file),
+                };
+                had_error
+            }
+            ExpressionKind::Assignment { target, op, value } => {
+                let ExpressionKind::Identifier(name) = &target.kind else {
+                    return self.unsupported("assignment to a non-variable target", pos, file);
+                };
+                let Some(slot) = self.resolve_local(name) else {
+                    return self.unsupported(&format!("assignment to undeclared local '{}'", name), pos, file);
+                };
+                let mut had_error = false;
+                if op != "=" {
+                    self.chunk.emit(Op::GetLocal(slot));
+                    had_error |= self.expression(value, file);
+                    let compound = &op[..op.len() - 1];
+                    match compound {
+                        "+" => self.chunk.emit(Op::Add),
+                        "-" => self.chunk.emit(Op::Sub),
+                        "*" => self.chunk.emit(Op::Mul),
+                        "/" => self.chunk.emit(Op::Div),
+                        "%" => self.chunk.emit(Op::Mod),
+                        _ => return had_error | self.unsupported(&format!("assignment operator '{}'", op), pos, file),
+                    };
+                } else {
+                    had_error |= self.expression(value, file);
+                }
+                self.chunk.emit(Op::SetLocal(slot));
+                self.chunk.emit(Op::GetLocal(slot));
+                had_error
+            }
+            ExpressionKind::Call { callee, args } => {
+                let ExpressionKind::Identifier(name) = &callee.kind else {
+                    return self.unsupported("calls to a non-identifier callee", pos, file);
+                };
+                let mut had_error = false;
+                for arg in args {
+                    had_error |= self.expression(arg, file);
+                }
+                if name == "print" {
+                    if args.len() != 1 {
+                        return had_error | self.unsupported("print with other than one argument", pos, file);
+                    }
+                    self.chunk.emit(Op::Print);
+                    let index = self.chunk.add_constant(Value::Void);
+                    self.chunk.emit(Op::Constant(index));
+                    return had_error;
+                }
+                if self.externs.contains(name) {
+                    return had_error | self.unsupported(&format!("calling extern function '{}'", name), pos, file);
+                }
+                match self.resolve_function(name) {
+                    Some(function_index) => {
+                        self.chunk.emit(Op::Call(function_index, args.len() as u8));
+                    }
+                    None => return had_error | self.unsupported(&format!("call to unknown function '{}'", name), pos, file),
+                }
+                had_error
+            }
+            ExpressionKind::Try(_)
+            | ExpressionKind::Postfix { .. }
+            | ExpressionKind::Get { .. }
+            | ExpressionKind::Index { .. }
+            | ExpressionKind::ArrayLiteral(_)
+            | ExpressionKind::Tuple(_)
+            | ExpressionKind::MapLiteral(_)
+            | ExpressionKind::StructInit { .. }
+            | ExpressionKind::Lambda { .. }
+            | ExpressionKind::Match { .. } => self.unsupported("this expression", pos, file),
+            ExpressionKind::Error => false,
+        }
+    }
+}
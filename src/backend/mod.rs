@@ -0,0 +1,7 @@
+//! Compiled backends: lowers a resolved AST to a compact instruction
+//! format ([`bytecode`]) and executes it ([`vm`]), or emits a standalone
+//! [`wasm`] module, instead of compilation ending at an `.ast` dump.
+
+pub mod bytecode;
+pub mod vm;
+pub mod wasm;
@@ -0,0 +1,222 @@
+//! Stack-based VM that executes a [`crate::backend::bytecode::Program`].
+
+use crate::backend::bytecode::{Chunk, Op, Program, Value};
+
+struct Frame<'a> {
+    chunk: &'a Chunk,
+    ip: usize,
+    base: usize,
+}
+
+/// A runtime failure that isn't a bug in the VM itself (division by zero,
+/// calling an arity the compiler should have already rejected, ...).
+#[derive(Debug)]
+pub struct RuntimeError(pub String);
+
+pub struct Vm<'a> {
+    program: &'a Program,
+    stack: Vec<Value>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Runs `program.main` to completion, printing anything the source
+    /// passed to `print(...)` along the way.
+    pub fn run(&mut self) -> Result<(), RuntimeError> {
+        let mut frames = vec![Frame {
+            chunk: &self.program.main,
+            ip: 0,
+            base: 0,
+        }];
+
+        loop {
+            let frame = frames.last_mut().expect("main frame is never popped early");
+            let Some(op) = frame.chunk.code.get(frame.ip) else {
+                frames.pop();
+                if frames.is_empty() {
+                    return Ok(());
+                }
+                continue;
+            };
+            frame.ip += 1;
+
+            match op {
+                Op::Constant(index) => {
+                    let value = frame.chunk.constants[*index as usize].clone();
+                    self.stack.push(value);
+                }
+                Op::Pop => {
+                    self.stack.pop();
+                }
+                Op::GetLocal(slot) => {
+                    let base = frame.base;
+                    self.stack.push(self.stack[base + *slot as usize].clone());
+                }
+                Op::SetLocal(slot) => {
+                    let base = frame.base;
+                    let value = self.stack.last().expect("assignment leaves its value on the stack").clone();
+                    self.stack[base + *slot as usize] = value;
+                }
+                Op::Neg => {
+                    let value = self.pop_numeric()?;
+                    self.stack.push(match value {
+                        Value::Int(n) => Value::Int(-n),
+                        Value::Float(n) => Value::Float(-n),
+                        _ => unreachable!("pop_numeric only returns Int or Float"),
+                    });
+                }
+                Op::Not => {
+                    let value = self.stack.pop().expect("unary operand");
+                    self.stack.push(Value::Bool(!truthy(&value)));
+                }
+                Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Mod => {
+                    let right = self.stack.pop().expect("binary rhs");
+                    let left = self.stack.pop().expect("binary lhs");
+                    self.stack.push(self.arithmetic(op, left, right)?);
+                }
+                Op::Eq | Op::NotEq | Op::Lt | Op::LtEq | Op::Gt | Op::GtEq => {
+                    let right = self.stack.pop().expect("comparison rhs");
+                    let left = self.stack.pop().expect("comparison lhs");
+                    self.stack.push(Value::Bool(compare(op, &left, &right)?));
+                }
+                Op::And => {
+                    let right = self.stack.pop().expect("and rhs");
+                    let left = self.stack.pop().expect("and lhs");
+                    self.stack.push(Value::Bool(truthy(&left) && truthy(&right)));
+                }
+                Op::Or => {
+                    let right = self.stack.pop().expect("or rhs");
+                    let left = self.stack.pop().expect("or lhs");
+                    self.stack.push(Value::Bool(truthy(&left) || truthy(&right)));
+                }
+                Op::Jump(target) => frame.ip = *target,
+                Op::JumpIfFalse(target) => {
+                    let condition = self.stack.pop().expect("if/while condition");
+                    if !truthy(&condition) {
+                        frame.ip = *target;
+                    }
+                }
+                Op::Print => {
+                    let value = self.stack.pop().expect("print argument");
+                    println!("{}", value);
+                }
+                Op::Call(function_index, arg_count) => {
+                    let proto = &self.program.functions[*function_index as usize];
+                    let base = self.stack.len() - *arg_count as usize;
+                    frames.push(Frame {
+                        chunk: &proto.chunk,
+                        ip: 0,
+                        base,
+                    });
+                }
+                Op::Return => {
+                    let value = self.stack.pop().expect("return leaves its value on the stack");
+                    let base = frame.base;
+                    self.stack.truncate(base);
+                    self.stack.push(value);
+                    frames.pop();
+                    if frames.is_empty() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    fn pop_numeric(&mut self) -> Result<Value, RuntimeError> {
+        match self.stack.pop() {
+            Some(value @ (Value::Int(_) | Value::Float(_))) => Ok(value),
+            Some(other) => Err(RuntimeError(format!("expected a number, found {}", other))),
+            None => Err(RuntimeError("expected a number, found nothing on the stack".to_string())),
+        }
+    }
+
+    fn arithmetic(&self, op: &Op, left: Value, right: Value) -> Result<Value, RuntimeError> {
+        match (left, right) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(int_op(op, a, b)?)),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(float_op(op, a, b)?)),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(float_op(op, a as f64, b)?)),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(float_op(op, a, b as f64)?)),
+            (Value::String(a), Value::String(b)) if matches!(op, Op::Add) => Ok(Value::String(a + &b)),
+            (a, b) => Err(RuntimeError(format!("cannot apply operator to {} and {}", a, b))),
+        }
+    }
+}
+
+fn int_op(op: &Op, a: i64, b: i64) -> Result<i64, RuntimeError> {
+    match op {
+        Op::Add => Ok(a + b),
+        Op::Sub => Ok(a - b),
+        Op::Mul => Ok(a * b),
+        Op::Div => {
+            if b == 0 {
+                Err(RuntimeError("division by zero".to_string()))
+            } else {
+                Ok(a / b)
+            }
+        }
+        Op::Mod => {
+            if b == 0 {
+                Err(RuntimeError("division by zero".to_string()))
+            } else {
+                Ok(a % b)
+            }
+        }
+        _ => unreachable!("int_op is only called for arithmetic ops"),
+    }
+}
+
+fn float_op(op: &Op, a: f64, b: f64) -> Result<f64, RuntimeError> {
+    match op {
+        Op::Add => Ok(a + b),
+        Op::Sub => Ok(a - b),
+        Op::Mul => Ok(a * b),
+        Op::Div => Ok(a / b),
+        Op::Mod => Ok(a % b),
+        _ => unreachable!("float_op is only called for arithmetic ops"),
+    }
+}
+
+fn compare(op: &Op, left: &Value, right: &Value) -> Result<bool, RuntimeError> {
+    let ordering = match (left, right) {
+        (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+        (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Value::Char(a), Value::Char(b)) => a.partial_cmp(b),
+        (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => {
+            if matches!(op, Op::Eq | Op::NotEq) {
+                return Ok(if matches!(op, Op::Eq) { a == b } else { a != b });
+            }
+            return Err(RuntimeError("cannot order two booleans".to_string()));
+        }
+        (a, b) => return Err(RuntimeError(format!("cannot compare {} and {}", a, b))),
+    };
+    let Some(ordering) = ordering else {
+        return Err(RuntimeError("comparison produced no ordering (NaN?)".to_string()));
+    };
+    Ok(match op {
+        Op::Eq => ordering.is_eq(),
+        Op::NotEq => !ordering.is_eq(),
+        Op::Lt => ordering.is_lt(),
+        Op::LtEq => ordering.is_le(),
+        Op::Gt => ordering.is_gt(),
+        Op::GtEq => ordering.is_ge(),
+        _ => unreachable!("compare is only called for comparison ops"),
+    })
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Void => false,
+        _ => true,
+    }
+}
@@ -0,0 +1,778 @@
+//! Emits a `.wasm` module by hand-encoding the binary format directly
+//! (no `wasm-encoder`/`walrus` — this crate takes no dependencies), so a
+//! compiled program can run in a browser or under wasmtime instead of
+//! only through [`crate::backend::vm`] or [`crate::interpreter`].
+//!
+//! Scope matches the other backends: the numeric/control-flow subset of
+//! the language lowers to real instructions; `String`/`Array`/`Map`/
+//! struct values have no representation in linear memory yet and report
+//! `E303` rather than emitting anything unsound. Two host functions,
+//! `env.print_i32` and `env.print_f64`, stand in for `print` until the
+//! module has a real ABI for passing strings across the boundary.
+
+use crate::ast::{Expression, ExpressionKind, LiteralValue, Module, Statement, StatementKind, TypeKind};
+use crate::common::Position;
+use crate::errors::Diagnostic;
+use std::collections::HashMap;
+
+const WASM_MAGIC: [u8; 4] = [0x00, 0x61, 0x73, 0x6D];
+const WASM_VERSION: [u8; 4] = [0x01, 0x00, 0x00, 0x00];
+
+const SECTION_TYPE: u8 = 1;
+const SECTION_IMPORT: u8 = 2;
+const SECTION_FUNCTION: u8 = 3;
+const SECTION_EXPORT: u8 = 7;
+const SECTION_CODE: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValType {
+    I32,
+    F64,
+}
+
+impl ValType {
+    fn byte(self) -> u8 {
+        match self {
+            ValType::I32 => 0x7F,
+            ValType::F64 => 0x7C,
+        }
+    }
+
+    /// The wider of two numeric wasm types, mirroring the promotion rule
+    /// [`TypeKind::precedence`] applies at the AST-typed level.
+    fn widen(self, other: ValType) -> ValType {
+        if self == ValType::F64 || other == ValType::F64 {
+            ValType::F64
+        } else {
+            ValType::I32
+        }
+    }
+}
+
+fn leb_u32(mut value: u32, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn leb_i64(mut value: i64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn wasm_name(name: &str, out: &mut Vec<u8>) {
+    leb_u32(name.len() as u32, out);
+    out.extend_from_slice(name.as_bytes());
+}
+
+/// Wraps `content` with its own length prefix, the shape every section
+/// (and every function body within the code section) takes.
+fn with_size_prefix(content: Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::new();
+    leb_u32(content.len() as u32, &mut out);
+    out.extend(content);
+    out
+}
+
+fn section(id: u8, content: Vec<u8>, out: &mut Vec<u8>) {
+    out.push(id);
+    out.extend(with_size_prefix(content));
+}
+
+/// Opcode bytes, named the way the spec names them, so `Instr::Emit`
+/// call sites read like the instruction they produce.
+mod op {
+    pub const BLOCK: u8 = 0x02;
+    pub const LOOP: u8 = 0x03;
+    pub const IF: u8 = 0x04;
+    pub const ELSE: u8 = 0x05;
+    pub const END: u8 = 0x0B;
+    pub const BR: u8 = 0x0C;
+    pub const BR_IF: u8 = 0x0D;
+    pub const CALL: u8 = 0x10;
+    pub const DROP: u8 = 0x1A;
+    pub const LOCAL_GET: u8 = 0x20;
+    pub const LOCAL_SET: u8 = 0x21;
+    pub const LOCAL_TEE: u8 = 0x22;
+    pub const I32_CONST: u8 = 0x41;
+    pub const F64_CONST: u8 = 0x44;
+    pub const I32_EQZ: u8 = 0x45;
+    pub const I32_EQ: u8 = 0x46;
+    pub const I32_NE: u8 = 0x47;
+    pub const I32_LT_S: u8 = 0x48;
+    pub const I32_GT_S: u8 = 0x4A;
+    pub const I32_LE_S: u8 = 0x4C;
+    pub const I32_GE_S: u8 = 0x4E;
+    pub const I32_ADD: u8 = 0x6A;
+    pub const I32_SUB: u8 = 0x6B;
+    pub const I32_MUL: u8 = 0x6C;
+    pub const I32_DIV_S: u8 = 0x6D;
+    pub const I32_REM_S: u8 = 0x6F;
+    pub const I32_AND: u8 = 0x71;
+    pub const I32_OR: u8 = 0x72;
+    pub const F64_EQ: u8 = 0x61;
+    pub const F64_NE: u8 = 0x62;
+    pub const F64_LT: u8 = 0x63;
+    pub const F64_GT: u8 = 0x64;
+    pub const F64_LE: u8 = 0x65;
+    pub const F64_GE: u8 = 0x66;
+    pub const F64_NEG: u8 = 0x9A;
+    pub const F64_ADD: u8 = 0xA0;
+    pub const F64_SUB: u8 = 0xA1;
+    pub const F64_MUL: u8 = 0xA2;
+    pub const F64_DIV: u8 = 0xA3;
+    pub const F64_CONVERT_I32_S: u8 = 0xB7;
+}
+
+const BLOCKTYPE_EMPTY: u8 = 0x40;
+
+struct FunctionSig {
+    params: Vec<ValType>,
+    result: Option<ValType>,
+}
+
+struct FunctionInfo {
+    index: u32,
+    sig: FunctionSig,
+}
+
+/// One open `block`/`loop`/`if`, recorded so `break`/`continue` can
+/// compute the relative label depth wasm's `br`/`br_if` expect (0 =
+/// innermost enclosing structured construct).
+struct LoopLabels {
+    /// Nesting depth right after the wrapping `block` was entered.
+    exit_depth: u32,
+    /// Nesting depth right after the `loop` itself was entered.
+    continue_depth: u32,
+}
+
+struct FunctionCompiler<'a> {
+    code: Vec<u8>,
+    locals: Vec<(String, ValType)>,
+    depth: u32,
+    /// Nesting depth of the block wrapping the whole function body,
+    /// which every `return` branches to (see [`compile`]).
+    return_depth: u32,
+    /// This function's declared return type, if not `Void` — every
+    /// `return` converts its value to this type before branching out.
+    result_ty: Option<ValType>,
+    loops: Vec<LoopLabels>,
+    functions: &'a HashMap<String, FunctionInfo>,
+    had_error: bool,
+    file: &'a str,
+}
+
+/// Lowers `module` to a `.wasm` binary. Returns the bytes and whether an
+/// unsupported construct was hit; on `true` the module may still parse
+/// (invalid pieces are dropped rather than emitted half-formed), but it
+/// isn't a faithful translation of the source.
+pub fn compile(module: &Module, file: &str) -> (Vec<u8>, bool) {
+    let mut had_error = false;
+    let mut functions: HashMap<String, FunctionInfo> = HashMap::new();
+    let mut order = Vec::new();
+
+    for statement in &module.statements {
+        if let StatementKind::FunctionDecl(function) = &statement.kind {
+            if function.extern_info.is_some() {
+                // A wasm function index needs a body in the same module;
+                // there's nowhere to put one for a symbol that's actually
+                // implemented by a native library. Leaving it out of
+                // `functions` means a call to it falls through to the
+                // "call to unknown function" diagnostic below, same as
+                // any other unresolvable callee.
+                continue;
+            }
+            let params = function
+                .params
+                .iter()
+                .map(|p| val_type(&p.ty.kind))
+                .collect::<Option<Vec<_>>>();
+            let Some(params) = params else {
+                Diagnostic::error("E303", format!("function '{}' has a parameter type not yet supported by the wasm backend", function.name), function.position)
+                    .report(file);
+                had_error = true;
+                continue;
+            };
+            let result = match &function.return_type.kind {
+                TypeKind::Void => None,
+                other => match val_type(other) {
+                    Some(vt) => Some(vt),
+                    None => {
+                        Diagnostic::error("E303", format!("function '{}' has a return type not yet supported by the wasm backend", function.name), function.position)
+                            .report(file);
+                        had_error = true;
+                        continue;
+                    }
+                },
+            };
+            let index = functions.len() as u32;
+            functions.insert(function.name.clone(), FunctionInfo { index, sig: FunctionSig { params, result } });
+            order.push(function.name.clone());
+        }
+    }
+
+    let mut bodies = Vec::new();
+    for statement in &module.statements {
+        let StatementKind::FunctionDecl(function) = &statement.kind else {
+            continue;
+        };
+        if !functions.contains_key(&function.name) {
+            continue;
+        }
+        let result = functions[&function.name].sig.result;
+        let mut compiler = FunctionCompiler {
+            code: Vec::new(),
+            locals: function
+                .params
+                .iter()
+                .map(|p| (p.name.clone(), val_type(&p.ty.kind).expect("checked above")))
+                .collect(),
+            depth: 1,
+            return_depth: 1,
+            result_ty: result,
+            loops: Vec::new(),
+            functions: &functions,
+            had_error: false,
+            file,
+        };
+        let param_count = compiler.locals.len();
+
+        // The whole body lives inside one wrapping block whose result
+        // type is the function's own: every `return` becomes a `br` to
+        // its `end`, and falling off the end (no explicit `return`) is
+        // covered by pushing a zero value of the right type first, so
+        // the block always leaves exactly the value the function type
+        // promises regardless of which path was taken.
+        compiler.code.push(op::BLOCK);
+        compiler.code.push(match result {
+            Some(ty) => ty.byte(),
+            None => BLOCKTYPE_EMPTY,
+        });
+        for stmt in &function.body {
+            compiler.statement(stmt);
+        }
+        if let Some(ty) = result {
+            compiler.push_zero(ty);
+        }
+        compiler.code.push(op::END);
+        compiler.code.push(op::END);
+        had_error |= compiler.had_error;
+        bodies.push((param_count, compiler.locals, compiler.code));
+    }
+
+    let mut out = Vec::new();
+    out.extend(WASM_MAGIC);
+    out.extend(WASM_VERSION);
+
+    // Type section: one func type per user function, plus the two
+    // `print` host imports.
+    let mut type_section = Vec::new();
+    let total_types = order.len() + 2;
+    leb_u32(total_types as u32, &mut type_section);
+    for name in &order {
+        emit_functype(&functions[name].sig, &mut type_section);
+    }
+    let print_i32_type = order.len() as u32;
+    emit_functype(&FunctionSig { params: vec![ValType::I32], result: None }, &mut type_section);
+    let print_f64_type = print_i32_type + 1;
+    emit_functype(&FunctionSig { params: vec![ValType::F64], result: None }, &mut type_section);
+    section(SECTION_TYPE, type_section, &mut out);
+
+    // Import section: the two print host functions. Imported functions
+    // occupy the low function indices, before any defined function.
+    let mut import_section = Vec::new();
+    leb_u32(2, &mut import_section);
+    wasm_name("env", &mut import_section);
+    wasm_name("print_i32", &mut import_section);
+    import_section.push(0x00);
+    leb_u32(print_i32_type, &mut import_section);
+    wasm_name("env", &mut import_section);
+    wasm_name("print_f64", &mut import_section);
+    import_section.push(0x00);
+    leb_u32(print_f64_type, &mut import_section);
+    section(SECTION_IMPORT, import_section, &mut out);
+
+    // Function section: type index per defined function.
+    let mut function_section = Vec::new();
+    leb_u32(order.len() as u32, &mut function_section);
+    for (i, _) in order.iter().enumerate() {
+        leb_u32(i as u32, &mut function_section);
+    }
+    section(SECTION_FUNCTION, function_section, &mut out);
+
+    // Export section: every user function, by name, so a host can call
+    // any of them directly.
+    let mut export_section = Vec::new();
+    leb_u32(order.len() as u32, &mut export_section);
+    for name in &order {
+        wasm_name(name, &mut export_section);
+        export_section.push(0x00);
+        // Two imports occupy indices 0 and 1; defined functions follow.
+        leb_u32(functions[name].index + 2, &mut export_section);
+    }
+    section(SECTION_EXPORT, export_section, &mut out);
+
+    // Code section.
+    let mut code_section = Vec::new();
+    leb_u32(bodies.len() as u32, &mut code_section);
+    for (param_count, locals, code) in &bodies {
+        let mut body = Vec::new();
+        let extra_locals = &locals[*param_count..];
+        leb_u32(extra_locals.len() as u32, &mut body);
+        for (_, ty) in extra_locals {
+            leb_u32(1, &mut body);
+            body.push(ty.byte());
+        }
+        body.extend(code);
+        code_section.extend(with_size_prefix(body));
+    }
+    section(SECTION_CODE, code_section, &mut out);
+
+    (out, had_error)
+}
+
+fn emit_functype(sig: &FunctionSig, out: &mut Vec<u8>) {
+    out.push(0x60);
+    leb_u32(sig.params.len() as u32, out);
+    for param in &sig.params {
+        out.push(param.byte());
+    }
+    match sig.result {
+        Some(result) => {
+            leb_u32(1, out);
+            out.push(result.byte());
+        }
+        None => leb_u32(0, out),
+    }
+}
+
+fn val_type(kind: &TypeKind) -> Option<ValType> {
+    match kind {
+        TypeKind::Int32 | TypeKind::Int64 | TypeKind::Bool | TypeKind::Char => Some(ValType::I32),
+        TypeKind::Float32 | TypeKind::Float64 => Some(ValType::F64),
+        _ => None,
+    }
+}
+
+impl<'a> FunctionCompiler<'a> {
+    fn unsupported(&mut self, what: &str, position: Position) {
+        self.had_error = true;
+        Diagnostic::error("E303", format!("'{}' is not yet supported by the wasm backend", what), position).report(self.file);
+    }
+
+    fn resolve_local(&self, name: &str) -> Option<(u32, ValType)> {
+        self.locals
+            .iter()
+            .rposition(|(local, _)| local == name)
+            .map(|i| (i as u32, self.locals[i].1))
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let pos = statement.position;
+        match &statement.kind {
+            StatementKind::Expression(expr) => {
+                if let Some(ty) = self.expression(expr) {
+                    let _ = ty;
+                    self.code.push(op::DROP);
+                }
+            }
+            StatementKind::Let { name, ty, value, .. } => {
+                let value_ty = value.as_ref().and_then(|expr| self.expression(expr));
+                let declared = ty.as_ref().and_then(|t| val_type(&t.kind));
+                let Some(local_ty) = declared.or(value_ty) else {
+                    self.unsupported(&format!("let binding '{}' with an unrepresentable type", name), pos);
+                    return;
+                };
+                match value_ty {
+                    Some(value_ty) => {
+                        if self.convert(value_ty, local_ty, pos).is_none() {
+                            return;
+                        }
+                    }
+                    None => self.push_zero(local_ty),
+                }
+                let index = self.locals.len() as u32;
+                self.locals.push((name.clone(), local_ty));
+                self.code.push(op::LOCAL_SET);
+                leb_u32(index, &mut self.code);
+            }
+            StatementKind::Return(value) => {
+                if let Some(expr) = value {
+                    if let (Some(value_ty), Some(result_ty)) = (self.expression(expr), self.result_ty) {
+                        if self.convert(value_ty, result_ty, pos).is_none() {
+                            return;
+                        }
+                    }
+                }
+                self.code.push(op::BR);
+                leb_u32(self.depth - self.return_depth, &mut self.code);
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expression(condition);
+                self.code.push(op::IF);
+                self.code.push(BLOCKTYPE_EMPTY);
+                self.depth += 1;
+                for stmt in then_branch {
+                    self.statement(stmt);
+                }
+                if let Some(else_branch) = else_branch {
+                    self.code.push(op::ELSE);
+                    for stmt in else_branch {
+                        self.statement(stmt);
+                    }
+                }
+                self.depth -= 1;
+                self.code.push(op::END);
+            }
+            StatementKind::While { condition, body } => {
+                self.code.push(op::BLOCK);
+                self.code.push(BLOCKTYPE_EMPTY);
+                self.depth += 1;
+                let exit_depth = self.depth;
+                self.code.push(op::LOOP);
+                self.code.push(BLOCKTYPE_EMPTY);
+                self.depth += 1;
+                let continue_depth = self.depth;
+
+                self.expression(condition);
+                self.code.push(op::I32_EQZ);
+                self.code.push(op::BR_IF);
+                leb_u32(self.depth - exit_depth, &mut self.code);
+
+                self.loops.push(LoopLabels { exit_depth, continue_depth });
+                for stmt in body {
+                    self.statement(stmt);
+                }
+                self.loops.pop();
+
+                self.code.push(op::BR);
+                leb_u32(self.depth - continue_depth, &mut self.code);
+                self.depth -= 1;
+                self.code.push(op::END);
+                self.depth -= 1;
+                self.code.push(op::END);
+            }
+            StatementKind::For { init, condition, update, body } => {
+                if let Some(init) = init {
+                    self.statement(init);
+                }
+                self.code.push(op::BLOCK);
+                self.code.push(BLOCKTYPE_EMPTY);
+                self.depth += 1;
+                let exit_depth = self.depth;
+                self.code.push(op::LOOP);
+                self.code.push(BLOCKTYPE_EMPTY);
+                self.depth += 1;
+                let loop_depth = self.depth;
+
+                if let Some(condition) = condition {
+                    self.expression(condition);
+                    self.code.push(op::I32_EQZ);
+                    self.code.push(op::BR_IF);
+                    leb_u32(self.depth - exit_depth, &mut self.code);
+                }
+
+                // `continue` must still run `update` before the next condition
+                // check, so it targets this inner block rather than the loop
+                // itself: branching here only skips the rest of the body.
+                self.code.push(op::BLOCK);
+                self.code.push(BLOCKTYPE_EMPTY);
+                self.depth += 1;
+                let continue_depth = self.depth;
+
+                self.loops.push(LoopLabels { exit_depth, continue_depth });
+                for stmt in body {
+                    self.statement(stmt);
+                }
+                self.loops.pop();
+
+                self.depth -= 1;
+                self.code.push(op::END);
+
+                if let Some(update) = update {
+                    if let Some(ty) = self.expression(update) {
+                        let _ = ty;
+                        self.code.push(op::DROP);
+                    }
+                }
+                self.code.push(op::BR);
+                leb_u32(self.depth - loop_depth, &mut self.code);
+                self.depth -= 1;
+                self.code.push(op::END);
+                self.depth -= 1;
+                self.code.push(op::END);
+            }
+            StatementKind::Block(statements) => {
+                for stmt in statements {
+                    self.statement(stmt);
+                }
+            }
+            StatementKind::Break => match self.loops.last() {
+                Some(labels) => {
+                    let relative = self.depth - labels.exit_depth;
+                    self.code.push(op::BR);
+                    leb_u32(relative, &mut self.code);
+                }
+                None => self.unsupported("break outside a loop", pos),
+            },
+            StatementKind::Continue => match self.loops.last() {
+                Some(labels) => {
+                    let relative = self.depth - labels.continue_depth;
+                    self.code.push(op::BR);
+                    leb_u32(relative, &mut self.code);
+                }
+                None => self.unsupported("continue outside a loop", pos),
+            },
+            StatementKind::FunctionDecl(_) => self.unsupported("nested function declarations", pos),
+            StatementKind::StructDecl(_)
+            | StatementKind::EnumDecl(_)
+            | StatementKind::InterfaceDecl(_)
+            | StatementKind::ImplBlock(_)
+            | StatementKind::ModuleDecl(_)
+            | StatementKind::Import(_)
+            | StatementKind::Export(_)
+            | StatementKind::ForEach { .. }
+            | StatementKind::Match { .. } => self.unsupported("this statement", pos),
+            StatementKind::Error => {}
+        }
+    }
+
+    fn push_zero(&mut self, ty: ValType) {
+        match ty {
+            ValType::I32 => {
+                self.code.push(op::I32_CONST);
+                leb_i64(0, &mut self.code);
+            }
+            ValType::F64 => {
+                self.code.push(op::F64_CONST);
+                self.code.extend_from_slice(&0.0f64.to_le_bytes());
+            }
+        }
+    }
+
+    /// Compiles `expr`, leaving its value on the wasm stack, and returns
+    /// its type — or `None` if the expression is unsupported (in which
+    /// case nothing was pushed).
+    fn expression(&mut self, expr: &Expression) -> Option<ValType> {
+        let pos = expr.position;
+        match &expr.kind {
+            ExpressionKind::Literal(literal) => match literal {
+                LiteralValue::Int(n, _) => {
+                    self.code.push(op::I32_CONST);
+                    leb_i64(*n as i64, &mut self.code);
+                    Some(ValType::I32)
+                }
+                LiteralValue::Bool(b) => {
+                    self.code.push(op::I32_CONST);
+                    leb_i64(*b as i64, &mut self.code);
+                    Some(ValType::I32)
+                }
+                LiteralValue::Char(c) => {
+                    self.code.push(op::I32_CONST);
+                    leb_i64(*c as i64, &mut self.code);
+                    Some(ValType::I32)
+                }
+                LiteralValue::Float(n, _) => {
+                    self.code.push(op::F64_CONST);
+                    self.code.extend_from_slice(&n.to_le_bytes());
+                    Some(ValType::F64)
+                }
+                LiteralValue::String(_) => {
+                    self.unsupported("string literals", pos);
+                    None
+                }
+            },
+            ExpressionKind::Identifier(name) => match self.resolve_local(name) {
+                Some((index, ty)) => {
+                    self.code.push(op::LOCAL_GET);
+                    leb_u32(index, &mut self.code);
+                    Some(ty)
+                }
+                None => {
+                    self.unsupported(&format!("reference to undeclared local '{}'", name), pos);
+                    None
+                }
+            },
+            ExpressionKind::Grouping(inner) => self.expression(inner),
+            ExpressionKind::Unary { op, operand } => {
+                // No standalone i32 negate opcode: `-x` lowers to `0 - x`,
+                // so the `i32.const 0` has to land *before* the operand's
+                // own code — spliced in after the fact via `mark`, since
+                // the operand's type (and so which negation applies)
+                // isn't known until it's been compiled.
+                let mark = self.code.len();
+                let ty = self.expression(operand)?;
+                match (op.as_str(), ty) {
+                    ("-", ValType::I32) => {
+                        let operand_code = self.code.split_off(mark);
+                        self.code.push(op::I32_CONST);
+                        self.code.push(0x00);
+                        self.code.extend(operand_code);
+                        self.code.push(op::I32_SUB);
+                        Some(ValType::I32)
+                    }
+                    ("-", ValType::F64) => {
+                        self.code.push(op::F64_NEG);
+                        Some(ValType::F64)
+                    }
+                    ("!", ValType::I32) => {
+                        self.code.push(op::I32_EQZ);
+                        Some(ValType::I32)
+                    }
+                    _ => {
+                        self.unsupported(&format!("unary operator '{}'", op), pos);
+                        None
+                    }
+                }
+            }
+            ExpressionKind::Binary { left, op, right } => self.binary(left, op, right, pos),
+            ExpressionKind::Assignment { target, op, value } => {
+                let ExpressionKind::Identifier(name) = &target.kind else {
+                    self.unsupported("assignment to a non-variable target", pos);
+                    return None;
+                };
+                let Some((index, ty)) = self.resolve_local(name) else {
+                    self.unsupported(&format!("assignment to undeclared local '{}'", name), pos);
+                    return None;
+                };
+                if op == "=" {
+                    let value_ty = self.expression(value)?;
+                    self.convert(value_ty, ty, pos)?;
+                } else {
+                    self.code.push(op::LOCAL_GET);
+                    leb_u32(index, &mut self.code);
+                    let value_ty = self.expression(value)?;
+                    self.convert(value_ty, ty, pos)?;
+                    emit_arith_binary(self, &op[..op.len() - 1], ty, pos)?;
+                }
+                self.code.push(op::LOCAL_TEE);
+                leb_u32(index, &mut self.code);
+                Some(ty)
+            }
+            ExpressionKind::Call { callee, args } => {
+                let ExpressionKind::Identifier(name) = &callee.kind else {
+                    self.unsupported("calls to a non-identifier callee", pos);
+                    return None;
+                };
+                if name == "print" {
+                    if args.len() != 1 {
+                        self.unsupported("print with other than one argument", pos);
+                        return None;
+                    }
+                    let ty = self.expression(&args[0])?;
+                    self.code.push(op::CALL);
+                    match ty {
+                        ValType::I32 => leb_u32(0, &mut self.code),
+                        ValType::F64 => leb_u32(1, &mut self.code),
+                    }
+                    return None;
+                }
+                let Some(info_index) = self.functions.get(name).map(|f| f.index) else {
+                    self.unsupported(&format!("call to unknown function '{}'", name), pos);
+                    return None;
+                };
+                let result = self.functions[name].sig.result;
+                let param_types = self.functions[name].sig.params.clone();
+                for (arg, param_ty) in args.iter().zip(&param_types) {
+                    let arg_ty = self.expression(arg)?;
+                    self.convert(arg_ty, *param_ty, pos)?;
+                }
+                self.code.push(op::CALL);
+                leb_u32(info_index + 2, &mut self.code);
+                result
+            }
+            _ => {
+                self.unsupported("this expression", pos);
+                None
+            }
+        }
+    }
+
+    fn binary(&mut self, left: &Expression, op: &str, right: &Expression, pos: Position) -> Option<ValType> {
+        let left_ty = self.expression(left)?;
+        let mid_mark = self.code.len();
+        let right_ty = self.expression(right)?;
+        let ty = left_ty.widen(right_ty);
+        // The wider operand's own code is already emitted; a mismatched
+        // narrower operand needs its `f64.convert_i32_s` spliced in right
+        // after its value (before `mid_mark` for the left operand, or at
+        // the very end — equivalent to a plain push — for the right).
+        if left_ty != ty {
+            self.code.insert(mid_mark, op::F64_CONVERT_I32_S);
+        }
+        if right_ty != ty {
+            self.code.push(op::F64_CONVERT_I32_S);
+        }
+        emit_arith_binary(self, op, ty, pos)
+    }
+
+    /// Converts a value of type `from` already on the stack to `to`, if
+    /// they differ. Only `I32 -> F64` (widening) is supported today —
+    /// the other direction would need a truncation opcode this backend
+    /// doesn't have a use for yet.
+    fn convert(&mut self, from: ValType, to: ValType, pos: Position) -> Option<()> {
+        if from == to {
+            return Some(());
+        }
+        if from == ValType::I32 && to == ValType::F64 {
+            self.code.push(op::F64_CONVERT_I32_S);
+            return Some(());
+        }
+        self.unsupported("narrowing a float to an int", pos);
+        None
+    }
+}
+
+fn emit_arith_binary(compiler: &mut FunctionCompiler, op: &str, ty: ValType, pos: Position) -> Option<ValType> {
+    let opcode = match (op, ty) {
+        ("+", ValType::I32) => op::I32_ADD,
+        ("+", ValType::F64) => op::F64_ADD,
+        ("-", ValType::I32) => op::I32_SUB,
+        ("-", ValType::F64) => op::F64_SUB,
+        ("*", ValType::I32) => op::I32_MUL,
+        ("*", ValType::F64) => op::F64_MUL,
+        ("/", ValType::I32) => op::I32_DIV_S,
+        ("/", ValType::F64) => op::F64_DIV,
+        ("%", ValType::I32) => op::I32_REM_S,
+        ("==", ValType::I32) => op::I32_EQ,
+        ("==", ValType::F64) => op::F64_EQ,
+        ("!=", ValType::I32) => op::I32_NE,
+        ("!=", ValType::F64) => op::F64_NE,
+        ("<", ValType::I32) => op::I32_LT_S,
+        ("<", ValType::F64) => op::F64_LT,
+        (">", ValType::I32) => op::I32_GT_S,
+        (">", ValType::F64) => op::F64_GT,
+        ("<=", ValType::I32) => op::I32_LE_S,
+        ("<=", ValType::F64) => op::F64_LE,
+        (">=", ValType::I32) => op::I32_GE_S,
+        (">=", ValType::F64) => op::F64_GE,
+        ("&&", ValType::I32) => op::I32_AND,
+        ("||", ValType::I32) => op::I32_OR,
+        _ => {
+            compiler.unsupported(&format!("operator '{}' on this type", op), pos);
+            return None;
+        }
+    };
+    compiler.code.push(opcode);
+    let result_is_comparison = matches!(op, "==" | "!=" | "<" | ">" | "<=" | ">=");
+    Some(if result_is_comparison { ValType::I32 } else { ty })
+}
+
@@ -0,0 +1,54 @@
+//! Shared source-location types used across the lexer, parser and diagnostics.
+
+/// A line/column location within a single source file, alongside the
+/// absolute byte offset from the start of that file. `line`/`column`
+/// drive diagnostic rendering and stay 1-indexed as before; `offset` is
+/// what lets a [`SourceMap`] or a byte-oriented consumer (an editor's
+/// incremental re-parse, say) locate the same point without rescanning
+/// from the top of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize, offset: usize) -> Self {
+        Self { line, column, offset }
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Converts an absolute byte offset back into a [`Position`] for some
+/// source text, the inverse of the offset every [`Position`] already
+/// carries. Built once per file and reused, rather than rescanning from
+/// the start on every lookup.
+pub struct SourceMap {
+    source: String,
+    /// Byte offset of the start of each line, indexed by `line - 1`.
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(index, _)| index + 1));
+        Self { source: source.to_string(), line_starts }
+    }
+
+    /// The 1-indexed line/column for `offset`, which must land on a `char`
+    /// boundary (every offset a [`crate::lexer::Lexer`] produces does).
+    /// Clamped to the end of the source if `offset` runs past it.
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset).max(1) - 1;
+        let column = self.source[self.line_starts[line]..offset].chars().count() + 1;
+        Position::new(line + 1, column, offset)
+    }
+}
@@ -0,0 +1,273 @@
+//! A human-facing explanation for each diagnostic code, for `matcha explain
+//! <code>`. Keeping this separate from where each code is actually raised
+//! means adding a new call site never has to touch prose, and the catalog
+//! can be skimmed (or grepped for a stale entry) without wading through
+//! every phase that reports a diagnostic.
+
+/// One catalog entry: a short title, a longer explanation of what the
+/// code means and why the compiler reports it, and a minimal example that
+/// triggers it.
+pub struct Entry {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub explanation: &'static str,
+    pub example: &'static str,
+}
+
+const ENTRIES: &[Entry] = &[
+    Entry {
+        code: "E001",
+        title: "undefined symbol",
+        explanation: "A name was referenced that no `let`, function, struct, enum, interface or \
+            import brought into scope. This usually means a typo, a missing `import`, or a \
+            declaration that comes after the point it's used in a context that doesn't hoist.",
+        example: "func main(): Int32 {\n    return unknown_name;\n}",
+    },
+    Entry {
+        code: "E010",
+        title: "unreachable declaration",
+        explanation: "A top-level function, struct or global constant is never called, \
+            constructed or read anywhere reachable from `main` or an `export`. It's still \
+            compiled, just flagged as dead weight; pass `--strip-dead-code` to drop it instead.",
+        example: "func unused(): Int32 {\n    return 1;\n}\n\nfunc main(): Int32 {\n    return 0;\n}",
+    },
+    Entry {
+        code: "E011",
+        title: "unused variable",
+        explanation: "A `let` binding inside a function body is never read after it's declared. \
+            Silence this one declaration with a leading underscore, or the whole lint with \
+            `-Aunused-variable`.",
+        example: "func main(): Int32 {\n    let x: Int32 = 1;\n    return 0;\n}",
+    },
+    Entry {
+        code: "E012",
+        title: "unused import",
+        explanation: "An `import` brings a name into scope that nothing in the module ever \
+            references. Remove it, or silence the lint with `-Aunused-import`.",
+        example: "import somemodule;\n\nfunc main(): Int32 {\n    return 0;\n}",
+    },
+    Entry {
+        code: "E013",
+        title: "unused parameter",
+        explanation: "A function or lambda parameter is never read in its body. Prefix it with \
+            an underscore if it's intentionally unused (e.g. to satisfy an interface), or \
+            silence the lint with `-Aunused-parameter`.",
+        example: "func ignore(x: Int32): Int32 {\n    return 0;\n}",
+    },
+    Entry {
+        code: "E014",
+        title: "shadowed binding",
+        explanation: "A `let` or parameter reuses the name of a binding already visible from an \
+            enclosing scope, hiding it for the rest of this one. Often a copy-paste leftover; \
+            rename the new binding, or silence the lint with `-Ashadowing`.",
+        example: "func outer(x: Int32): Int32 {\n    let x: Int32 = 0;\n    return x;\n}",
+    },
+    Entry {
+        code: "E015",
+        title: "unreachable code",
+        explanation: "A statement can never run: it follows a `return`, `break` or `continue` \
+            in the same block, or sits in a branch whose condition constant-folds to the literal \
+            that rules it out. Silence this one declaration by restructuring the code, or the \
+            whole lint with `-Aunreachable-code`.",
+        example: "func main(): Int32 {\n    return 0;\n    let x: Int32 = 1;\n}",
+    },
+    Entry {
+        code: "E016",
+        title: "lossy numeric conversion",
+        explanation: "A binary operation implicitly promoted two numeric operands across a \
+            precision or signedness boundary (e.g. `Int8` widened alongside `Int32`, or \
+            `Int32` mixed with `UInt32`) rather than both sides already sharing a type. Make \
+            the conversion explicit at the source, or silence the lint with \
+            `-Alossy-conversion`.",
+        example: "func main(): Int32 {\n    let a: Int32 = 1;\n    let b: UInt32 = 2;\n    return a + b;\n}",
+    },
+    Entry {
+        code: "E017",
+        title: "use of deprecated symbol",
+        explanation: "A direct, unqualified call names a function this module itself declared \
+            `@deprecated` (optionally with a message, `@deprecated(\"...\")`). Switch to \
+            whatever replaces it, or silence the lint with `-Adeprecated`.",
+        example: "@deprecated(\"use new_api instead\")\nfunc old_api(): Int32 {\n    return 0;\n}\n\nfunc main(): Int32 {\n    return old_api();\n}",
+    },
+    Entry {
+        code: "E100",
+        title: "syntax error",
+        explanation: "The parser couldn't make sense of the token stream at this point -- a \
+            missing delimiter, an unexpected keyword, or a malformed expression. The message \
+            names what the parser expected to see instead.",
+        example: "func main(): Int32 {\n    return\n}",
+    },
+    Entry {
+        code: "E200",
+        title: "type mismatch",
+        explanation: "Two types were expected to unify -- e.g. a value assigned to a binding, \
+            passed as an argument, or returned from a function -- but don't.",
+        example: "func main(): Int32 {\n    return \"not an int\";\n}",
+    },
+    Entry {
+        code: "E201",
+        title: "type error in expression",
+        explanation: "An expression's type couldn't be reconciled with how it's used -- most \
+            often an operator applied to operand types it isn't defined for.",
+        example: "func main(): Int32 {\n    return 1 + \"two\";\n}",
+    },
+    Entry {
+        code: "E202",
+        title: "wrong argument count or type",
+        explanation: "A call passed a different number of arguments than the callee declares, \
+            or an argument whose type doesn't match the corresponding parameter.",
+        example: "func add(a: Int32, b: Int32): Int32 {\n    return a + b;\n}\n\nfunc main(): Int32 {\n    return add(1);\n}",
+    },
+    Entry {
+        code: "E203",
+        title: "invalid member access",
+        explanation: "A `.field` or `.method` access named something that doesn't exist on the \
+            receiver's type, or was used on a type that has no members at all.",
+        example: "func main(): Int32 {\n    let x: Int32 = 1;\n    return x.missing;\n}",
+    },
+    Entry {
+        code: "E204",
+        title: "non-exhaustive match",
+        explanation: "A `match` over an enum doesn't have an arm (or a catch-all `_`/binding) \
+            for every variant. The message names each variant left uncovered.",
+        example: "enum Color { Red, Green, Blue }\n\nfunc name(c: Color): String {\n    match (c) {\n        Color.Red => \"red\",\n        Color.Green => \"green\",\n    }\n}",
+    },
+    Entry {
+        code: "E205",
+        title: "unreachable match arm",
+        explanation: "A `match` arm can never run: an earlier arm already tests the same enum \
+            variant, or a catch-all (`_`, or a plain binding) above it already handles every \
+            remaining case.",
+        example: "enum Color { Red, Green, Blue }\n\nfunc name(c: Color): String {\n    match (c) {\n        Color.Red => \"red\",\n        _ => \"other\",\n        Color.Green => \"green\",\n    }\n}",
+    },
+    Entry {
+        code: "E206",
+        title: "missing return",
+        explanation: "A function declares a non-`Void` return type but has at least one \
+            execution path that falls off the end of its body instead of ending in a `return` \
+            with a value.",
+        example: "func abs(x: Int32): Int32 {\n    if (x < 0) {\n        return -x;\n    }\n}",
+    },
+    Entry {
+        code: "E207",
+        title: "not a compile-time constant",
+        explanation: "A global `const`'s initializer, or an enum variant's value, has to be \
+            evaluable at compile time -- literals, arithmetic and boolean operators over them, \
+            string concatenation, and references to other `const`s. A call, a field access or a \
+            reference to a runtime variable isn't.",
+        example: "func one(): Int32 {\n    return 1;\n}\n\nconst X: Int32 = one();",
+    },
+    Entry {
+        code: "E208",
+        title: "cyclic constant reference",
+        explanation: "A `const`'s initializer refers back to itself, directly or through one or \
+            more other `const`s, so there's no order in which they could all be evaluated.",
+        example: "const A: Int32 = B;\nconst B: Int32 = A;",
+    },
+    Entry {
+        code: "E209",
+        title: "duplicate enum discriminant",
+        explanation: "Two variants of the same enum evaluated to the same underlying integer \
+            value, whether from an explicit `= value` or from auto-numbering filling one in. \
+            Give each variant a distinct value.",
+        example: "enum Status {\n    Ok = 0,\n    Ready = 0,\n}",
+    },
+    Entry {
+        code: "E210",
+        title: "integer literal out of range",
+        explanation: "An integer literal doesn't fit in the width its inferred or annotated \
+            type allows.",
+        example: "func main(): Int32 {\n    let x: Int32 = 99999999999999;\n    return x;\n}",
+    },
+    Entry {
+        code: "E211",
+        title: "no operator method for type",
+        explanation: "A binary operator or index expression was used with a struct operand, but \
+            that struct's impls define no method for it (`add` for `+`, `sub` for `-`, `mul` for \
+            `*`, `div` for `/`, `rem` for `%`, `eq` for `==`/`!=`, `index` for `[]`). Add the \
+            matching method to an `impl ... for` block, or use a numeric/`Bool` operand instead.",
+        example: "struct Vec2 { x: Int32, y: Int32 }\n\nfunc main(): Int32 {\n    let a = Vec2 { x: 1, y: 2 };\n    let b = Vec2 { x: 3, y: 4 };\n    let c = a + b;\n    return 0;\n}",
+    },
+    Entry {
+        code: "E212",
+        title: "invalid assignment target",
+        explanation: "The left side of `=` or a compound assignment (`+=`, `-=`, ...) has to be a \
+            variable, a field access, or an index expression -- something that names a place to \
+            store the value. A literal, call, or arbitrary expression can't be assigned to.",
+        example: "func main(): Int32 {\n    1 + 1 = 2;\n    return 0;\n}",
+    },
+    Entry {
+        code: "E213",
+        title: "type not FFI-safe",
+        explanation: "An `extern` function's parameter or return type has no well-defined layout \
+            on the other side of the native boundary. Only the fixed-width numeric types, `Bool`, \
+            `Char` and (in return position) `Void` are allowed -- `String`, `Array`, structs, \
+            enums and closures all have a managed representation this compiler doesn't define a \
+            C-compatible layout for.",
+        example: "extern \"C\" func puts(s: String): Int32;",
+    },
+    Entry {
+        code: "E214",
+        title: "undefined interface bound",
+        explanation: "A function's `<T: Bound>` type-parameter list named a bound that isn't a \
+            declared interface. Note that this compiler has no generic instantiation mechanism -- \
+            a bound is only checked for existing here, never enforced against the concrete type a \
+            call site actually uses.",
+        example: "func max<T: Ordered>(a: T, b: T): T {\n    return a;\n}",
+    },
+    Entry {
+        code: "E215",
+        title: "wrong number of type arguments",
+        explanation: "A generic struct was named with a `<...>` argument list whose length \
+            doesn't match the number of type parameters in its declaration -- too few, too many, \
+            or (for a non-generic struct) any at all.",
+        example: "struct Box<T> { value: T }\n\nfunc main(): Int32 {\n    let b: Box<Int32, Int32> = Box { value: 1 };\n    return 0;\n}",
+    },
+    Entry {
+        code: "E216",
+        title: "array literal length mismatch",
+        explanation: "A `let` or return whose declared type is a fixed-size array (`T[N]`) was \
+            given an array literal with a different number of elements than `N`.",
+        example: "func main(): Int32 {\n    let a: Int32[3] = [1, 2];\n    return a[0];\n}",
+    },
+    Entry {
+        code: "E300",
+        title: "bytecode compilation error",
+        explanation: "The bytecode backend couldn't lower a construct from the AST -- usually a \
+            feature the interpreter/typechecker accept but this backend doesn't yet support.",
+        example: "(backend-specific; see the reported message for the unsupported construct)",
+    },
+    Entry {
+        code: "E301",
+        title: "interpreter runtime error",
+        explanation: "The tree-walking interpreter hit a failure while executing an otherwise \
+            well-typed program -- e.g. a division by zero or an out-of-bounds index.",
+        example: "func main(): Int32 {\n    return 1 / 0;\n}",
+    },
+    Entry {
+        code: "E302",
+        title: "wasm compilation error",
+        explanation: "The WebAssembly backend couldn't lower a construct from the AST -- usually \
+            a feature this backend doesn't yet support.",
+        example: "(backend-specific; see the reported message for the unsupported construct)",
+    },
+    Entry {
+        code: "E303",
+        title: "IR lowering error",
+        explanation: "The mid-level IR lowering pass couldn't translate a construct from the \
+            AST.",
+        example: "(backend-specific; see the reported message for the unsupported construct)",
+    },
+    Entry {
+        code: "E304",
+        title: "IR pass error",
+        explanation: "An IR optimization pass (e.g. SSA construction, constant folding) hit an \
+            invariant it expects to always hold and didn't.",
+        example: "(backend-specific; see the reported message for the failed invariant)",
+    },
+];
+
+/// The catalog entry for `code`, if one exists.
+pub fn lookup(code: &str) -> Option<&'static Entry> {
+    ENTRIES.iter().find(|entry| entry.code == code)
+}
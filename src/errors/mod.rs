@@ -0,0 +1,516 @@
+//! Diagnostic reporting. A [`Diagnostic`] can be rendered the moment it's
+//! constructed via [`Diagnostic::report`], or collected into a
+//! [`DiagnosticBag`] and rendered together, sorted by where it appears in
+//! the source, once a phase (or several sharing one bag) has finished.
+//! Either way, each compiler phase still tracks its own `had_error` flag.
+//!
+//! Every reported diagnostic is also tallied in a pair of process-wide
+//! counters, so a driver can print a "2 errors, 1 warning"-style summary
+//! without threading a collector through every phase that can report one.
+//! [`set_format`] switches [`Diagnostic::report`] between that human-
+//! readable line and a JSON-lines form for editors and CI, the same way
+//! the counters are process-wide state consulted at report time rather
+//! than threaded through every call site that can produce a diagnostic.
+//! [`Diagnostic::report`] also drops exact repeats at the same span and,
+//! past [`set_error_limit`], stops printing further errors -- both to
+//! keep a cascading failure from burying the diagnostic that caused it.
+
+use crate::common::Position;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub mod catalog;
+
+static ERRORS: AtomicUsize = AtomicUsize::new(0);
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Every `(file, position, code, message)` already reported, so cascading
+/// failures that raise the same diagnostic at the same span over and over
+/// -- a parser stuck re-reporting a missing `;` at the same token, say --
+/// only print once. A `Vec` rather than a `HashSet` because a compile
+/// rarely reports more than a few dozen diagnostics; linear-scanning that
+/// is simpler than pulling in `Hash` for `Position`'s float-free fields.
+static SEEN: Mutex<Vec<(String, Position, String, String)>> = Mutex::new(Vec::new());
+
+/// How many errors [`Diagnostic::report`] will still print before
+/// suppressing the rest. Unset (the default) means no limit.
+static ERROR_LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+static ERROR_LIMIT_NOTICE_PRINTED: AtomicBool = AtomicBool::new(false);
+
+/// Caps how many errors [`Diagnostic::report`] prints before it starts
+/// silently swallowing the rest (still counting them, just not printing)
+/// -- for a driver's `--error-limit=` flag, so a badly broken file doesn't
+/// scroll the real problem off the terminal under a hundred knock-on
+/// errors.
+pub fn set_error_limit(limit: usize) {
+    ERROR_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// How [`Diagnostic::report`] renders each diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `error[E200]: message (file:line:column)`.
+    Text,
+    /// One JSON object per diagnostic, newline-delimited, with `severity`,
+    /// `code`, `message`, `file`, `span`, `notes` and `help` (populated
+    /// from [`Diagnostic::with_note`]/[`Diagnostic::with_help`]), and an
+    /// empty `labels` array reserved for secondary spans later.
+    Json,
+}
+
+static FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the format every subsequent [`Diagnostic::report`] call uses.
+/// Defaults to [`Format::Text`]; a driver calls this once, up front, from
+/// a `--error-format=` flag.
+pub fn set_format(format: Format) {
+    FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// The format every subsequent [`Diagnostic::report`] call will use.
+pub fn format() -> Format {
+    match FORMAT.load(Ordering::Relaxed) {
+        1 => Format::Json,
+        _ => Format::Text,
+    }
+}
+
+/// Whether [`Diagnostic::report`] wraps its `Format::Text` output in ANSI
+/// color codes -- a driver's `--color=` flag. `Auto` (the default) colors
+/// only when stderr looks like an interactive terminal and `NO_COLOR`
+/// isn't set, matching the convention most CLI tools follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the color mode every subsequent [`Diagnostic::report`] call uses.
+/// Defaults to [`ColorMode::Auto`]; a driver calls this once, up front,
+/// from a `--color=` flag.
+pub fn set_color_mode(mode: ColorMode) {
+    COLOR_MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+/// Resolves the current [`ColorMode`] to a yes/no answer for this report
+/// call, checking `NO_COLOR` and whether stderr is a terminal when the
+/// mode is [`ColorMode::Auto`].
+fn color_enabled() -> bool {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        1 => true,
+        2 => false,
+        _ => std::env::var_os("NO_COLOR").is_none() && std::io::stderr().is_terminal(),
+    }
+}
+
+/// Wraps `text` in `code` (an ANSI SGR parameter, e.g. `"31"` for red) when
+/// [`color_enabled`], otherwise returns it unchanged.
+fn colorize(text: &str, code: &str) -> String {
+    if color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Error/warning totals tallied since the last [`reset`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl Counts {
+    pub fn is_empty(&self) -> bool {
+        self.errors == 0 && self.warnings == 0
+    }
+
+    /// Renders as `"2 errors, 1 warning"`, `"1 error"`, `"3 warnings"` —
+    /// whichever counts are non-zero, singularized where it applies.
+    pub fn summary(&self) -> String {
+        fn plural(count: usize, word: &str) -> String {
+            format!("{} {}{}", count, word, if count == 1 { "" } else { "s" })
+        }
+        match (self.errors, self.warnings) {
+            (0, 0) => String::new(),
+            (errors, 0) => plural(errors, "error"),
+            (0, warnings) => plural(warnings, "warning"),
+            (errors, warnings) => format!("{}, {}", plural(errors, "error"), plural(warnings, "warning")),
+        }
+    }
+}
+
+/// Zeroes the running error/warning counters. A driver calls this before
+/// each independent compile so its summary reflects just that run.
+pub fn reset() {
+    ERRORS.store(0, Ordering::Relaxed);
+    WARNINGS.store(0, Ordering::Relaxed);
+    SEEN.lock().unwrap().clear();
+    ERROR_LIMIT_NOTICE_PRINTED.store(false, Ordering::Relaxed);
+}
+
+/// The running totals since the last [`reset`].
+pub fn counts() -> Counts {
+    Counts {
+        errors: ERRORS.load(Ordering::Relaxed),
+        warnings: WARNINGS.load(Ordering::Relaxed),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A diagnostic paired with the file it was reported against — the same
+/// `(Diagnostic, file)` combination every direct [`Diagnostic::report`]
+/// call already took, just not rendered yet.
+struct Located {
+    diagnostic: Diagnostic,
+    file: String,
+}
+
+/// Collects diagnostics instead of rendering each one the instant it's
+/// constructed, so a phase (or several, sharing one bag) can report
+/// everything it found together, ordered by where it appears in the
+/// source rather than the order the lexer, parser and resolver happened
+/// to visit things — a parse error three lines in doesn't have to print
+/// before a resolver error on line one just because parsing runs first.
+#[derive(Default)]
+pub struct DiagnosticBag {
+    diagnostics: Vec<Located>,
+}
+
+impl DiagnosticBag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic, file: impl Into<String>) {
+        self.diagnostics.push(Located {
+            diagnostic,
+            file: file.into(),
+        });
+    }
+
+    pub fn had_error(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|located| located.diagnostic.severity == Severity::Error)
+    }
+
+    /// How many diagnostics have been collected so far -- lets a caller
+    /// mark a point in the bag (e.g. "before the resolver ran") and later
+    /// slice out just what was pushed after it, without cloning via
+    /// [`Self::entries`] just to measure it.
+    pub(crate) fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Every collected `(file, Diagnostic)` pair, cloned out for a caller
+    /// that wants the structured data rather than [`Self::report_all`]'s
+    /// rendered text -- `matcha lsp`'s `textDocument/publishDiagnostics`,
+    /// for one.
+    pub fn entries(&self) -> Vec<(String, Diagnostic)> {
+        self.diagnostics
+            .iter()
+            .map(|located| (located.file.clone(), located.diagnostic.clone()))
+            .collect()
+    }
+
+    /// Renders every collected diagnostic — via [`Diagnostic::report`], so
+    /// counting and `--error-format` both still apply — sorted by file
+    /// and then by position. Diagnostics collected but never rendered
+    /// (e.g. a debug dump that only cares about the resulting symbol
+    /// table) simply never reach the counters or stderr.
+    pub fn report_all(&mut self) {
+        self.diagnostics
+            .sort_by(|a, b| (a.file.as_str(), a.diagnostic.position).cmp(&(b.file.as_str(), b.diagnostic.position)));
+        for located in &self.diagnostics {
+            located.diagnostic.report(&located.file);
+        }
+        self.diagnostics.clear();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+    pub position: Position,
+    notes: Vec<String>,
+    help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &str, message: impl Into<String>, position: Position) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: code.to_string(),
+            message: message.into(),
+            position,
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    pub fn warning(code: &str, message: impl Into<String>, position: Position) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code: code.to_string(),
+            message: message.into(),
+            position,
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    /// Attaches a secondary note, rendered on its own `note:` line under the
+    /// primary message -- for context (why this matters, what triggered it)
+    /// that doesn't fit in the one-line diagnostic itself. Chainable, so a
+    /// call site can build the whole diagnostic in one expression.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Attaches a suggested fix, rendered on its own `help:` line under the
+    /// primary message and any notes -- e.g. pointing at the specific
+    /// change that resolves the diagnostic.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Serializes this diagnostic, plus the file it was reported against,
+    /// into one tab-separated line for [`crate::utils::incremental`] to
+    /// write into a `.mtc` cache and replay verbatim on a later cache
+    /// hit -- a cache hit skips resolving/type-checking entirely, so
+    /// without this the diagnostics it found the first time would simply
+    /// never be reprinted. Not the same format as [`Self::to_json`]:
+    /// that one is a stable, external, per-diagnostic wire format for
+    /// editors and CI; this one only ever needs to round-trip through
+    /// [`Self::from_cache_line`] within the same compiler build, so it's
+    /// as simple as the field set allows.
+    pub(crate) fn to_cache_line(&self, file: &str) -> String {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            severity,
+            self.code,
+            self.position.line,
+            self.position.column,
+            self.position.offset,
+            escape_cache_field(file),
+            escape_cache_field(&self.message),
+            join_cache_list(&self.notes),
+            join_cache_list(&self.help),
+        )
+    }
+
+    /// The inverse of [`Self::to_cache_line`]. `None` if `line` isn't
+    /// well-formed -- a cache written by a different compiler version, or
+    /// simply corrupted on disk -- so the caller can treat it exactly
+    /// like any other cache-format mismatch.
+    pub(crate) fn from_cache_line(line: &str) -> Option<(String, Diagnostic)> {
+        let mut parts = line.split('\t');
+        let severity = match parts.next()? {
+            "error" => Severity::Error,
+            "warning" => Severity::Warning,
+            _ => return None,
+        };
+        let code = parts.next()?.to_string();
+        let line_no: usize = parts.next()?.parse().ok()?;
+        let column: usize = parts.next()?.parse().ok()?;
+        let offset: usize = parts.next()?.parse().ok()?;
+        let file = unescape_cache_field(parts.next()?);
+        let message = unescape_cache_field(parts.next()?);
+        let notes = split_cache_list(parts.next()?);
+        let help = split_cache_list(parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        Some((
+            file,
+            Diagnostic {
+                severity,
+                code,
+                message,
+                position: Position::new(line_no, column, offset),
+                notes,
+                help,
+            },
+        ))
+    }
+
+    pub fn report(&self, file: &str) {
+        let key = (file.to_string(), self.position, self.code.clone(), self.message.clone());
+        {
+            let mut seen = SEEN.lock().unwrap();
+            if seen.contains(&key) {
+                return;
+            }
+            seen.push(key);
+        }
+
+        let tag = match self.severity {
+            Severity::Error => {
+                let errors_so_far = ERRORS.fetch_add(1, Ordering::Relaxed) + 1;
+                if errors_so_far > ERROR_LIMIT.load(Ordering::Relaxed) {
+                    if !ERROR_LIMIT_NOTICE_PRINTED.swap(true, Ordering::Relaxed) {
+                        eprintln!(
+                            "error: too many errors reported (limit {}); further errors are suppressed",
+                            ERROR_LIMIT.load(Ordering::Relaxed)
+                        );
+                    }
+                    return;
+                }
+                "error"
+            }
+            Severity::Warning => {
+                WARNINGS.fetch_add(1, Ordering::Relaxed);
+                "warning"
+            }
+        };
+        match format() {
+            Format::Text => {
+                let tag_color = match self.severity {
+                    Severity::Error => "31",   // red
+                    Severity::Warning => "33", // yellow
+                };
+                eprintln!(
+                    "{}[{}]: {} ({}:{})",
+                    colorize(tag, tag_color),
+                    self.code,
+                    self.message,
+                    file,
+                    self.position
+                );
+                if let Some(snippet) = source_line(file, self.position.line) {
+                    eprintln!("{}", snippet);
+                    eprintln!(
+                        "{}{}",
+                        " ".repeat(self.position.column.saturating_sub(1)),
+                        colorize("^", tag_color)
+                    );
+                }
+                for note in &self.notes {
+                    eprintln!("{} {}", colorize("note:", "36"), note); // cyan
+                }
+                for help in &self.help {
+                    eprintln!("{} {}", colorize("help:", "32"), help); // green
+                }
+            }
+            Format::Json => eprintln!("{}", self.to_json(tag, file)),
+        }
+    }
+
+    /// One line of the `Format::Json` form. `labels` is always empty for
+    /// now — this diagnostic model has no secondary spans yet, but the
+    /// field is reserved so consumers don't need a schema migration once
+    /// it does.
+    fn to_json(&self, severity: &str, file: &str) -> String {
+        format!(
+            "{{\"severity\":\"{}\",\"code\":{},\"message\":{},\"file\":{},\"span\":{{\"line\":{},\"column\":{},\"offset\":{}}},\"labels\":[],\"notes\":{},\"help\":{}}}",
+            severity,
+            json_string(&self.code),
+            json_string(&self.message),
+            json_string(file),
+            self.position.line,
+            self.position.column,
+            self.position.offset,
+            json_string_array(&self.notes),
+            json_string_array(&self.help),
+        )
+    }
+}
+
+/// The 1-indexed `line` of `file`, for the caret underline under a text
+/// diagnostic. `None` for anything that isn't a readable path on disk —
+/// `compile_source`'s in-memory `name` label, for one, which was never
+/// meant to be read back — so a diagnostic still renders, just without
+/// the snippet.
+fn source_line(file: &str, line: usize) -> Option<String> {
+    let contents = crate::utils::vfs::read_to_string(file)?;
+    contents.lines().nth(line.checked_sub(1)?).map(str::to_string)
+}
+
+/// Escapes `\`, tab and newline out of a [`Diagnostic::to_cache_line`]
+/// field, so a stray one in a message can't be mistaken for the `\t`
+/// field delimiter or the `\x1f` [`join_cache_list`] item separator.
+fn escape_cache_field(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape_cache_field(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('t') => out.push('\t'),
+                Some('n') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Joins a diagnostic's `notes`/`help` list into one `.mtc`-cache field,
+/// each item escaped and separated by `\x1f` (a control character no
+/// generated diagnostic text contains) -- the inverse of
+/// [`split_cache_list`].
+fn join_cache_list(items: &[String]) -> String {
+    items.iter().map(|item| escape_cache_field(item)).collect::<Vec<_>>().join("\u{1f}")
+}
+
+fn split_cache_list(field: &str) -> Vec<String> {
+    if field.is_empty() {
+        Vec::new()
+    } else {
+        field.split('\u{1f}').map(unescape_cache_field).collect()
+    }
+}
+
+fn json_string_array(items: &[String]) -> String {
+    let mut out = String::from("[");
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&json_string(item));
+    }
+    out.push(']');
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,466 @@
+//! A tree-walking interpreter that runs a resolved [`Module`] directly,
+//! independent of [`crate::backend`]'s bytecode compiler and VM. It trades
+//! the bytecode backend's speed for simplicity: no lowering pass, no
+//! instruction format, just a [`Visitor`] walking the AST evaluating as it
+//! goes — useful for running small programs (or, eventually, a REPL)
+//! without waiting on a real backend to be finished.
+
+use crate::ast::{Expression, ExpressionKind, Function, LiteralValue, Module, Statement, StatementKind};
+use crate::common::Position;
+use crate::errors::Diagnostic;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Char(char),
+    Void,
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Char(c) => write!(f, "{}", c),
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+/// What a statement handed back up to its enclosing block, so `return`,
+/// `break` and `continue` can unwind through nested blocks without Rust
+/// exceptions.
+enum Signal {
+    None,
+    Return(Value),
+    Break,
+    Continue,
+}
+
+/// A tree-walking `Expression`/`Statement` visitor. Expressions evaluate
+/// to a [`Value`]; statements only ever produce a control-flow [`Signal`]
+/// internally, so the public surface is `visit_expression` alone —
+/// `Interpreter` runs statements through its own `execute`.
+pub trait Visitor {
+    fn visit_expression(&mut self, expr: &Expression) -> Value;
+}
+
+struct Frame {
+    variables: HashMap<String, Value>,
+}
+
+pub struct Interpreter {
+    functions: HashMap<String, Arc<Function>>,
+    frames: Vec<Frame>,
+    had_error: bool,
+    file: String,
+}
+
+impl Interpreter {
+    pub fn new(file: impl Into<String>) -> Self {
+        Self {
+            functions: HashMap::new(),
+            frames: vec![Frame {
+                variables: HashMap::new(),
+            }],
+            had_error: false,
+            file: file.into(),
+        }
+    }
+
+    fn unsupported(&mut self, what: &str, position: Position) -> Value {
+        self.had_error = true;
+        Diagnostic::error("E301", format!("'{}' is not yet supported by the interpreter", what), position)
+            .report(&self.file);
+        Value::Void
+    }
+
+    /// Executes one of the built-in string methods the typechecker
+    /// already validated the shape of (`src/semantic/mod.rs`'s
+    /// `check_string_intrinsic`). `split` isn't handled here even though
+    /// it typechecks: arrays have no runtime representation yet in this
+    /// interpreter, so there's nowhere to put its result.
+    fn call_string_method(&mut self, s: &str, name: &str, args: Vec<Value>, position: Position) -> Value {
+        match (name, args.as_slice()) {
+            ("len", []) => Value::Int(s.chars().count() as i64),
+            ("substring", [Value::Int(start), Value::Int(end)]) => {
+                let chars: Vec<char> = s.chars().collect();
+                let start = (*start).clamp(0, chars.len() as i64) as usize;
+                let end = (*end).clamp(start as i64, chars.len() as i64) as usize;
+                Value::String(chars[start..end].iter().collect())
+            }
+            ("contains", [Value::String(needle)]) => Value::Bool(s.contains(needle.as_str())),
+            ("to_int", []) => Value::Int(s.trim().parse::<i64>().unwrap_or(0)),
+            ("split", [Value::String(_)]) => {
+                self.unsupported("'split' (arrays have no runtime representation yet)", position)
+            }
+            _ => self.unsupported(&format!("string method '{}'", name), position),
+        }
+    }
+
+    fn define(&mut self, name: &str, value: Value) {
+        self.frames.last_mut().expect("at least one frame is always open").variables.insert(name.to_string(), value);
+    }
+
+    fn assign(&mut self, name: &str, value: Value) {
+        for frame in self.frames.iter_mut().rev() {
+            if let Some(slot) = frame.variables.get_mut(name) {
+                *slot = value;
+                return;
+            }
+        }
+        self.define(name, value);
+    }
+
+    fn lookup(&self, name: &str) -> Value {
+        for frame in self.frames.iter().rev() {
+            if let Some(value) = frame.variables.get(name) {
+                return value.clone();
+            }
+        }
+        Value::Void
+    }
+
+    /// Runs `module`'s top-level statements, in order, after registering
+    /// every function declaration so forward/recursive calls resolve.
+    /// Returns whether anything went wrong (an unsupported construct, or
+    /// a runtime failure such as division by zero) the same way the
+    /// resolver and typechecker do: a `had_error` flag rather than a
+    /// `Result`, so one bad statement doesn't abort the whole run.
+    pub fn run(&mut self, module: &Module) -> bool {
+        for statement in &module.statements {
+            if let StatementKind::FunctionDecl(function) = &statement.kind {
+                self.functions.insert(function.name.clone(), Arc::clone(function));
+            }
+        }
+        for statement in &module.statements {
+            if matches!(statement.kind, StatementKind::FunctionDecl(_)) {
+                continue;
+            }
+            self.execute(statement);
+        }
+        self.had_error
+    }
+
+    fn call(&mut self, function: &Arc<Function>, args: Vec<Value>) -> Value {
+        if let Some(extern_info) = &function.extern_info {
+            // Actually loading a native library and calling into it with
+            // an arbitrary signature needs either an external crate
+            // (`libloading`/`libffi`, ruled out -- this crate takes on no
+            // external dependencies) or hand-written per-ABI calling
+            // convention codegen, neither of which exists here yet. Fail
+            // loudly rather than silently returning `Value::Void` for
+            // whatever the declared return type actually is.
+            return self.unsupported(
+                &format!("calling extern \"{}\" function '{}'", extern_info.abi, function.name),
+                function.position,
+            );
+        }
+        self.frames.push(Frame {
+            variables: HashMap::new(),
+        });
+        for (param, arg) in function.params.iter().zip(args) {
+            self.define(&param.name, arg);
+        }
+        let mut result = Value::Void;
+        for statement in &function.body {
+            match self.execute(statement) {
+                Signal::Return(value) => {
+                    result = value;
+                    break;
+                }
+                Signal::None => {}
+                Signal::Break | Signal::Continue => break,
+            }
+        }
+        self.frames.pop();
+        result
+    }
+
+    fn execute(&mut self, statement: &Statement) -> Signal {
+        let pos = statement.position;
+        match &statement.kind {
+            StatementKind::Expression(expr) => {
+                self.visit_expression(expr);
+                Signal::None
+            }
+            StatementKind::Let { name, value, .. } => {
+                let value = match value {
+                    Some(expr) => self.visit_expression(expr),
+                    None => Value::Void,
+                };
+                self.define(name, value);
+                Signal::None
+            }
+            StatementKind::Return(value) => {
+                let value = match value {
+                    Some(expr) => self.visit_expression(expr),
+                    None => Value::Void,
+                };
+                Signal::Return(value)
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let branch = if truthy(&self.visit_expression(condition)) {
+                    Some(then_branch)
+                } else {
+                    else_branch.as_ref()
+                };
+                match branch {
+                    Some(body) => self.execute_block(body),
+                    None => Signal::None,
+                }
+            }
+            StatementKind::While { condition, body } => {
+                while truthy(&self.visit_expression(condition)) {
+                    match self.execute_block(body) {
+                        Signal::Break => break,
+                        Signal::Return(value) => return Signal::Return(value),
+                        Signal::None | Signal::Continue => {}
+                    }
+                }
+                Signal::None
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.frames.push(Frame {
+                    variables: HashMap::new(),
+                });
+                let result = (|| {
+                    if let Some(init) = init {
+                        self.execute(init);
+                    }
+                    loop {
+                        if let Some(condition) = condition {
+                            if !truthy(&self.visit_expression(condition)) {
+                                break;
+                            }
+                        }
+                        match self.execute_block(body) {
+                            Signal::Break => break,
+                            Signal::Return(value) => return Signal::Return(value),
+                            Signal::None | Signal::Continue => {}
+                        }
+                        if let Some(update) = update {
+                            self.visit_expression(update);
+                        }
+                    }
+                    Signal::None
+                })();
+                self.frames.pop();
+                result
+            }
+            StatementKind::Block(statements) => self.execute_block(statements),
+            StatementKind::Break => Signal::Break,
+            StatementKind::Continue => Signal::Continue,
+            StatementKind::FunctionDecl(_) => Signal::None,
+            StatementKind::StructDecl(_)
+            | StatementKind::EnumDecl(_)
+            | StatementKind::InterfaceDecl(_)
+            | StatementKind::ImplBlock(_)
+            | StatementKind::ModuleDecl(_)
+            | StatementKind::Import(_)
+            | StatementKind::Export(_)
+            | StatementKind::ForEach { .. }
+            | StatementKind::Match { .. } => {
+                self.unsupported("this statement", pos);
+                Signal::None
+            }
+            StatementKind::Error => Signal::None,
+        }
+    }
+
+    fn execute_block(&mut self, statements: &[Statement]) -> Signal {
+        self.frames.push(Frame {
+            variables: HashMap::new(),
+        });
+        let mut signal = Signal::None;
+        for statement in statements {
+            signal = self.execute(statement);
+            if !matches!(signal, Signal::None) {
+                break;
+            }
+        }
+        self.frames.pop();
+        signal
+    }
+}
+
+impl Visitor for Interpreter {
+    fn visit_expression(&mut self, expr: &Expression) -> Value {
+        let pos = expr.position;
+        match &expr.kind {
+            ExpressionKind::Literal(literal) => match literal {
+                LiteralValue::Int(n, _) => Value::Int(*n as i64),
+                LiteralValue::Float(n, _) => Value::Float(*n),
+                LiteralValue::String(s) => Value::String(s.clone()),
+                LiteralValue::Char(c) => Value::Char(*c),
+                LiteralValue::Bool(b) => Value::Bool(*b),
+            },
+            ExpressionKind::Identifier(name) => self.lookup(name),
+            ExpressionKind::Grouping(inner) => self.visit_expression(inner),
+            ExpressionKind::Unary { op, operand } if op == "++" || op == "--" => {
+                let ExpressionKind::Identifier(name) = &operand.kind else {
+                    return self.unsupported("prefix operator on a non-variable", pos);
+                };
+                let value = self.lookup(name);
+                let updated = match (op.as_str(), &value) {
+                    ("++", Value::Int(n)) => Value::Int(n + 1),
+                    ("--", Value::Int(n)) => Value::Int(n - 1),
+                    _ => return self.unsupported(&format!("prefix operator '{}'", op), pos),
+                };
+                self.assign(name, updated.clone());
+                updated
+            }
+            ExpressionKind::Unary { op, operand } => {
+                let value = self.visit_expression(operand);
+                match (op.as_str(), value) {
+                    ("-", Value::Int(n)) => Value::Int(-n),
+                    ("-", Value::Float(n)) => Value::Float(-n),
+                    ("!", value) => Value::Bool(!truthy(&value)),
+                    _ => self.unsupported(&format!("unary operator '{}'", op), pos),
+                }
+            }
+            ExpressionKind::Postfix { op, operand } => {
+                let ExpressionKind::Identifier(name) = &operand.kind else {
+                    return self.unsupported("postfix operator on a non-variable", pos);
+                };
+                let value = self.lookup(name);
+                let (result, updated) = match (op.as_str(), &value) {
+                    ("++", Value::Int(n)) => (value.clone(), Value::Int(n + 1)),
+                    ("--", Value::Int(n)) => (value.clone(), Value::Int(n - 1)),
+                    _ => return self.unsupported(&format!("postfix operator '{}'", op), pos),
+                };
+                self.assign(name, updated);
+                result
+            }
+            ExpressionKind::Binary { left, op, right } => {
+                let left = self.visit_expression(left);
+                let right = self.visit_expression(right);
+                self.binary(op, left, right, pos)
+            }
+            ExpressionKind::Assignment { target, op, value } => {
+                let ExpressionKind::Identifier(name) = &target.kind else {
+                    return self.unsupported("assignment to a non-variable target", pos);
+                };
+                let value = self.visit_expression(value);
+                let value = if op == "=" {
+                    value
+                } else {
+                    let current = self.lookup(name);
+                    self.binary(&op[..op.len() - 1], current, value, pos)
+                };
+                self.assign(name, value.clone());
+                value
+            }
+            ExpressionKind::Call { callee, args } => {
+                if let ExpressionKind::Get { object, name } = &callee.kind {
+                    let receiver = self.visit_expression(object);
+                    let args: Vec<Value> = args.iter().map(|arg| self.visit_expression(arg)).collect();
+                    return match receiver {
+                        Value::String(s) => self.call_string_method(&s, name, args, pos),
+                        _ => self.unsupported(&format!("method '{}' on this receiver", name), pos),
+                    };
+                }
+                let ExpressionKind::Identifier(name) = &callee.kind else {
+                    return self.unsupported("calls to a non-identifier callee", pos);
+                };
+                let args: Vec<Value> = args.iter().map(|arg| self.visit_expression(arg)).collect();
+                if name == "print" {
+                    for arg in &args {
+                        println!("{}", arg);
+                    }
+                    return Value::Void;
+                }
+                match self.functions.get(name).cloned() {
+                    Some(function) => self.call(&function, args),
+                    None => self.unsupported(&format!("call to unknown function '{}'", name), pos),
+                }
+            }
+            ExpressionKind::Try(_)
+            | ExpressionKind::Get { .. }
+            | ExpressionKind::Index { .. }
+            | ExpressionKind::ArrayLiteral(_)
+            | ExpressionKind::Tuple(_)
+            | ExpressionKind::MapLiteral(_)
+            | ExpressionKind::StructInit { .. }
+            | ExpressionKind::Lambda { .. }
+            | ExpressionKind::Match { .. } => self.unsupported("this expression", pos),
+            ExpressionKind::Error => Value::Void,
+        }
+    }
+}
+
+impl Interpreter {
+    fn binary(&mut self, op: &str, left: Value, right: Value, position: Position) -> Value {
+        match (op, left, right) {
+            ("+", Value::Int(a), Value::Int(b)) => Value::Int(a + b),
+            ("+", Value::Float(a), Value::Float(b)) => Value::Float(a + b),
+            ("+", Value::String(a), Value::String(b)) => Value::String(a + &b),
+            ("-", Value::Int(a), Value::Int(b)) => Value::Int(a - b),
+            ("-", Value::Float(a), Value::Float(b)) => Value::Float(a - b),
+            ("*", Value::Int(a), Value::Int(b)) => Value::Int(a * b),
+            ("*", Value::Float(a), Value::Float(b)) => Value::Float(a * b),
+            ("/", Value::Int(_), Value::Int(0)) => {
+                self.had_error = true;
+                Diagnostic::error("E302", "division by zero", position)
+                    .with_help("check the divisor is non-zero before dividing, e.g. with an `if`")
+                    .report(&self.file);
+                Value::Void
+            }
+            ("/", Value::Int(a), Value::Int(b)) => Value::Int(a / b),
+            ("/", Value::Float(a), Value::Float(b)) => Value::Float(a / b),
+            ("%", Value::Int(_), Value::Int(0)) => {
+                self.had_error = true;
+                Diagnostic::error("E302", "division by zero", position)
+                    .with_help("check the divisor is non-zero before taking a remainder, e.g. with an `if`")
+                    .report(&self.file);
+                Value::Void
+            }
+            ("%", Value::Int(a), Value::Int(b)) => Value::Int(a % b),
+            ("==", a, b) => Value::Bool(a == b),
+            ("!=", a, b) => Value::Bool(a != b),
+            ("<", a, b) => Value::Bool(compare(&a, &b) == Some(std::cmp::Ordering::Less)),
+            ("<=", a, b) => Value::Bool(matches!(compare(&a, &b), Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal))),
+            (">", a, b) => Value::Bool(compare(&a, &b) == Some(std::cmp::Ordering::Greater)),
+            (">=", a, b) => Value::Bool(matches!(compare(&a, &b), Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal))),
+            ("&&", a, b) => Value::Bool(truthy(&a) && truthy(&b)),
+            ("||", a, b) => Value::Bool(truthy(&a) || truthy(&b)),
+            (op, a, b) => self.unsupported(&format!("operator '{}' on {} and {}", op, a, b), position),
+        }
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
+    match (left, right) {
+        (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+        (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Value::Char(a), Value::Char(b)) => a.partial_cmp(b),
+        (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Void => false,
+        _ => true,
+    }
+}
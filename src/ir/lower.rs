@@ -0,0 +1,505 @@
+//! Lowers a resolved [`Module`] into the [`crate::ir`] representation.
+//!
+//! Mirrors the shape of [`crate::backend::bytecode::compile`]: a function
+//! table is built up front so calls can resolve forward references, each
+//! top-level `func` lowers to its own [`Function`], and top-level
+//! statements outside any function lower into a synthesized `main`. Unlike
+//! `bytecode`, every value here carries an explicit [`Type`], computed the
+//! same way [`crate::backend::wasm`] computes its `ValType`s (there is no
+//! stored type annotation on [`Expression`] to read back).
+
+use std::collections::HashMap;
+
+use crate::ast::{Expression, ExpressionKind, LiteralValue, Module, Statement, StatementKind, TypeKind};
+use crate::common::Position;
+use crate::errors::Diagnostic;
+use crate::ir::{BinOp, Block, BlockId, Const, Expr, Function, Instr, Local, Program, Terminator, Type, UnOp};
+
+fn ir_type(kind: &TypeKind) -> Option<Type> {
+    match kind {
+        TypeKind::Int32 => Some(Type::Int32),
+        TypeKind::Int64 => Some(Type::Int64),
+        TypeKind::Float32 => Some(Type::Float32),
+        TypeKind::Float64 => Some(Type::Float64),
+        TypeKind::Bool => Some(Type::Bool),
+        TypeKind::Char => Some(Type::Char),
+        TypeKind::Void => Some(Type::Void),
+        _ => None,
+    }
+}
+
+fn zero_of(ty: Type) -> Const {
+    match ty {
+        Type::Int32 | Type::Int64 => Const::Int(0),
+        Type::Float32 | Type::Float64 => Const::Float(0.0),
+        Type::Bool => Const::Bool(false),
+        Type::Char => Const::Char('\0'),
+        Type::Void => Const::Int(0),
+    }
+}
+
+/// Where `break`/`continue` inside the loop currently being lowered jump
+/// to. `continue_block` is a latch that still has to run a `for` loop's
+/// update clause before looping back, the same fix the wasm backend
+/// needed for its own `continue` (see [`crate::backend::wasm`]'s `For`
+/// lowering): jumping straight back to the header would skip it.
+struct LoopTargets {
+    break_block: BlockId,
+    continue_block: BlockId,
+}
+
+struct FunctionLowering<'a> {
+    blocks: Vec<Block>,
+    current: BlockId,
+    locals: Vec<Local>,
+    loops: Vec<LoopTargets>,
+    functions: &'a HashMap<String, (Vec<Type>, Type)>,
+    /// Names of `extern` functions -- known to `functions` for signature
+    /// purposes, but with no IR body to call into (see the `Call` arm of
+    /// [`Self::expression`]).
+    externs: &'a std::collections::HashSet<String>,
+    had_error: bool,
+    file: &'a str,
+}
+
+/// Lowers `module` into the mid-level IR. `had_error` mirrors the other
+/// phases' convention of a boolean flag rather than a `Result`; anything
+/// outside the arithmetic/control-flow/function-call subset reports
+/// `E304` and is skipped rather than lowered incorrectly.
+pub fn lower(module: &Module, file: &str) -> (Program, bool) {
+    let mut had_error = false;
+    let mut function_table: HashMap<String, (Vec<Type>, Type)> = HashMap::new();
+    let mut externs: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for statement in &module.statements {
+        if let StatementKind::FunctionDecl(function) = &statement.kind {
+            let params: Option<Vec<Type>> = function.params.iter().map(|p| ir_type(&p.ty.kind)).collect();
+            let Some(params) = params else {
+                unsupported_at(file, &format!("function '{}' has a parameter type not representable in the ir", function.name), function.position);
+                had_error = true;
+                continue;
+            };
+            let Some(return_ty) = ir_type(&function.return_type.kind) else {
+                unsupported_at(file, &format!("function '{}' has a return type not representable in the ir", function.name), function.position);
+                had_error = true;
+                continue;
+            };
+            if function.extern_info.is_some() {
+                externs.insert(function.name.clone());
+            }
+            function_table.insert(function.name.clone(), (params, return_ty));
+        }
+    }
+
+    let mut functions = Vec::new();
+    for statement in &module.statements {
+        if let StatementKind::FunctionDecl(function) = &statement.kind {
+            if !function_table.contains_key(&function.name) || externs.contains(&function.name) {
+                continue;
+            }
+            let (_, return_ty) = function_table[&function.name].clone();
+            let mut lowering = FunctionLowering::new(&function_table, &externs, file);
+            for param in &function.params {
+                let ty = ir_type(&param.ty.kind).expect("validated above");
+                lowering.declare_local(param.name.clone(), ty);
+            }
+            for stmt in &function.body {
+                lowering.statement(stmt);
+            }
+            lowering.finish_with_default_return(return_ty);
+            had_error |= lowering.had_error;
+            functions.push(lowering.into_function(function.name.clone(), function.params.len(), return_ty));
+        }
+    }
+
+    let mut main = FunctionLowering::new(&function_table, &externs, file);
+    for statement in &module.statements {
+        if matches!(statement.kind, StatementKind::FunctionDecl(_)) {
+            continue;
+        }
+        main.statement(statement);
+    }
+    main.finish_with_default_return(Type::Void);
+    had_error |= main.had_error;
+    functions.push(main.into_function("main".to_string(), 0, Type::Void));
+
+    (Program { functions }, had_error)
+}
+
+fn unsupported_at(file: &str, what: &str, position: Position) {
+    Diagnostic::error("E304", what.to_string(), position).report(file);
+}
+
+impl<'a> FunctionLowering<'a> {
+    fn new(functions: &'a HashMap<String, (Vec<Type>, Type)>, externs: &'a std::collections::HashSet<String>, file: &'a str) -> Self {
+        let mut lowering = FunctionLowering {
+            blocks: Vec::new(),
+            current: 0,
+            locals: Vec::new(),
+            loops: Vec::new(),
+            functions,
+            externs,
+            had_error: false,
+            file,
+        };
+        lowering.new_block();
+        lowering
+    }
+
+    fn into_function(self, name: String, param_count: usize, return_ty: Type) -> Function {
+        Function {
+            name,
+            param_count,
+            return_ty,
+            locals: self.locals,
+            blocks: self.blocks,
+            entry: 0,
+        }
+    }
+
+    /// A function whose body never explicitly returns falls through here;
+    /// give it a default return so every block ends in a terminator.
+    fn finish_with_default_return(&mut self, return_ty: Type) {
+        if self.blocks[self.current].terminator.is_none() {
+            let value = if return_ty == Type::Void { None } else { Some(Expr::Const(zero_of(return_ty), return_ty)) };
+            self.terminate(Terminator::Return(value));
+        }
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.push(Block::default());
+        self.blocks.len() - 1
+    }
+
+    fn switch_to(&mut self, block: BlockId) {
+        self.current = block;
+    }
+
+    fn emit(&mut self, instr: Instr) {
+        if self.blocks[self.current].terminator.is_none() {
+            self.blocks[self.current].instructions.push(instr);
+        }
+    }
+
+    /// Sets the current block's terminator, unless it's already
+    /// terminated (a `return`/`break`/`continue` earlier in the same
+    /// block makes everything after it dead code).
+    fn terminate(&mut self, terminator: Terminator) {
+        if self.blocks[self.current].terminator.is_none() {
+            self.blocks[self.current].terminator = Some(terminator);
+        }
+    }
+
+    fn declare_local(&mut self, name: String, ty: Type) -> u32 {
+        let index = self.locals.len() as u32;
+        self.locals.push(Local { name, ty });
+        index
+    }
+
+    fn resolve_local(&self, name: &str) -> Option<(u32, Type)> {
+        self.locals.iter().enumerate().rev().find(|(_, local)| local.name == name).map(|(i, local)| (i as u32, local.ty))
+    }
+
+    fn unsupported(&mut self, what: &str, position: Position) {
+        self.had_error = true;
+        unsupported_at(self.file, &format!("'{}' is not representable in the ir", what), position);
+    }
+
+    fn cast(&mut self, value: Expr, to: Type, position: Position) -> Option<Expr> {
+        let from = value.ty();
+        if from == to {
+            return Some(value);
+        }
+        if from.is_float() && !to.is_float() {
+            self.unsupported("narrowing a float to an integer type", position);
+            return None;
+        }
+        Some(Expr::Cast { value: Box::new(value), from, to })
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let pos = statement.position;
+        match &statement.kind {
+            StatementKind::Expression(expr) => {
+                if let Some(e) = self.expression(expr) {
+                    self.emit(Instr::Eval(e));
+                }
+            }
+            StatementKind::Let { name, ty, value, .. } => {
+                let value_expr = value.as_ref().and_then(|v| self.expression(v));
+                let declared = ty.as_ref().and_then(|t| ir_type(&t.kind));
+                let Some(local_ty) = declared.or_else(|| value_expr.as_ref().map(Expr::ty)) else {
+                    self.unsupported(&format!("let binding '{}' with an unrepresentable type", name), pos);
+                    return;
+                };
+                let value_expr = match value_expr {
+                    Some(v) => match self.cast(v, local_ty, pos) {
+                        Some(v) => v,
+                        None => return,
+                    },
+                    None => Expr::Const(zero_of(local_ty), local_ty),
+                };
+                let index = self.declare_local(name.clone(), local_ty);
+                self.emit(Instr::Store { local: index, value: value_expr });
+            }
+            StatementKind::Return(value) => {
+                let value = match value {
+                    Some(expr) => match self.expression(expr) {
+                        Some(v) => Some(v),
+                        None => return,
+                    },
+                    None => None,
+                };
+                self.terminate(Terminator::Return(value));
+            }
+            StatementKind::If { condition, then_branch, else_branch } => {
+                let Some(condition) = self.expression(condition) else { return };
+                let then_block = self.new_block();
+                let else_block = self.new_block();
+                let merge_block = self.new_block();
+                self.terminate(Terminator::Branch { condition, then_block, else_block });
+
+                self.switch_to(then_block);
+                for stmt in then_branch {
+                    self.statement(stmt);
+                }
+                self.terminate(Terminator::Jump(merge_block));
+
+                self.switch_to(else_block);
+                if let Some(else_branch) = else_branch {
+                    for stmt in else_branch {
+                        self.statement(stmt);
+                    }
+                }
+                self.terminate(Terminator::Jump(merge_block));
+
+                self.switch_to(merge_block);
+            }
+            StatementKind::While { condition, body } => {
+                let header = self.new_block();
+                let body_block = self.new_block();
+                let exit_block = self.new_block();
+                self.terminate(Terminator::Jump(header));
+
+                self.switch_to(header);
+                let Some(condition) = self.expression(condition) else { return };
+                self.terminate(Terminator::Branch { condition, then_block: body_block, else_block: exit_block });
+
+                self.switch_to(body_block);
+                self.loops.push(LoopTargets { break_block: exit_block, continue_block: header });
+                for stmt in body {
+                    self.statement(stmt);
+                }
+                self.loops.pop();
+                self.terminate(Terminator::Jump(header));
+
+                self.switch_to(exit_block);
+            }
+            StatementKind::For { init, condition, update, body } => {
+                if let Some(init) = init {
+                    self.statement(init);
+                }
+                let header = self.new_block();
+                let body_block = self.new_block();
+                let latch = self.new_block();
+                let exit_block = self.new_block();
+                self.terminate(Terminator::Jump(header));
+
+                self.switch_to(header);
+                match condition {
+                    Some(condition) => {
+                        let Some(condition) = self.expression(condition) else { return };
+                        self.terminate(Terminator::Branch { condition, then_block: body_block, else_block: exit_block });
+                    }
+                    None => self.terminate(Terminator::Jump(body_block)),
+                }
+
+                self.switch_to(body_block);
+                self.loops.push(LoopTargets { break_block: exit_block, continue_block: latch });
+                for stmt in body {
+                    self.statement(stmt);
+                }
+                self.loops.pop();
+                self.terminate(Terminator::Jump(latch));
+
+                self.switch_to(latch);
+                if let Some(update) = update {
+                    if let Some(e) = self.expression(update) {
+                        self.emit(Instr::Eval(e));
+                    }
+                }
+                self.terminate(Terminator::Jump(header));
+
+                self.switch_to(exit_block);
+            }
+            StatementKind::Block(statements) => {
+                for stmt in statements {
+                    self.statement(stmt);
+                }
+            }
+            StatementKind::Break => match self.loops.last() {
+                Some(targets) => self.terminate(Terminator::Jump(targets.break_block)),
+                None => self.unsupported("break outside a loop", pos),
+            },
+            StatementKind::Continue => match self.loops.last() {
+                Some(targets) => self.terminate(Terminator::Jump(targets.continue_block)),
+                None => self.unsupported("continue outside a loop", pos),
+            },
+            StatementKind::FunctionDecl(_) => self.unsupported("nested function declarations", pos),
+            StatementKind::StructDecl(_)
+            | StatementKind::EnumDecl(_)
+            | StatementKind::InterfaceDecl(_)
+            | StatementKind::ImplBlock(_)
+            | StatementKind::ModuleDecl(_)
+            | StatementKind::Import(_)
+            | StatementKind::Export(_)
+            | StatementKind::ForEach { .. }
+            | StatementKind::Match { .. } => self.unsupported("this statement", pos),
+            StatementKind::Error => {}
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) -> Option<Expr> {
+        let pos = expr.position;
+        match &expr.kind {
+            ExpressionKind::Literal(literal) => Some(match literal {
+                LiteralValue::Int(n, suffix) => {
+                    let ty = if *suffix == Some(crate::ast::TypeKind::Int64) { Type::Int64 } else { Type::Int32 };
+                    Expr::Const(Const::Int(*n as i64), ty)
+                }
+                LiteralValue::Float(n, suffix) => {
+                    let ty = if *suffix == Some(crate::ast::TypeKind::Float32) { Type::Float32 } else { Type::Float64 };
+                    Expr::Const(Const::Float(*n), ty)
+                }
+                LiteralValue::Bool(b) => Expr::Const(Const::Bool(*b), Type::Bool),
+                LiteralValue::Char(c) => Expr::Const(Const::Char(*c), Type::Char),
+                LiteralValue::String(_) => {
+                    self.unsupported("string literals", pos);
+                    return None;
+                }
+            }),
+            ExpressionKind::Identifier(name) => match self.resolve_local(name) {
+                Some((index, ty)) => Some(Expr::Local(index, ty)),
+                None => {
+                    self.unsupported(&format!("reference to undeclared local '{}'", name), pos);
+                    None
+                }
+            },
+            ExpressionKind::Grouping(inner) => self.expression(inner),
+            ExpressionKind::Unary { op, operand } => {
+                let operand = self.expression(operand)?;
+                let ty = operand.ty();
+                let op = match op.as_str() {
+                    "-" => UnOp::Neg,
+                    "!" => UnOp::Not,
+                    other => {
+                        self.unsupported(&format!("unary operator '{}'", other), pos);
+                        return None;
+                    }
+                };
+                Some(Expr::Unary { op, operand: Box::new(operand), ty })
+            }
+            ExpressionKind::Binary { left, op, right } => self.binary(left, op, right, pos),
+            ExpressionKind::Assignment { target, op, value } => {
+                let ExpressionKind::Identifier(name) = &target.kind else {
+                    self.unsupported("assignment to a non-variable target", pos);
+                    return None;
+                };
+                let Some((index, local_ty)) = self.resolve_local(name) else {
+                    self.unsupported(&format!("assignment to undeclared local '{}'", name), pos);
+                    return None;
+                };
+                let value = if op == "=" {
+                    self.expression(value)?
+                } else {
+                    let current = Expr::Local(index, local_ty);
+                    let compound = &op[..op.len() - 1];
+                    let rhs = self.expression(value)?;
+                    self.binary_exprs(current, compound, rhs, pos)?
+                };
+                let value = self.cast(value, local_ty, pos)?;
+                self.emit(Instr::Store { local: index, value: value.clone() });
+                Some(Expr::Local(index, local_ty))
+            }
+            ExpressionKind::Call { callee, args } => {
+                let ExpressionKind::Identifier(name) = &callee.kind else {
+                    self.unsupported("calls to a non-identifier callee", pos);
+                    return None;
+                };
+                if name == "print" {
+                    if args.len() != 1 {
+                        self.unsupported("print with other than one argument", pos);
+                        return None;
+                    }
+                    let arg = self.expression(&args[0])?;
+                    return Some(Expr::Print(Box::new(arg)));
+                }
+                if self.externs.contains(name) {
+                    // No IR body exists for an `extern` function -- see
+                    // the interpreter's equivalent check in `call`.
+                    self.unsupported(&format!("calling extern function '{}'", name), pos);
+                    return None;
+                }
+                let Some((param_types, return_ty)) = self.functions.get(name).cloned() else {
+                    self.unsupported(&format!("call to unknown function '{}'", name), pos);
+                    return None;
+                };
+                if param_types.len() != args.len() {
+                    self.unsupported(&format!("call to '{}' with the wrong number of arguments", name), pos);
+                    return None;
+                }
+                let mut lowered_args = Vec::with_capacity(args.len());
+                for (arg, param_ty) in args.iter().zip(param_types.iter()) {
+                    let value = self.expression(arg)?;
+                    lowered_args.push(self.cast(value, *param_ty, arg.position)?);
+                }
+                Some(Expr::Call { function: name.clone(), args: lowered_args, ty: return_ty })
+            }
+            ExpressionKind::Try(_)
+            | ExpressionKind::Postfix { .. }
+            | ExpressionKind::Get { .. }
+            | ExpressionKind::Index { .. }
+            | ExpressionKind::ArrayLiteral(_)
+            | ExpressionKind::Tuple(_)
+            | ExpressionKind::MapLiteral(_)
+            | ExpressionKind::StructInit { .. }
+            | ExpressionKind::Lambda { .. }
+            | ExpressionKind::Match { .. } => {
+                self.unsupported("this expression", pos);
+                None
+            }
+            ExpressionKind::Error => None,
+        }
+    }
+
+    fn binary(&mut self, left: &Expression, op: &str, right: &Expression, pos: Position) -> Option<Expr> {
+        let left = self.expression(left)?;
+        let right = self.expression(right)?;
+        self.binary_exprs(left, op, right, pos)
+    }
+
+    fn binary_exprs(&mut self, left: Expr, op: &str, right: Expr, pos: Position) -> Option<Expr> {
+        let promoted = left.ty().widen(right.ty());
+        let left = self.cast(left, promoted, pos)?;
+        let right = self.cast(right, promoted, pos)?;
+        let (op, ty) = match op {
+            "+" => (BinOp::Add, promoted),
+            "-" => (BinOp::Sub, promoted),
+            "*" => (BinOp::Mul, promoted),
+            "/" => (BinOp::Div, promoted),
+            "%" => (BinOp::Rem, promoted),
+            "==" => (BinOp::Eq, Type::Bool),
+            "!=" => (BinOp::NotEq, Type::Bool),
+            "<" => (BinOp::Lt, Type::Bool),
+            "<=" => (BinOp::LtEq, Type::Bool),
+            ">" => (BinOp::Gt, Type::Bool),
+            ">=" => (BinOp::GtEq, Type::Bool),
+            "&&" => (BinOp::And, Type::Bool),
+            "||" => (BinOp::Or, Type::Bool),
+            other => {
+                self.unsupported(&format!("binary operator '{}'", other), pos);
+                return None;
+            }
+        };
+        Some(Expr::Binary { op, left: Box::new(left), right: Box::new(right), ty })
+    }
+}
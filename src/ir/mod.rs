@@ -0,0 +1,189 @@
+//! A typed, desugared mid-level IR sitting between the resolved
+//! [`crate::ast::Module`] and the backends.
+//!
+//! Lowering straight from the visitor AST duplicates the same work in
+//! every backend: [`crate::backend::bytecode`], the tree-walking
+//! [`crate::interpreter`], and [`crate::backend::wasm`] each re-derive
+//! operand types, re-flatten `if`/`while`/`for` into jumps, and re-resolve
+//! `Get`/assignment targets by hand. This module gives them a shared,
+//! already-desugared representation to consume instead: no `Get`/`Set`
+//! sugar (targets are local slots), explicit numeric casts (no operator
+//! silently widens an operand), and flattened control flow (a function
+//! body is a list of [`Block`]s ending in a [`Terminator`] rather than
+//! nested `if`/`while` statements).
+//!
+//! [`lower::lower`] produces this representation from a module that has
+//! already passed the resolver and typechecker; it only covers the subset
+//! those phases guarantee is well-formed arithmetic, control flow and
+//! plain function calls, the same subset the existing backends support.
+//! Constructs outside that subset report `E304` and are skipped, the same
+//! "honest partial coverage" convention `bytecode`, `interpreter` and
+//! `wasm` already use.
+//!
+//! Backends migrate onto this IR incrementally; consuming it is not yet
+//! mandatory for a backend to exist.
+//!
+//! [`ssa`] converts a lowered [`Function`] to SSA form (one definition
+//! per local, [`Instr::Phi`] at merge points), and [`pass`] runs
+//! registered [`pass::Pass`]es like it over a [`Program`] in order,
+//! timing each one.
+
+pub mod lower;
+pub mod pass;
+pub mod ssa;
+
+/// The types this IR can distinguish. Narrower than [`crate::ast::TypeKind`]
+/// (no arrays, structs, ...) since only the subset [`lower`] actually
+/// lowers needs a representation here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    Char,
+    Void,
+}
+
+impl Type {
+    /// True for the two floating-point types, the same distinction
+    /// [`UnOp::Neg`]/[`BinOp`] arithmetic needs to pick an integer or
+    /// float instruction downstream.
+    pub fn is_float(self) -> bool {
+        matches!(self, Type::Float32 | Type::Float64)
+    }
+
+    /// The wider of two numeric types, mirroring the promotion rule
+    /// [`crate::ast::TypeKind::precedence`] applies at the AST level. A
+    /// mismatch here is made explicit by [`lower`] inserting a [`Expr::Cast`]
+    /// rather than leaving the mismatch implicit.
+    pub fn widen(self, other: Type) -> Type {
+        match (self, other) {
+            (Type::Float64, _) | (_, Type::Float64) => Type::Float64,
+            (Type::Float32, _) | (_, Type::Float32) => Type::Float32,
+            (Type::Int64, _) | (_, Type::Int64) => Type::Int64,
+            _ => Type::Int32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Const {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+}
+
+/// A local variable slot, indexed the same way a backend's own frame
+/// would (parameters first, then `let`-bound locals in declaration order).
+pub type LocalId = u32;
+
+/// A basic block within a function's [`Function::blocks`].
+pub type BlockId = usize;
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Const(Const, Type),
+    Local(LocalId, Type),
+    /// An explicit numeric widening, e.g. an `Int32` operand of a `Float64`
+    /// binary expression. Desugared here so no backend needs its own
+    /// coercion-insertion pass.
+    Cast { value: Box<Expr>, from: Type, to: Type },
+    Unary { op: UnOp, operand: Box<Expr>, ty: Type },
+    Binary { op: BinOp, left: Box<Expr>, right: Box<Expr>, ty: Type },
+    Call { function: String, args: Vec<Expr>, ty: Type },
+    /// The `print` builtin, kept distinct from [`Expr::Call`] the same way
+    /// [`crate::semantic::mod`]'s `is_builtin_function` keeps it out of the
+    /// symbol table: it isn't a `Function` any backend defines.
+    Print(Box<Expr>),
+}
+
+impl Expr {
+    pub fn ty(&self) -> Type {
+        match self {
+            Expr::Const(_, ty) | Expr::Local(_, ty) | Expr::Binary { ty, .. } | Expr::Unary { ty, .. } | Expr::Call { ty, .. } => *ty,
+            Expr::Cast { to, .. } => *to,
+            Expr::Print(_) => Type::Void,
+        }
+    }
+}
+
+/// One statement within a [`Block`]. Everything that isn't control flow
+/// (that's [`Terminator`]'s job) lives here.
+#[derive(Debug, Clone)]
+pub enum Instr {
+    Eval(Expr),
+    Store { local: LocalId, value: Expr },
+    /// A join point: `dest` takes `local`'s value from whichever
+    /// `incoming` edge control actually arrived through. Only present
+    /// once [`ssa::convert`] has run; always the leading instructions of
+    /// the block they appear in.
+    Phi { dest: LocalId, incoming: Vec<(BlockId, LocalId)> },
+}
+
+/// How a block ends. Every block ends in exactly one of these; there is no
+/// implicit fallthrough, which is what "flattened control flow" means
+/// here — an `if`/`while`/`for` becomes a handful of blocks wired together
+/// by `Terminator`s instead of a single nested statement.
+#[derive(Debug, Clone)]
+pub enum Terminator {
+    Jump(BlockId),
+    Branch { condition: Expr, then_block: BlockId, else_block: BlockId },
+    Return(Option<Expr>),
+    /// Placeholder left behind when lowering a block failed; a backend
+    /// should never reach one in a module for which `lower` reported no
+    /// errors.
+    Unreachable,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Block {
+    pub instructions: Vec<Instr>,
+    pub terminator: Option<Terminator>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Local {
+    pub name: String,
+    pub ty: Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub param_count: usize,
+    pub return_ty: Type,
+    pub locals: Vec<Local>,
+    pub blocks: Vec<Block>,
+    pub entry: BlockId,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub functions: Vec<Function>,
+}
@@ -0,0 +1,184 @@
+//! A small pass framework for transforming a lowered [`Program`].
+//!
+//! Passes register with a [`PassManager`] and run in registration order;
+//! each is timed independently, and `--print-ir-after=<pass>` (wired up
+//! in `main`) dumps the whole program's IR to stderr right after the
+//! named pass finishes, the same way a debug-build compiler flag would.
+
+use std::time::{Duration, Instant};
+
+use crate::ir::{BinOp, Const, Expr, Instr, Program, Terminator, UnOp};
+
+pub trait Pass {
+    fn name(&self) -> &'static str;
+    fn run(&self, program: &mut Program);
+}
+
+pub struct PassReport {
+    pub name: &'static str,
+    pub duration: Duration,
+}
+
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+    print_after: Option<String>,
+}
+
+impl PassManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, pass: Box<dyn Pass>) -> &mut Self {
+        self.passes.push(pass);
+        self
+    }
+
+    pub fn print_ir_after(&mut self, pass_name: impl Into<String>) -> &mut Self {
+        self.print_after = Some(pass_name.into());
+        self
+    }
+
+    pub fn run(&self, program: &mut Program) -> Vec<PassReport> {
+        let mut reports = Vec::with_capacity(self.passes.len());
+        for pass in &self.passes {
+            let start = Instant::now();
+            pass.run(program);
+            reports.push(PassReport { name: pass.name(), duration: start.elapsed() });
+            if self.print_after.as_deref() == Some(pass.name()) {
+                eprintln!("=== ir after '{}' ===\n{:#?}", pass.name(), program);
+            }
+        }
+        reports
+    }
+}
+
+/// Converts every function to SSA form; see [`crate::ir::ssa`].
+pub struct SsaConstruction;
+
+impl Pass for SsaConstruction {
+    fn name(&self) -> &'static str {
+        "ssa-construction"
+    }
+
+    fn run(&self, program: &mut Program) {
+        for function in &mut program.functions {
+            crate::ir::ssa::convert(function);
+        }
+    }
+}
+
+/// Folds operations whose operands are both compile-time constants, e.g.
+/// `2 + 3` into `5`. Deliberately local to one expression tree at a time
+/// — it doesn't propagate constants through locals, which is why it's
+/// registered after [`SsaConstruction`]: once a local has exactly one
+/// definition, a later constant-propagation pass can fold through it too
+/// without this one needing to change.
+pub struct ConstantFold;
+
+impl Pass for ConstantFold {
+    fn name(&self) -> &'static str {
+        "constant-fold"
+    }
+
+    fn run(&self, program: &mut Program) {
+        for function in &mut program.functions {
+            for block in &mut function.blocks {
+                for instr in &mut block.instructions {
+                    match instr {
+                        Instr::Eval(expr) => fold(expr),
+                        Instr::Store { value, .. } => fold(value),
+                        Instr::Phi { .. } => {}
+                    }
+                }
+                match &mut block.terminator {
+                    Some(Terminator::Branch { condition, .. }) => fold(condition),
+                    Some(Terminator::Return(Some(value))) => fold(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn fold(expr: &mut Expr) {
+    match expr {
+        Expr::Unary { op, operand, ty } => {
+            fold(operand);
+            if let Expr::Const(c, _) = operand.as_ref() {
+                let folded = match (op, c) {
+                    (UnOp::Neg, Const::Int(n)) => Some(Const::Int(-n)),
+                    (UnOp::Neg, Const::Float(n)) => Some(Const::Float(-n)),
+                    (UnOp::Not, Const::Bool(b)) => Some(Const::Bool(!b)),
+                    _ => None,
+                };
+                if let Some(folded) = folded {
+                    *expr = Expr::Const(folded, *ty);
+                }
+            }
+        }
+        Expr::Binary { op, left, right, ty } => {
+            fold(left);
+            fold(right);
+            if let (Expr::Const(l, _), Expr::Const(r, _)) = (left.as_ref(), right.as_ref()) {
+                if let Some(folded) = fold_binary(*op, l, r) {
+                    *expr = Expr::Const(folded, *ty);
+                }
+            }
+        }
+        Expr::Cast { value, to, .. } => {
+            fold(value);
+            if let Expr::Const(Const::Int(n), _) = value.as_ref() {
+                *expr = Expr::Const(Const::Float(*n as f64), *to);
+            }
+        }
+        Expr::Call { args, .. } => {
+            for arg in args {
+                fold(arg);
+            }
+        }
+        Expr::Print(inner) => fold(inner),
+        Expr::Const(..) | Expr::Local(..) => {}
+    }
+}
+
+fn fold_binary(op: BinOp, left: &Const, right: &Const) -> Option<Const> {
+    match (left, right) {
+        (Const::Int(a), Const::Int(b)) => match op {
+            BinOp::Add => Some(Const::Int(a + b)),
+            BinOp::Sub => Some(Const::Int(a - b)),
+            BinOp::Mul => Some(Const::Int(a * b)),
+            BinOp::Div if *b != 0 => Some(Const::Int(a / b)),
+            BinOp::Rem if *b != 0 => Some(Const::Int(a % b)),
+            BinOp::Eq => Some(Const::Bool(a == b)),
+            BinOp::NotEq => Some(Const::Bool(a != b)),
+            BinOp::Lt => Some(Const::Bool(a < b)),
+            BinOp::LtEq => Some(Const::Bool(a <= b)),
+            BinOp::Gt => Some(Const::Bool(a > b)),
+            BinOp::GtEq => Some(Const::Bool(a >= b)),
+            _ => None,
+        },
+        (Const::Float(a), Const::Float(b)) => match op {
+            BinOp::Add => Some(Const::Float(a + b)),
+            BinOp::Sub => Some(Const::Float(a - b)),
+            BinOp::Mul => Some(Const::Float(a * b)),
+            BinOp::Div if *b != 0.0 => Some(Const::Float(a / b)),
+            BinOp::Eq => Some(Const::Bool(a == b)),
+            BinOp::NotEq => Some(Const::Bool(a != b)),
+            BinOp::Lt => Some(Const::Bool(a < b)),
+            BinOp::LtEq => Some(Const::Bool(a <= b)),
+            BinOp::Gt => Some(Const::Bool(a > b)),
+            BinOp::GtEq => Some(Const::Bool(a >= b)),
+            _ => None,
+        },
+        (Const::Bool(a), Const::Bool(b)) => match op {
+            BinOp::And => Some(Const::Bool(*a && *b)),
+            BinOp::Or => Some(Const::Bool(*a || *b)),
+            BinOp::Eq => Some(Const::Bool(a == b)),
+            BinOp::NotEq => Some(Const::Bool(a != b)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,282 @@
+//! SSA construction over [`crate::ir`]'s basic-block CFG.
+//!
+//! [`convert`] places [`Instr::Phi`] nodes at the iterated dominance
+//! frontier of each local's definitions (the standard Cytron et al.
+//! algorithm) and renames every definition and use to a fresh, versioned
+//! local id, so each local ends up assigned exactly once. This runs as a
+//! [`crate::ir::pass::Pass`] after [`crate::ir::lower::lower`] rather than
+//! as part of lowering itself — dominance is a pure CFG property, and
+//! keeping it out of the AST-to-IR translation lets later dominance-based
+//! passes reuse the same computation without re-deriving it.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ir::{BlockId, Function, Instr, Local, LocalId, Terminator};
+
+fn successors(terminator: &Terminator) -> Vec<BlockId> {
+    match terminator {
+        Terminator::Jump(target) => vec![*target],
+        Terminator::Branch { then_block, else_block, .. } => vec![*then_block, *else_block],
+        Terminator::Return(_) | Terminator::Unreachable => vec![],
+    }
+}
+
+fn predecessors(function: &Function) -> Vec<Vec<BlockId>> {
+    let mut preds = vec![Vec::new(); function.blocks.len()];
+    for (id, block) in function.blocks.iter().enumerate() {
+        if let Some(terminator) = &block.terminator {
+            for succ in successors(terminator) {
+                preds[succ].push(id);
+            }
+        }
+    }
+    preds
+}
+
+fn reverse_postorder(function: &Function) -> Vec<BlockId> {
+    let mut visited = vec![false; function.blocks.len()];
+    let mut postorder = Vec::new();
+    let mut stack = vec![(function.entry, false)];
+    while let Some((block, processed)) = stack.pop() {
+        if processed {
+            postorder.push(block);
+            continue;
+        }
+        if visited[block] {
+            continue;
+        }
+        visited[block] = true;
+        stack.push((block, true));
+        if let Some(terminator) = &function.blocks[block].terminator {
+            for succ in successors(terminator) {
+                if !visited[succ] {
+                    stack.push((succ, false));
+                }
+            }
+        }
+    }
+    postorder.reverse();
+    postorder
+}
+
+/// Immediate dominators via the Cooper/Harvey/Kennedy iterative
+/// algorithm — simpler to implement correctly than Lengauer-Tarjan and
+/// fast enough for function-sized CFGs.
+fn immediate_dominators(function: &Function, preds: &[Vec<BlockId>]) -> Vec<Option<BlockId>> {
+    let rpo = reverse_postorder(function);
+    let rpo_index: HashMap<BlockId, usize> = rpo.iter().enumerate().map(|(i, &b)| (b, i)).collect();
+    let mut idom: Vec<Option<BlockId>> = vec![None; function.blocks.len()];
+    idom[function.entry] = Some(function.entry);
+
+    let intersect = |mut a: BlockId, mut b: BlockId, idom: &[Option<BlockId>]| -> BlockId {
+        while a != b {
+            while rpo_index[&a] > rpo_index[&b] {
+                a = idom[a].expect("a processed block always has an idom");
+            }
+            while rpo_index[&b] > rpo_index[&a] {
+                b = idom[b].expect("a processed block always has an idom");
+            }
+        }
+        a
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &block in rpo.iter().filter(|&&b| b != function.entry) {
+            let mut processed_preds = preds[block].iter().copied().filter(|p| idom[*p].is_some());
+            let Some(first) = processed_preds.next() else { continue };
+            let mut new_idom = first;
+            for pred in processed_preds {
+                new_idom = intersect(new_idom, pred, &idom);
+            }
+            if idom[block] != Some(new_idom) {
+                idom[block] = Some(new_idom);
+                changed = true;
+            }
+        }
+    }
+    idom
+}
+
+fn dominance_frontiers(preds: &[Vec<BlockId>], idom: &[Option<BlockId>]) -> Vec<HashSet<BlockId>> {
+    let mut frontier = vec![HashSet::new(); preds.len()];
+    for (block, block_preds) in preds.iter().enumerate() {
+        if block_preds.len() < 2 || idom[block].is_none() {
+            continue;
+        }
+        for &pred in block_preds {
+            let mut runner = pred;
+            while idom[runner].is_some() && Some(runner) != idom[block] {
+                frontier[runner].insert(block);
+                runner = idom[runner].expect("checked above");
+            }
+        }
+    }
+    frontier
+}
+
+fn fresh_local(function: &mut Function, name: String, ty: crate::ir::Type) -> LocalId {
+    let id = function.locals.len() as LocalId;
+    function.locals.push(Local { name, ty });
+    id
+}
+
+fn rename_expr(expr: crate::ir::Expr, stacks: &[Vec<LocalId>]) -> crate::ir::Expr {
+    use crate::ir::Expr;
+
+    match expr {
+        Expr::Local(id, ty) => Expr::Local(*stacks[id as usize].last().expect("every base local has an initial version"), ty),
+        Expr::Const(..) => expr,
+        Expr::Cast { value, from, to } => Expr::Cast { value: Box::new(rename_expr(*value, stacks)), from, to },
+        Expr::Unary { op, operand, ty } => Expr::Unary { op, operand: Box::new(rename_expr(*operand, stacks)), ty },
+        Expr::Binary { op, left, right, ty } => {
+            Expr::Binary { op, left: Box::new(rename_expr(*left, stacks)), right: Box::new(rename_expr(*right, stacks)), ty }
+        }
+        Expr::Call { function, args, ty } => {
+            Expr::Call { function, args: args.into_iter().map(|arg| rename_expr(arg, stacks)).collect(), ty }
+        }
+        Expr::Print(inner) => Expr::Print(Box::new(rename_expr(*inner, stacks))),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn visit(
+    block: BlockId,
+    function: &mut Function,
+    children: &[Vec<BlockId>],
+    phi_dest: &HashMap<(BlockId, LocalId), LocalId>,
+    stacks: &mut [Vec<LocalId>],
+    phi_incoming: &mut HashMap<(BlockId, LocalId), Vec<(BlockId, LocalId)>>,
+) {
+    let mut pushed_bases = Vec::new();
+
+    for base in 0..stacks.len() as LocalId {
+        if let Some(&dest) = phi_dest.get(&(block, base)) {
+            stacks[base as usize].push(dest);
+            pushed_bases.push(base);
+        }
+    }
+
+    let instructions = std::mem::take(&mut function.blocks[block].instructions);
+    let mut renamed = Vec::with_capacity(instructions.len());
+    for instr in instructions {
+        match instr {
+            Instr::Eval(expr) => renamed.push(Instr::Eval(rename_expr(expr, stacks))),
+            Instr::Store { local, value } => {
+                let value = rename_expr(value, stacks);
+                let ty = function.locals[local as usize].ty;
+                let name = function.locals[local as usize].name.clone();
+                let dest = fresh_local(function, name, ty);
+                stacks[local as usize].push(dest);
+                pushed_bases.push(local);
+                renamed.push(Instr::Store { local: dest, value });
+            }
+            Instr::Phi { .. } => unreachable!("convert only ever runs once, before any phi exists"),
+        }
+    }
+    function.blocks[block].instructions = renamed;
+
+    if let Some(terminator) = function.blocks[block].terminator.take() {
+        let terminator = match terminator {
+            Terminator::Branch { condition, then_block, else_block } => {
+                Terminator::Branch { condition: rename_expr(condition, stacks), then_block, else_block }
+            }
+            Terminator::Return(Some(value)) => Terminator::Return(Some(rename_expr(value, stacks))),
+            other => other,
+        };
+        function.blocks[block].terminator = Some(terminator);
+    }
+
+    if let Some(terminator) = &function.blocks[block].terminator {
+        for succ in successors(terminator) {
+            for base in 0..stacks.len() as LocalId {
+                if phi_dest.contains_key(&(succ, base)) {
+                    let current = *stacks[base as usize].last().expect("base always has a version reaching a successor");
+                    phi_incoming.entry((succ, base)).or_default().push((block, current));
+                }
+            }
+        }
+    }
+
+    for &child in &children[block] {
+        visit(child, function, children, phi_dest, stacks, phi_incoming);
+    }
+
+    for base in pushed_bases {
+        stacks[base as usize].pop();
+    }
+}
+
+fn collect_def_blocks(function: &Function, original_local_count: usize) -> Vec<HashSet<BlockId>> {
+    let mut def_blocks = vec![HashSet::new(); original_local_count];
+    for def_block in def_blocks.iter_mut().take(function.param_count) {
+        def_block.insert(function.entry);
+    }
+    for (id, block) in function.blocks.iter().enumerate() {
+        for instr in &block.instructions {
+            if let Instr::Store { local, .. } = instr {
+                if (*local as usize) < original_local_count {
+                    def_blocks[*local as usize].insert(id);
+                }
+            }
+        }
+    }
+    def_blocks
+}
+
+/// Converts `function` in place to pruned SSA form.
+pub fn convert(function: &mut Function) {
+    let preds = predecessors(function);
+    let idom = immediate_dominators(function, &preds);
+    let frontier = dominance_frontiers(&preds, &idom);
+
+    let original_local_count = function.locals.len();
+    let def_blocks = collect_def_blocks(function, original_local_count);
+
+    let mut phi_locals: Vec<HashSet<LocalId>> = vec![HashSet::new(); function.blocks.len()];
+    for base in 0..original_local_count as LocalId {
+        let mut worklist: Vec<BlockId> = def_blocks[base as usize].iter().copied().collect();
+        let mut has_phi = HashSet::new();
+        while let Some(block) = worklist.pop() {
+            for &df_block in &frontier[block] {
+                if has_phi.insert(df_block) {
+                    phi_locals[df_block].insert(base);
+                    worklist.push(df_block);
+                }
+            }
+        }
+    }
+
+    let mut phi_dest: HashMap<(BlockId, LocalId), LocalId> = HashMap::new();
+    for (block, bases) in phi_locals.iter().enumerate() {
+        for &base in bases {
+            let ty = function.locals[base as usize].ty;
+            let name = format!("{}.phi", function.locals[base as usize].name);
+            let dest = fresh_local(function, name, ty);
+            phi_dest.insert((block, base), dest);
+        }
+    }
+
+    let mut children: Vec<Vec<BlockId>> = vec![Vec::new(); function.blocks.len()];
+    for (block, dominator) in idom.iter().enumerate() {
+        if let Some(dominator) = dominator {
+            if *dominator != block {
+                children[*dominator].push(block);
+            }
+        }
+    }
+
+    let mut stacks: Vec<Vec<LocalId>> = (0..original_local_count as LocalId).map(|base| vec![base]).collect();
+    let mut phi_incoming: HashMap<(BlockId, LocalId), Vec<(BlockId, LocalId)>> = HashMap::new();
+    visit(function.entry, function, &children, &phi_dest, &mut stacks, &mut phi_incoming);
+
+    for block in 0..function.blocks.len() {
+        for base in (0..original_local_count as LocalId).rev() {
+            if let Some(&dest) = phi_dest.get(&(block, base)) {
+                let incoming = phi_incoming.remove(&(block, base)).unwrap_or_default();
+                function.blocks[block].instructions.insert(0, Instr::Phi { dest, incoming });
+            }
+        }
+    }
+}
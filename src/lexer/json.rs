@@ -0,0 +1,139 @@
+//! Renders a token stream as JSON, the lexer-side counterpart to
+//! [`crate::ast::json`] -- same rationale: external tooling that wants
+//! the token stream without linking against this crate, and no `serde`
+//! (or any other dependency) in this crate to derive it with.
+
+use super::{Token, TokenType};
+use crate::common::Position;
+
+pub fn to_json(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    out.push('[');
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&token_json(token));
+    }
+    out.push(']');
+    out
+}
+
+fn token_json(token: &Token) -> String {
+    format!(
+        "{{\"kind\":{},\"lexeme\":{},\"position\":{}}}",
+        escape(kind_str(token.kind)),
+        escape(&token.lexeme),
+        position_json(token.position)
+    )
+}
+
+fn position_json(position: Position) -> String {
+    format!("{{\"line\":{},\"column\":{}}}", position.line, position.column)
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn kind_str(kind: TokenType) -> &'static str {
+    match kind {
+        TokenType::Int => "Int",
+        TokenType::Float => "Float",
+        TokenType::String => "String",
+        TokenType::Char => "Char",
+        TokenType::Identifier => "Identifier",
+        TokenType::Func => "Func",
+        TokenType::Var => "Var",
+        TokenType::Let => "Let",
+        TokenType::Const => "Const",
+        TokenType::If => "If",
+        TokenType::Else => "Else",
+        TokenType::While => "While",
+        TokenType::For => "For",
+        TokenType::In => "In",
+        TokenType::Match => "Match",
+        TokenType::Return => "Return",
+        TokenType::Break => "Break",
+        TokenType::Continue => "Continue",
+        TokenType::Struct => "Struct",
+        TokenType::Enum => "Enum",
+        TokenType::Interface => "Interface",
+        TokenType::Impl => "Impl",
+        TokenType::Import => "Import",
+        TokenType::Export => "Export",
+        TokenType::Module => "Module",
+        TokenType::Pub => "Pub",
+        TokenType::Extern => "Extern",
+        TokenType::From => "From",
+        TokenType::As => "As",
+        TokenType::True => "True",
+        TokenType::False => "False",
+        TokenType::Void => "Void",
+        TokenType::Null => "Null",
+        TokenType::LParen => "LParen",
+        TokenType::RParen => "RParen",
+        TokenType::LBrace => "LBrace",
+        TokenType::RBrace => "RBrace",
+        TokenType::LBracket => "LBracket",
+        TokenType::RBracket => "RBracket",
+        TokenType::Comma => "Comma",
+        TokenType::Semicolon => "Semicolon",
+        TokenType::Colon => "Colon",
+        TokenType::Dot => "Dot",
+        TokenType::Question => "Question",
+        TokenType::At => "At",
+        TokenType::Plus => "Plus",
+        TokenType::Minus => "Minus",
+        TokenType::Star => "Star",
+        TokenType::Slash => "Slash",
+        TokenType::Percent => "Percent",
+        TokenType::Amp => "Amp",
+        TokenType::Pipe => "Pipe",
+        TokenType::Caret => "Caret",
+        TokenType::Tilde => "Tilde",
+        TokenType::Bang => "Bang",
+        TokenType::Eq => "Eq",
+        TokenType::EqEq => "EqEq",
+        TokenType::BangEq => "BangEq",
+        TokenType::Lt => "Lt",
+        TokenType::LtEq => "LtEq",
+        TokenType::Gt => "Gt",
+        TokenType::GtEq => "GtEq",
+        TokenType::LtLt => "LtLt",
+        TokenType::GtGt => "GtGt",
+        TokenType::AmpAmp => "AmpAmp",
+        TokenType::PipePipe => "PipePipe",
+        TokenType::PlusPlus => "PlusPlus",
+        TokenType::MinusMinus => "MinusMinus",
+        TokenType::Arrow => "Arrow",
+        TokenType::FatArrow => "FatArrow",
+        TokenType::PlusEq => "PlusEq",
+        TokenType::MinusEq => "MinusEq",
+        TokenType::StarEq => "StarEq",
+        TokenType::SlashEq => "SlashEq",
+        TokenType::PercentEq => "PercentEq",
+        TokenType::AmpEq => "AmpEq",
+        TokenType::PipeEq => "PipeEq",
+        TokenType::CaretEq => "CaretEq",
+        TokenType::LtLtEq => "LtLtEq",
+        TokenType::GtGtEq => "GtGtEq",
+        TokenType::DocComment => "DocComment",
+        TokenType::Error => "Error",
+        TokenType::Eof => "Eof",
+    }
+}
@@ -0,0 +1,550 @@
+mod token;
+pub mod json;
+
+pub use token::{Token, TokenType};
+
+use crate::common::Position;
+
+/// Turns Matcha source text into a flat token stream.
+///
+/// The lexer is intentionally forgiving: on malformed input it emits an
+/// `Error` token and keeps scanning, rather than aborting, so the parser
+/// can still recover and report multiple diagnostics per run.
+pub struct Lexer {
+    source: Vec<char>,
+    start: usize,
+    current: usize,
+    line: usize,
+    column: usize,
+    /// Absolute byte offset of `current` into the original source text.
+    /// Tracked alongside `current` (a `char` index) rather than derived
+    /// from it, since `source` is stored as `Vec<char>` and a byte offset
+    /// isn't recoverable from a char index without rescanning.
+    byte_offset: usize,
+}
+
+impl Lexer {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.chars().collect(),
+            start: 0,
+            current: 0,
+            line: 1,
+            column: 1,
+            byte_offset: 0,
+        }
+    }
+
+    pub fn scan_tokens(&mut self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments();
+            self.start = self.current;
+            if self.is_at_end() {
+                tokens.push(Token::new(TokenType::Eof, String::new(), self.position()));
+                break;
+            }
+            tokens.push(self.scan_token());
+        }
+        tokens
+    }
+
+    fn scan_token(&mut self) -> Token {
+        let pos = self.position();
+        let c = self.advance();
+
+        match c {
+            '(' => self.make(TokenType::LParen, pos),
+            ')' => self.make(TokenType::RParen, pos),
+            '{' => self.make(TokenType::LBrace, pos),
+            '}' => self.make(TokenType::RBrace, pos),
+            '[' => self.make(TokenType::LBracket, pos),
+            ']' => self.make(TokenType::RBracket, pos),
+            ',' => self.make(TokenType::Comma, pos),
+            ';' => self.make(TokenType::Semicolon, pos),
+            ':' => self.make(TokenType::Colon, pos),
+            '.' => self.make(TokenType::Dot, pos),
+            '~' => self.make(TokenType::Tilde, pos),
+            '?' => self.make(TokenType::Question, pos),
+            '@' => self.make(TokenType::At, pos),
+            '+' => {
+                if self.match_char('+') {
+                    self.make(TokenType::PlusPlus, pos)
+                } else if self.match_char('=') {
+                    self.make(TokenType::PlusEq, pos)
+                } else {
+                    self.make(TokenType::Plus, pos)
+                }
+            }
+            '-' => {
+                if self.match_char('-') {
+                    self.make(TokenType::MinusMinus, pos)
+                } else if self.match_char('=') {
+                    self.make(TokenType::MinusEq, pos)
+                } else if self.match_char('>') {
+                    self.make(TokenType::Arrow, pos)
+                } else {
+                    self.make(TokenType::Minus, pos)
+                }
+            }
+            '*' => {
+                if self.match_char('=') {
+                    self.make(TokenType::StarEq, pos)
+                } else {
+                    self.make(TokenType::Star, pos)
+                }
+            }
+            '/' => {
+                if self.peek() == '/' && self.peek_next() == '/' && self.peek_at(2) != '/' {
+                    self.scan_doc_comment(pos)
+                } else if self.match_char('=') {
+                    self.make(TokenType::SlashEq, pos)
+                } else {
+                    self.make(TokenType::Slash, pos)
+                }
+            }
+            '%' => {
+                if self.match_char('=') {
+                    self.make(TokenType::PercentEq, pos)
+                } else {
+                    self.make(TokenType::Percent, pos)
+                }
+            }
+            '&' => {
+                if self.match_char('&') {
+                    self.make(TokenType::AmpAmp, pos)
+                } else if self.match_char('=') {
+                    self.make(TokenType::AmpEq, pos)
+                } else {
+                    self.make(TokenType::Amp, pos)
+                }
+            }
+            '|' => {
+                if self.match_char('|') {
+                    self.make(TokenType::PipePipe, pos)
+                } else if self.match_char('=') {
+                    self.make(TokenType::PipeEq, pos)
+                } else {
+                    self.make(TokenType::Pipe, pos)
+                }
+            }
+            '^' => {
+                if self.match_char('=') {
+                    self.make(TokenType::CaretEq, pos)
+                } else {
+                    self.make(TokenType::Caret, pos)
+                }
+            }
+            '!' => {
+                if self.match_char('=') {
+                    self.make(TokenType::BangEq, pos)
+                } else {
+                    self.make(TokenType::Bang, pos)
+                }
+            }
+            '=' => {
+                if self.match_char('=') {
+                    self.make(TokenType::EqEq, pos)
+                } else if self.match_char('>') {
+                    self.make(TokenType::FatArrow, pos)
+                } else {
+                    self.make(TokenType::Eq, pos)
+                }
+            }
+            '<' => {
+                if self.match_char('=') {
+                    self.make(TokenType::LtEq, pos)
+                } else if self.match_char('<') {
+                    if self.match_char('=') {
+                        self.make(TokenType::LtLtEq, pos)
+                    } else {
+                        self.make(TokenType::LtLt, pos)
+                    }
+                } else {
+                    self.make(TokenType::Lt, pos)
+                }
+            }
+            '>' => {
+                if self.match_char('=') {
+                    self.make(TokenType::GtEq, pos)
+                } else if self.match_char('>') {
+                    if self.match_char('=') {
+                        self.make(TokenType::GtGtEq, pos)
+                    } else {
+                        self.make(TokenType::GtGt, pos)
+                    }
+                } else {
+                    self.make(TokenType::Gt, pos)
+                }
+            }
+            '"' if self.peek() == '"' && self.peek_next() == '"' => self.triple_quoted_string(pos),
+            '"' => self.string(pos),
+            'r' if self.peek() == '"' => self.raw_string(pos),
+            '\'' => self.char_literal(pos),
+            c if c.is_ascii_digit() => self.number(pos),
+            c if c.is_alphabetic() || c == '_' => self.identifier(pos),
+            _ => Token::new(TokenType::Error, c.to_string(), pos),
+        }
+    }
+
+    fn identifier(&mut self, pos: Position) -> Token {
+        while self.peek().is_alphanumeric() || self.peek() == '_' {
+            self.advance();
+        }
+        let text: String = self.source[self.start..self.current].iter().collect();
+        let kind = Self::keyword(&text).unwrap_or(TokenType::Identifier);
+        Token::new(kind, text, pos)
+    }
+
+    fn keyword(text: &str) -> Option<TokenType> {
+        Some(match text {
+            "func" => TokenType::Func,
+            "var" => TokenType::Var,
+            "let" => TokenType::Let,
+            "const" => TokenType::Const,
+            "if" => TokenType::If,
+            "else" => TokenType::Else,
+            "while" => TokenType::While,
+            "for" => TokenType::For,
+            "in" => TokenType::In,
+            "match" => TokenType::Match,
+            "return" => TokenType::Return,
+            "break" => TokenType::Break,
+            "continue" => TokenType::Continue,
+            "struct" => TokenType::Struct,
+            "enum" => TokenType::Enum,
+            "interface" => TokenType::Interface,
+            "impl" => TokenType::Impl,
+            "import" => TokenType::Import,
+            "export" => TokenType::Export,
+            "module" => TokenType::Module,
+            "pub" => TokenType::Pub,
+            "extern" => TokenType::Extern,
+            "from" => TokenType::From,
+            "as" => TokenType::As,
+            "true" => TokenType::True,
+            "false" => TokenType::False,
+            "void" => TokenType::Void,
+            "null" => TokenType::Null,
+            _ => return None,
+        })
+    }
+
+    /// Scans an integer or float literal. `0x`/`0b`/`0o` prefixes select a
+    /// radix for integers, and `_` may separate digits anywhere in either
+    /// form (`1_000_000`, `0xFF_FF`); separators are stripped from the
+    /// token's lexeme so the parser can hand it straight to
+    /// `i64::from_str_radix`/`str::parse`.
+    fn number(&mut self, pos: Position) -> Token {
+        if self.source[self.start] == '0' && matches!(self.peek(), 'x' | 'X' | 'b' | 'B' | 'o' | 'O') {
+            let radix_char = self.advance();
+            let is_radix_digit: fn(char) -> bool = match radix_char.to_ascii_lowercase() {
+                'x' => |c: char| c.is_ascii_hexdigit(),
+                'b' => |c: char| c == '0' || c == '1',
+                'o' => |c: char| ('0'..='7').contains(&c),
+                _ => unreachable!(),
+            };
+            let mut digits = String::new();
+            while is_radix_digit(self.peek()) || self.peek() == '_' {
+                let ch = self.advance();
+                if ch != '_' {
+                    digits.push(ch);
+                }
+            }
+            if digits.is_empty() {
+                return Token::new(
+                    TokenType::Error,
+                    format!("Expected digits after '0{}'.", radix_char),
+                    pos,
+                );
+            }
+            let mut text = format!("0{}{}", radix_char, digits);
+            self.consume_suffix(TokenType::Int, &mut text);
+            return Token::new(TokenType::Int, text, pos);
+        }
+
+        let mut text = String::new();
+        text.push(self.source[self.start]);
+        while self.peek().is_ascii_digit() || self.peek() == '_' {
+            let ch = self.advance();
+            if ch != '_' {
+                text.push(ch);
+            }
+        }
+        let mut kind = TokenType::Int;
+        if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+            kind = TokenType::Float;
+            text.push(self.advance());
+            while self.peek().is_ascii_digit() || self.peek() == '_' {
+                let ch = self.advance();
+                if ch != '_' {
+                    text.push(ch);
+                }
+            }
+        }
+        self.consume_suffix(kind, &mut text);
+        Token::new(kind, text, pos)
+    }
+
+    /// Consumes a trailing `i8`/`i16`/.../`u64` type suffix on an `Int`
+    /// literal, or `f32`/`f64` on a `Float` one, appending it to `text`
+    /// verbatim so the parser can split it back off to pick the literal's
+    /// type ([`crate::parser::split_int_suffix`]/[`crate::parser::split_float_suffix`]).
+    /// Left alone if what follows
+    /// isn't one of this literal's own suffixes -- `10bees` still lexes
+    /// as `Int(10)` followed by an `Identifier`, the same parse error as
+    /// before suffixes existed, rather than a silently misread number.
+    fn consume_suffix(&mut self, kind: TokenType, text: &mut String) {
+        let candidates: &[&str] = match kind {
+            TokenType::Int => &["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"],
+            TokenType::Float => &["f32", "f64"],
+            _ => return,
+        };
+        for suffix in candidates {
+            let matches = suffix.chars().enumerate().all(|(i, c)| self.peek_at(i) == c);
+            let trailing = self.peek_at(suffix.len());
+            if matches && !trailing.is_alphanumeric() && trailing != '_' {
+                for _ in 0..suffix.len() {
+                    text.push(self.advance());
+                }
+                return;
+            }
+        }
+    }
+
+    fn string(&mut self, pos: Position) -> Token {
+        let mut value = String::new();
+        while self.peek() != '"' && !self.is_at_end() {
+            if self.peek() == '\n' {
+                self.line += 1;
+            }
+            if self.peek() == '\\' {
+                self.advance();
+                match self.decode_escape() {
+                    Ok(ch) => value.push(ch),
+                    Err(message) => return Token::new(TokenType::Error, message, pos),
+                }
+            } else {
+                value.push(self.advance());
+            }
+        }
+        if self.is_at_end() {
+            return Token::new(TokenType::Error, "Unterminated string.".to_string(), pos);
+        }
+        self.advance(); // closing quote
+        Token::new(TokenType::String, value, pos)
+    }
+
+    /// Scans a `///` doc comment: the two remaining slashes and one
+    /// leading space (if present) are dropped, so `Token::lexeme` holds
+    /// just the comment's text, the way [`Self::string`] stores a
+    /// string's decoded value rather than its raw source slice.
+    fn scan_doc_comment(&mut self, pos: Position) -> Token {
+        self.advance(); // 2nd '/'
+        self.advance(); // 3rd '/'
+        if self.peek() == ' ' {
+            self.advance();
+        }
+        let start = self.current;
+        while self.peek() != '\n' && !self.is_at_end() {
+            self.advance();
+        }
+        let text: String = self.source[start..self.current].iter().collect();
+        Token::new(TokenType::DocComment, text, pos)
+    }
+
+    /// Scans a `r"..."` raw string: no escape processing, so regex-like
+    /// content can be written without doubling backslashes. Can't contain
+    /// `"` at all, since there's no escape left to spell one.
+    fn raw_string(&mut self, pos: Position) -> Token {
+        self.advance(); // opening quote
+        let start = self.current;
+        while self.peek() != '"' && !self.is_at_end() {
+            if self.peek() == '\n' {
+                self.line += 1;
+            }
+            self.advance();
+        }
+        if self.is_at_end() {
+            return Token::new(TokenType::Error, "Unterminated raw string.".to_string(), pos);
+        }
+        let value: String = self.source[start..self.current].iter().collect();
+        self.advance(); // closing quote
+        Token::new(TokenType::String, value, pos)
+    }
+
+    /// Scans a `"""..."""` triple-quoted string: like a raw string, but may
+    /// contain `"` (just not three in a row), letting multi-line text be
+    /// written without escaping either quotes or newlines.
+    fn triple_quoted_string(&mut self, pos: Position) -> Token {
+        self.advance(); // 2nd opening quote
+        self.advance(); // 3rd opening quote
+        let start = self.current;
+        loop {
+            if self.is_at_end() {
+                return Token::new(
+                    TokenType::Error,
+                    "Unterminated triple-quoted string.".to_string(),
+                    pos,
+                );
+            }
+            if self.peek() == '"' && self.peek_next() == '"' && self.source.get(self.current + 2) == Some(&'"') {
+                break;
+            }
+            if self.peek() == '\n' {
+                self.line += 1;
+            }
+            self.advance();
+        }
+        let value: String = self.source[start..self.current].iter().collect();
+        self.advance();
+        self.advance();
+        self.advance();
+        Token::new(TokenType::String, value, pos)
+    }
+
+    /// Scans a `'c'` character literal. A handful of common escapes are
+    /// recognized; anything else after a `\` is taken literally. A body
+    /// that doesn't unescape to exactly one character is a lexer error
+    /// rather than silently becoming a string, since `'ab'` is almost
+    /// always a typo for `"ab"`.
+    fn char_literal(&mut self, pos: Position) -> Token {
+        let mut value = String::new();
+        while self.peek() != '\'' && !self.is_at_end() {
+            if self.peek() == '\\' {
+                self.advance();
+                match self.decode_escape() {
+                    Ok(ch) => value.push(ch),
+                    Err(message) => return Token::new(TokenType::Error, message, pos),
+                }
+            } else {
+                value.push(self.advance());
+            }
+        }
+        if self.is_at_end() {
+            return Token::new(TokenType::Error, "Unterminated character literal.".to_string(), pos);
+        }
+        self.advance(); // closing '
+        if value.chars().count() != 1 {
+            return Token::new(
+                TokenType::Error,
+                format!(
+                    "Character literal must contain exactly one character, found {} ('{}'). Use a string literal (\"...\") for text.",
+                    value.chars().count(),
+                    value
+                ),
+                pos,
+            );
+        }
+        Token::new(TokenType::Char, value, pos)
+    }
+
+    /// Decodes the escape sequence following a `\` the caller has already
+    /// consumed. Shared by string and char literals so `\n`/`\u{...}`/etc.
+    /// behave identically in both.
+    fn decode_escape(&mut self) -> Result<char, String> {
+        if self.is_at_end() {
+            return Err("Unterminated escape sequence.".to_string());
+        }
+        let escaped = self.advance();
+        Ok(match escaped {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'u' => {
+                if self.peek() != '{' {
+                    return Err("Expected '{' after '\\u'.".to_string());
+                }
+                self.advance(); // {
+                let mut digits = String::new();
+                while self.peek() != '}' && !self.is_at_end() {
+                    digits.push(self.advance());
+                }
+                if self.is_at_end() {
+                    return Err("Unterminated unicode escape.".to_string());
+                }
+                self.advance(); // }
+                let code = u32::from_str_radix(&digits, 16)
+                    .map_err(|_| format!("Invalid unicode escape '\\u{{{}}}'.", digits))?;
+                char::from_u32(code)
+                    .ok_or_else(|| format!("Invalid unicode escape '\\u{{{}}}'.", digits))?
+            }
+            other => return Err(format!("Unknown escape sequence '\\{}'.", other)),
+        })
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            match self.peek() {
+                ' ' | '\r' | '\t' => {
+                    self.advance();
+                }
+                '\n' => {
+                    self.line += 1;
+                    self.column = 1;
+                    self.current += 1;
+                    self.byte_offset += 1;
+                }
+                '/' if self.peek_next() == '/' => {
+                    // Exactly `///` (not `////...`) is a doc comment: stop
+                    // here and let `scan_token` produce a real token for
+                    // it instead of discarding it as trivia.
+                    if self.peek_at(2) == '/' && self.peek_at(3) != '/' {
+                        break;
+                    }
+                    while self.peek() != '\n' && !self.is_at_end() {
+                        self.advance();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn make(&self, kind: TokenType, pos: Position) -> Token {
+        let text: String = self.source[self.start..self.current].iter().collect();
+        Token::new(kind, text, pos)
+    }
+
+    fn match_char(&mut self, expected: char) -> bool {
+        if self.is_at_end() || self.source[self.current] != expected {
+            return false;
+        }
+        self.current += 1;
+        self.column += 1;
+        self.byte_offset += expected.len_utf8();
+        true
+    }
+
+    fn advance(&mut self) -> char {
+        let c = self.source[self.current];
+        self.current += 1;
+        self.column += 1;
+        self.byte_offset += c.len_utf8();
+        c
+    }
+
+    fn peek(&self) -> char {
+        *self.source.get(self.current).unwrap_or(&'\0')
+    }
+
+    fn peek_next(&self) -> char {
+        *self.source.get(self.current + 1).unwrap_or(&'\0')
+    }
+
+    fn peek_at(&self, offset: usize) -> char {
+        *self.source.get(self.current + offset).unwrap_or(&'\0')
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.current >= self.source.len()
+    }
+
+    fn position(&self) -> Position {
+        Position::new(self.line, self.column, self.byte_offset)
+    }
+}
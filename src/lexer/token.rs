@@ -0,0 +1,122 @@
+use crate::common::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    // Literals
+    Int,
+    Float,
+    String,
+    Char,
+    Identifier,
+
+    // Keywords
+    Func,
+    Var,
+    Let,
+    Const,
+    If,
+    Else,
+    While,
+    For,
+    In,
+    Match,
+    Return,
+    Break,
+    Continue,
+    Struct,
+    Enum,
+    Interface,
+    Impl,
+    Import,
+    Export,
+    Module,
+    Pub,
+    Extern,
+    From,
+    As,
+    True,
+    False,
+    Void,
+    Null,
+
+    // Punctuation
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Semicolon,
+    Colon,
+    Dot,
+    Question,
+    /// `@`, preceding an attribute like `@deprecated`. Not itself part of
+    /// a general attribute system -- just the punctuation `@deprecated`
+    /// needs, kept out of the keyword list below since the identifier
+    /// after it is checked by the parser rather than reserved here.
+    At,
+
+    // Operators
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Amp,
+    Pipe,
+    Caret,
+    Tilde,
+    Bang,
+    Eq,
+    EqEq,
+    BangEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    LtLt,
+    GtGt,
+    AmpAmp,
+    PipePipe,
+    PlusPlus,
+    MinusMinus,
+    Arrow,
+    FatArrow,
+
+    PlusEq,
+    MinusEq,
+    StarEq,
+    SlashEq,
+    PercentEq,
+    AmpEq,
+    PipeEq,
+    CaretEq,
+    LtLtEq,
+    GtGtEq,
+
+    /// A `///` doc comment. Unlike a plain `//` comment (discarded as
+    /// trivia by the lexer), this becomes a real token so the parser can
+    /// attach its text to the declaration that follows.
+    DocComment,
+
+    Error,
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenType,
+    pub lexeme: String,
+    pub position: Position,
+}
+
+impl Token {
+    pub fn new(kind: TokenType, lexeme: String, position: Position) -> Self {
+        Self {
+            kind,
+            lexeme,
+            position,
+        }
+    }
+}
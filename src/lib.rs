@@ -0,0 +1,59 @@
+//! Matcha is both a standalone compiler/interpreter (`matcha`, in
+//! `main.rs`) and a library other tools can embed: `matcha lsp` and this
+//! crate's own driver are already just callers of the API re-exported
+//! here, not privileged internals.
+//!
+//! [`compile`]/[`compile_source`] run the whole pipeline (lex, parse,
+//! resolve, type-check) and hand back a [`CompileResult`]; [`parse`] and
+//! [`resolve`] expose the earlier stages individually for a caller that
+//! wants to work with the AST or symbol table directly -- an editor
+//! plugin doing its own incremental re-parse, say, rather than shelling
+//! out to the `matcha` binary.
+
+pub mod ast;
+pub mod backend;
+pub mod common;
+pub mod errors;
+pub mod interpreter;
+pub mod ir;
+pub mod lexer;
+pub mod lsp;
+pub mod parser;
+pub mod semantic;
+pub mod utils;
+
+pub use common::{Position, SourceMap};
+pub use utils::compile::{compile, compile_parallel, compile_source, CompileResult};
+
+/// Lexes and parses `source` into an AST, without resolving or
+/// type-checking it. `name` labels diagnostic positions the same way it
+/// does for [`compile`]. Returns the module alongside any diagnostics the
+/// parser reported along the way.
+pub fn parse(name: &str, source: &str) -> (ast::Module, Vec<(String, errors::Diagnostic)>) {
+    let mut bag = errors::DiagnosticBag::new();
+    let mut lexer = lexer::Lexer::new(source);
+    let tokens = lexer.scan_tokens();
+    let mut parser = parser::Parser::new(tokens, name, &mut bag);
+    let module = parser.parse();
+    (module, bag.entries())
+}
+
+/// Resolves and type-checks an already-parsed `module` as a standalone
+/// unit -- the first-pass-then-resolve-then-typecheck pipeline
+/// [`compile_source`] runs, without its project/import-graph/incremental-
+/// cache machinery. Meant for a caller that already has a [`parse`]d
+/// module (or built one directly) and wants its symbol table without
+/// going through a full [`compile`].
+pub fn resolve(
+    name: &str,
+    module: &ast::Module,
+) -> (semantic::environment::SymbolTable, Vec<(String, errors::Diagnostic)>) {
+    let mut bag = errors::DiagnosticBag::new();
+    let mut symtable = semantic::environment::SymbolTable::new();
+    semantic::FirstPassResolver::new(&mut symtable).run(module);
+    semantic::Resolver::new(&mut symtable, name, &mut bag).resolve(module);
+    let mut typechecker = semantic::Typechecker::new(name, &mut bag);
+    typechecker.seed(&symtable);
+    typechecker.run();
+    (symtable, bag.entries())
+}
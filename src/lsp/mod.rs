@@ -0,0 +1,308 @@
+//! `matcha lsp`: a minimal Language Server Protocol server over stdio,
+//! built directly on the existing lexer/parser/resolver rather than a
+//! JSON-RPC or LSP crate, since this crate takes on no external
+//! dependencies. Only what a basic editor integration needs is
+//! implemented: diagnostics on open/change (via [`crate::utils::compile`],
+//! the same pipeline `matcha check` uses), and go-to-definition/hover,
+//! both resolved straight from the resolver's first-pass [`SymbolTable`]
+//! rather than a separate index -- the error-tolerant parser means even a
+//! document mid-edit still yields a usable symbol table.
+//!
+//! Positions in the protocol are 0-indexed UTF-16 code units; this
+//! implementation treats them as 0-indexed `char`s instead, which matches
+//! for all-ASCII source and is judged good enough for a first cut.
+
+mod json;
+mod query;
+
+use crate::common::Position;
+use crate::errors::{Diagnostic, Severity};
+use crate::utils::vfs::{set_source_manager, InMemorySourceManager};
+use json::Json;
+use query::QueryCache;
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+/// Reads and dispatches JSON-RPC messages from stdin until `exit` is
+/// received or stdin closes, writing responses and notifications to
+/// stdout the same way.
+pub fn run() {
+    let mut documents: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut queries = QueryCache::new();
+    // Diagnostics get rendered by `compile_source`'s `bag.report_all()`
+    // (a stderr side effect, harmless alongside stdout's JSON-RPC frames)
+    // as well as collected into `CompileResult.diagnostics`; installing
+    // this overlay means either path can find a source line to snippet
+    // for a buffer that only exists in the editor, not on disk.
+    let overlay = Arc::new(InMemorySourceManager::new());
+    set_source_manager(Box::new(overlay.clone()));
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut stdout = io::stdout();
+
+    while let Some(message) = read_message(&mut input) {
+        let Some(request) = Json::parse(&message) else { continue };
+        let method = request.get("method").and_then(Json::as_str).unwrap_or_default();
+        let id = request.get("id").cloned();
+        let params = request.get("params").cloned().unwrap_or(Json::Null);
+
+        match method {
+            "initialize" => respond(&mut stdout, id, initialize_result()),
+            "shutdown" => respond(&mut stdout, id, Json::Null),
+            "exit" => return,
+            "textDocument/didOpen" => {
+                let uri = text_document_uri(&params);
+                let text = params
+                    .get("textDocument")
+                    .and_then(|doc| doc.get("text"))
+                    .and_then(Json::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                documents.insert(uri.clone(), text.clone());
+                overlay.insert(uri.clone(), text.clone());
+                publish_diagnostics(&mut stdout, &mut queries, &uri, &text);
+            }
+            "textDocument/didChange" => {
+                let uri = text_document_uri(&params);
+                if let Some(text) = params
+                    .get("contentChanges")
+                    .and_then(|changes| changes.index(0))
+                    .and_then(|change| change.get("text"))
+                    .and_then(Json::as_str)
+                {
+                    documents.insert(uri.clone(), text.to_string());
+                    overlay.insert(uri.clone(), text.to_string());
+                    publish_diagnostics(&mut stdout, &mut queries, &uri, text);
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = text_document_uri(&params);
+                documents.remove(&uri);
+                overlay.remove(&uri);
+                queries.remove(&uri);
+            }
+            "textDocument/definition" => {
+                let uri = text_document_uri(&params);
+                let result = documents
+                    .get(&uri)
+                    .and_then(|text| definition(&mut queries, &uri, text, &params))
+                    .unwrap_or(Json::Null);
+                respond(&mut stdout, id, result);
+            }
+            "textDocument/hover" => {
+                let uri = text_document_uri(&params);
+                let result = documents
+                    .get(&uri)
+                    .and_then(|text| hover(&mut queries, &uri, text, &params))
+                    .unwrap_or(Json::Null);
+                respond(&mut stdout, id, result);
+            }
+            // Notifications (no `id`) are silently ignored when
+            // unrecognized; unhandled requests still get an empty
+            // response so a client waiting on one doesn't hang.
+            _ if id.is_some() => respond(&mut stdout, id, Json::Null),
+            _ => {}
+        }
+    }
+}
+
+/// Reads one `Content-Length: N\r\n\r\n<N bytes of JSON>` frame. `None` at
+/// EOF or on a malformed frame -- either way, [`run`]'s loop ends.
+fn read_message(input: &mut impl BufRead) -> Option<String> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut buffer = vec![0u8; content_length?];
+    input.read_exact(&mut buffer).ok()?;
+    String::from_utf8(buffer).ok()
+}
+
+fn write_message(output: &mut impl Write, message: &Json) {
+    let body = message.render();
+    let _ = write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let _ = output.flush();
+}
+
+fn respond(output: &mut impl Write, id: Option<Json>, result: Json) {
+    write_message(
+        output,
+        &Json::object([
+            ("jsonrpc", Json::String("2.0".to_string())),
+            ("id", id.unwrap_or(Json::Null)),
+            ("result", result),
+        ]),
+    );
+}
+
+fn notify(output: &mut impl Write, method: &str, params: Json) {
+    write_message(
+        output,
+        &Json::object([
+            ("jsonrpc", Json::String("2.0".to_string())),
+            ("method", Json::String(method.to_string())),
+            ("params", params),
+        ]),
+    );
+}
+
+fn initialize_result() -> Json {
+    Json::object([(
+        "capabilities",
+        Json::object([
+            // `TextDocumentSyncKind::Full` -- each change carries the
+            // whole document, which suits recompiling from scratch.
+            ("textDocumentSync", Json::Number(1.0)),
+            ("definitionProvider", Json::Bool(true)),
+            ("hoverProvider", Json::Bool(true)),
+        ]),
+    )])
+}
+
+fn text_document_uri(params: &Json) -> String {
+    params
+        .get("textDocument")
+        .and_then(|doc| doc.get("uri"))
+        .and_then(Json::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn position_params(params: &Json) -> Option<(usize, usize)> {
+    let position = params.get("position")?;
+    let line = position.get("line")?.as_f64()? as usize;
+    let character = position.get("character")?.as_f64()? as usize;
+    Some((line, character))
+}
+
+/// Compiles `text` and publishes every diagnostic attributed to `uri` --
+/// the full pipeline (parser, resolver, typechecker, lints), the same as
+/// `matcha check`, just delivered as `publishDiagnostics` instead of
+/// stderr lines. Goes through `queries` so a `hover`/`definition` request
+/// against this same edit doesn't recompile it again.
+fn publish_diagnostics(output: &mut impl Write, queries: &mut QueryCache, uri: &str, text: &str) {
+    let result = queries.get(uri, text);
+    let diagnostics: Vec<Json> = result
+        .diagnostics
+        .iter()
+        .filter(|(file, _)| file == uri)
+        .map(|(_, diagnostic)| diagnostic_to_json(diagnostic))
+        .collect();
+    notify(
+        output,
+        "textDocument/publishDiagnostics",
+        Json::object([
+            ("uri", Json::String(uri.to_string())),
+            ("diagnostics", Json::Array(diagnostics)),
+        ]),
+    );
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic) -> Json {
+    let end = Position::new(
+        diagnostic.position.line,
+        diagnostic.position.column + 1,
+        diagnostic.position.offset + 1,
+    );
+    let severity = match diagnostic.severity {
+        Severity::Error => 1.0,
+        Severity::Warning => 2.0,
+    };
+    Json::object([
+        ("range", range_json(diagnostic.position, end)),
+        ("severity", Json::Number(severity)),
+        ("code", Json::String(diagnostic.code.clone())),
+        ("source", Json::String("matcha".to_string())),
+        ("message", Json::String(diagnostic.message.clone())),
+    ])
+}
+
+fn range_json(start: Position, end: Position) -> Json {
+    Json::object([("start", position_json(start)), ("end", position_json(end))])
+}
+
+/// Converts this crate's 1-indexed [`Position`] to the protocol's
+/// 0-indexed `{line, character}`.
+fn position_json(position: Position) -> Json {
+    Json::object([
+        ("line", Json::Number(position.line.saturating_sub(1) as f64)),
+        ("character", Json::Number(position.column.saturating_sub(1) as f64)),
+    ])
+}
+
+/// The identifier touching column `character` of `text`'s `line`th line
+/// (both 0-indexed), if any -- used to resolve go-to-definition and hover
+/// without needing exact AST position matching for every expression kind.
+fn word_at(text: &str, line: usize, character: usize) -> Option<String> {
+    let chars: Vec<char> = text.lines().nth(line)?.chars().collect();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    let mut start = character.min(chars.len());
+    if start > 0 && (start == chars.len() || !is_word(&chars[start])) && is_word(&chars[start - 1]) {
+        start -= 1;
+    }
+    if chars.get(start).is_none_or(|c| !is_word(c)) {
+        return None;
+    }
+    let begin = (0..=start).rev().find(|&i| !is_word(&chars[i])).map_or(0, |i| i + 1);
+    let end = (start..chars.len()).find(|&i| !is_word(&chars[i])).unwrap_or(chars.len());
+    Some(chars[begin..end].iter().collect())
+}
+
+fn definition(queries: &mut QueryCache, uri: &str, text: &str, params: &Json) -> Option<Json> {
+    let (line, character) = position_params(params)?;
+    let name = word_at(text, line, character)?;
+    let result = queries.get(uri, text);
+    let symtable = &result.symbols;
+    let position = symtable
+        .get_function(&name)
+        .map(|symbol| symbol.position)
+        .or_else(|| symtable.get_struct(&name).map(|symbol| symbol.position))
+        .or_else(|| symtable.get_enum(&name).map(|symbol| symbol.position))
+        .or_else(|| symtable.get_interface(&name).map(|symbol| symbol.position))?;
+    let end = Position::new(position.line, position.column + name.chars().count(), position.offset + name.len());
+    Some(Json::object([
+        ("uri", Json::String(uri.to_string())),
+        ("range", range_json(position, end)),
+    ]))
+}
+
+fn hover(queries: &mut QueryCache, uri: &str, text: &str, params: &Json) -> Option<Json> {
+    let (line, character) = position_params(params)?;
+    let name = word_at(text, line, character)?;
+    let result = queries.get(uri, text);
+    let symtable = &result.symbols;
+    let signature = if let Some(symbol) = symtable.get_function(&name) {
+        let function = symbol.get();
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|param| format!("{}: {:?}", param.name, param.ty.kind))
+            .collect();
+        format!("func {}({}): {:?}", function.name, params.join(", "), function.return_type.kind)
+    } else if symtable.get_struct(&name).is_some() {
+        format!("struct {}", name)
+    } else if symtable.get_enum(&name).is_some() {
+        format!("enum {}", name)
+    } else if symtable.get_interface(&name).is_some() {
+        format!("interface {}", name)
+    } else {
+        return None;
+    };
+    Some(Json::object([(
+        "contents",
+        Json::object([
+            ("kind", Json::String("plaintext".to_string())),
+            ("value", Json::String(signature)),
+        ]),
+    )]))
+}
@@ -0,0 +1,70 @@
+//! Per-document compile memoization for the LSP.
+//!
+//! `matcha lsp` syncs whole documents (`TextDocumentSyncKind::Full` --
+//! see [`super::initialize_result`]), so a single edit already
+//! invalidates the lexer's tokens and everything downstream of them --
+//! there's no finer-grained "which query changed" to track within one
+//! edit. What *is* wasted without this cache is recomputing the same
+//! edit's tokens/AST/symbols more than once: `textDocument/didChange`
+//! used to compile the new text once for diagnostics, and then a
+//! `hover`/`definition` request against that same, still-unedited text
+//! re-lexed, re-parsed and re-ran [`FirstPassResolver`](crate::semantic::FirstPassResolver)
+//! from scratch just to get a [`SymbolTable`]. [`QueryCache`] keeps the
+//! last [`CompileResult`] computed for each open document, keyed by a
+//! hash of its text, so those calls share one compile until the text
+//! actually changes.
+
+use crate::utils::compile::{compile_source, CompileResult};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+fn fingerprint(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct Entry {
+    fingerprint: u64,
+    result: Arc<CompileResult>,
+}
+
+/// Caches the most recently computed [`CompileResult`] per open document
+/// URI.
+#[derive(Default)]
+pub struct QueryCache {
+    entries: HashMap<String, Entry>,
+}
+
+impl QueryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `uri`'s compile result for `text`, recomputing it only if
+    /// `text` differs from whatever this URI was last compiled with.
+    pub fn get(&mut self, uri: &str, text: &str) -> Arc<CompileResult> {
+        let fingerprint = fingerprint(text);
+        if let Some(entry) = self.entries.get(uri) {
+            if entry.fingerprint == fingerprint {
+                return Arc::clone(&entry.result);
+            }
+        }
+        let result = Arc::new(compile_source(uri, text));
+        self.entries.insert(
+            uri.to_string(),
+            Entry {
+                fingerprint,
+                result: Arc::clone(&result),
+            },
+        );
+        result
+    }
+
+    /// Drops a closed document's cached result.
+    pub fn remove(&mut self, uri: &str) {
+        self.entries.remove(uri);
+    }
+}
@@ -0,0 +1,617 @@
+use matcha::ast::Module;
+use matcha::backend::{bytecode, vm, wasm};
+use matcha::errors::DiagnosticBag;
+use matcha::interpreter::Interpreter;
+use matcha::ir::lower::lower;
+use matcha::ir::pass::{ConstantFold, PassManager, SsaConstruction};
+use matcha::lexer::Lexer;
+use matcha::parser::Parser;
+use matcha::semantic::environment::SymbolTable;
+use matcha::semantic::graph::DependencyGraph;
+use matcha::semantic::{FirstPassResolver, Resolver};
+use matcha::utils::compile::{compile, compile_parallel, compile_source};
+use matcha::utils::project::ProjectManifest;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime};
+
+/// The command line, or the arguments to it, couldn't even be understood:
+/// an unknown command, a missing `<file>`, an unreadable input.
+const EXIT_USAGE: i32 = 2;
+/// The command ran to completion, but a diagnostic reached error severity.
+const EXIT_COMPILE_ERROR: i32 = 1;
+/// Something the driver itself expected to always succeed didn't — e.g.
+/// an artifact couldn't be written to disk. Distinct from a usage error
+/// (the invocation was fine; the environment wasn't) and from a compile
+/// error (the input was fine; something else went wrong).
+const EXIT_INTERNAL_ERROR: i32 = 70;
+
+const HELP: &str = "\
+matcha: a compiler and interpreter for the Matcha language
+
+usage: matcha <command> <file> [options]
+
+a `<file>` of `-` reads source from stdin instead (compiled in-memory,
+without touching the filesystem for project lookup or caching).
+
+commands:
+    tokens <file>     lex <file> and write its token stream to <file>.tokens
+    ast <file>        parse and resolve <file>, writing the AST to <file>.ast
+    check <file>      run semantic analysis only; report diagnostics, produce no artifacts
+    build <file>      compile <file>, writing <file>.ast and <file>.wasm
+    run <file>        compile <file> to bytecode and execute it on the VM
+    interpret <file>  walk <file>'s AST directly, without compiling
+    wasm <file>       compile <file> to a standalone <file>.wasm module
+    ir <file>         lower <file> to the mid-level IR, writing <file>.ir
+    doc <file>        render <file>'s `///` doc comments to Markdown in <file>.md
+    watch <file>      recheck <file> and its dependencies on every change
+    explain <code>    print an extended explanation and example for a diagnostic code
+    lsp               run a Language Server Protocol server over stdio
+    help              print this message
+
+options:
+    --strip-dead-code        remove declarations semantic::dce finds unreachable before codegen
+    --parallel-typecheck     type-check this file's functions across multiple threads
+    --timings                report wall time spent in each compiler phase
+    --print-ir-after=<pass>  (ir only) dump the IR to stderr right after <pass> runs
+    --emit=<targets>         additionally write a comma-separated list of:
+                             tokens, tokens-json, ast, ast-json, pretty, symbols, deps, depgraph
+    --error-format=<fmt>     report diagnostics as 'text' (default) or 'json' lines
+    --error-limit=<n>        stop printing errors after <n> (default: unlimited)
+    --color=<when>           color diagnostics 'auto' (default), 'always', or 'never';
+                             'auto' also respects NO_COLOR
+    -W<lint>                 warn on <lint> (the default; only useful after an earlier -A<lint>)
+    -A<lint>                 allow (silence) <lint>: unused-variable, unused-import,
+                             unused-parameter, shadowing, unreachable-code, lossy-conversion,
+                             deprecated
+
+exit codes:
+    0   success
+    1   a diagnostic reached error severity
+    2   the command line itself was invalid
+    70  an internal failure unrelated to the input (e.g. a write failed)";
+
+enum Mode {
+    Tokens,
+    Ast,
+    Check,
+    Build,
+    Run,
+    Interpret,
+    Wasm,
+    Ir,
+    Doc,
+    Watch,
+}
+
+fn main() {
+    let mut args = env::args();
+    let _bin = args.next();
+    let command = match args.next() {
+        Some(command) => command,
+        None => {
+            println!("{}", HELP);
+            finish(EXIT_USAGE);
+        }
+    };
+
+    let mode = match command.as_str() {
+        "tokens" => Mode::Tokens,
+        "ast" => Mode::Ast,
+        "check" => Mode::Check,
+        "build" => Mode::Build,
+        "run" => Mode::Run,
+        "interpret" => Mode::Interpret,
+        "wasm" => Mode::Wasm,
+        "ir" => Mode::Ir,
+        "doc" => Mode::Doc,
+        "watch" => Mode::Watch,
+        "help" | "-h" | "--help" => {
+            println!("{}", HELP);
+            process::exit(0);
+        }
+        "explain" => {
+            let code = match args.next() {
+                Some(code) => code,
+                None => {
+                    eprintln!("usage: matcha explain <code>");
+                    finish(EXIT_USAGE);
+                }
+            };
+            explain(&code);
+        }
+        "lsp" => {
+            matcha::lsp::run();
+            process::exit(0);
+        }
+        other => {
+            eprintln!("error: unknown command '{}'\n", other);
+            eprintln!("{}", HELP);
+            finish(EXIT_USAGE);
+        }
+    };
+
+    let path = expect_path(&mut args, &command);
+    let rest: Vec<String> = args.collect();
+    let strip_dead_code = rest.iter().any(|arg| arg == "--strip-dead-code");
+    let parallel_typecheck = rest.iter().any(|arg| arg == "--parallel-typecheck");
+    let print_timings = rest.iter().any(|arg| arg == "--timings");
+
+    match rest.iter().find_map(|arg| arg.strip_prefix("--error-format=")) {
+        None | Some("text") => {}
+        Some("json") => matcha::errors::set_format(matcha::errors::Format::Json),
+        Some(other) => {
+            eprintln!("error: unknown --error-format '{}' (expected 'text' or 'json')", other);
+            finish(EXIT_USAGE);
+        }
+    }
+
+    if let Some(limit) = rest.iter().find_map(|arg| arg.strip_prefix("--error-limit=")) {
+        match limit.parse::<usize>() {
+            Ok(limit) => matcha::errors::set_error_limit(limit),
+            Err(_) => {
+                eprintln!("error: invalid --error-limit '{}' (expected a non-negative integer)", limit);
+                finish(EXIT_USAGE);
+            }
+        }
+    }
+
+    match rest.iter().find_map(|arg| arg.strip_prefix("--color=")) {
+        None | Some("auto") => {}
+        Some("always") => matcha::errors::set_color_mode(matcha::errors::ColorMode::Always),
+        Some("never") => matcha::errors::set_color_mode(matcha::errors::ColorMode::Never),
+        Some(other) => {
+            eprintln!("error: unknown --color '{}' (expected 'auto', 'always', or 'never')", other);
+            finish(EXIT_USAGE);
+        }
+    }
+
+    for arg in &rest {
+        let (set_lint, name): (fn(matcha::semantic::lint::Lint), &str) = if let Some(name) = arg.strip_prefix("-A") {
+            (matcha::semantic::lint::allow, name)
+        } else if let Some(name) = arg.strip_prefix("-W") {
+            (matcha::semantic::lint::warn, name)
+        } else {
+            continue;
+        };
+        match matcha::semantic::lint::Lint::from_name(name) {
+            Some(lint) => set_lint(lint),
+            None => {
+                eprintln!("error: unknown lint '{}'", name);
+                finish(EXIT_USAGE);
+            }
+        }
+    }
+
+    if matches!(mode, Mode::Watch) {
+        watch(&path, strip_dead_code);
+    }
+
+    let from_stdin = path == "-";
+    let source = if from_stdin {
+        let mut source = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut source) {
+            eprintln!("error: could not read stdin: {}", e);
+            finish(EXIT_INTERNAL_ERROR);
+        }
+        source
+    } else {
+        match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("error: could not read '{}': {}", path, e);
+                finish(EXIT_INTERNAL_ERROR);
+            }
+        }
+    };
+
+    let result = if from_stdin {
+        compile_source("<stdin>", &source)
+    } else if parallel_typecheck {
+        compile_parallel(&path, &source, strip_dead_code)
+    } else {
+        compile(&path, &source, strip_dead_code)
+    };
+    let (module, had_error, mut timings) = (result.module, result.had_error, result.timings);
+
+    if let Some(targets) = rest.iter().find_map(|arg| arg.strip_prefix("--emit=")) {
+        emit(targets, &path, &source, &module);
+    }
+
+    match mode {
+        Mode::Tokens => {
+            write_artifact(&format!("{}.tokens", path), tokens_dump(&source));
+        }
+        Mode::Check => {
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Ast => {
+            write_artifact(&format!("{}.ast", path), ast_dump(&module));
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Build => {
+            write_artifact(&format!("{}.ast", path), ast_dump(&module));
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+            let (bytes, wasm_had_error) = timings.time("codegen", || wasm::compile(&module, &path));
+            write_artifact(&format!("{}.wasm", path), bytes);
+            if wasm_had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Run => {
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+            let (program, backend_had_error) = timings.time("codegen", || bytecode::compile(&module, &path));
+            if backend_had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+            if let Err(e) = vm::Vm::new(&program).run() {
+                eprintln!("runtime error: {}", e.0);
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Interpret => {
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+            if Interpreter::new(&path).run(&module) {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Wasm => {
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+            let (bytes, wasm_had_error) = timings.time("codegen", || wasm::compile(&module, &path));
+            write_artifact(&format!("{}.wasm", path), bytes);
+            if wasm_had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Ir => {
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+            let (mut program, ir_had_error) = timings.time("codegen", || lower(&module, &path));
+
+            let mut passes = PassManager::new();
+            passes.add(Box::new(SsaConstruction)).add(Box::new(ConstantFold));
+            if let Some(pass_name) = rest.iter().find_map(|arg| arg.strip_prefix("--print-ir-after=").map(str::to_string)) {
+                passes.print_ir_after(pass_name);
+            }
+            for report in passes.run(&mut program) {
+                eprintln!("pass '{}' took {:?}", report.name, report.duration);
+            }
+
+            write_artifact(&format!("{}.ir", path), format!("{:#?}", program));
+            if ir_had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Doc => {
+            write_artifact(&format!("{}.md", path), doc_dump(&path, &source));
+            if had_error {
+                finish(EXIT_COMPILE_ERROR);
+            }
+        }
+        Mode::Watch => unreachable!("handled above, before `path` is even read"),
+    }
+
+    if print_timings {
+        print_timings_report(&timings);
+    }
+    finish(0);
+}
+
+/// Prints `timings` to stderr in whichever format `--error-format` picked
+/// for diagnostics, so `--timings` output composes with `--error-format`
+/// instead of needing its own separate flag.
+fn print_timings_report(timings: &matcha::utils::timings::PhaseTimings) {
+    match matcha::errors::format() {
+        matcha::errors::Format::Text => eprint!("{}", timings.to_table()),
+        matcha::errors::Format::Json => eprintln!("{}", timings.to_json()),
+    }
+}
+
+/// Prints this invocation's diagnostic summary (if it reported anything)
+/// and exits with `code`. Every way `main` ends other than `--help`/`help`
+/// funnels through here exactly once, so the summary always covers the
+/// whole run — front end and backend alike — and is never printed twice.
+fn finish(code: i32) -> ! {
+    let counts = matcha::errors::counts();
+    if !counts.is_empty() {
+        match matcha::errors::format() {
+            matcha::errors::Format::Text => eprintln!("{}", counts.summary()),
+            matcha::errors::Format::Json => {
+                eprintln!("{{\"summary\":{{\"errors\":{},\"warnings\":{}}}}}", counts.errors, counts.warnings)
+            }
+        }
+    }
+    process::exit(code);
+}
+
+/// Recompiles `path` (and reports whether it succeeded) whenever it or
+/// any of its transitive dependencies change on disk. Polling modified
+/// times is the only portable option here — there's no dependency-free
+/// way to subscribe to filesystem events, and this crate doesn't reach
+/// for one just for `watch`. [`compile`]'s own incremental cache still
+/// applies underneath each recompile, so touching one file in a large
+/// project doesn't force every other unchanged file back through the
+/// resolver and typechecker.
+fn watch(path: &str, strip_dead_code: bool) -> ! {
+    println!("watching '{}' for changes (ctrl-c to stop)", path);
+    let mut mtimes: HashMap<PathBuf, SystemTime> = HashMap::new();
+    loop {
+        let watched = watched_files(path);
+        let current: HashMap<PathBuf, SystemTime> = watched
+            .into_iter()
+            .filter_map(|file| {
+                let mtime = fs::metadata(&file).and_then(|meta| meta.modified()).ok()?;
+                Some((file, mtime))
+            })
+            .collect();
+
+        if current != mtimes {
+            mtimes = current;
+            match fs::read_to_string(path) {
+                Ok(source) => {
+                    let result = compile(path, &source, strip_dead_code);
+                    let status = if result.had_error { "failed" } else { "ok" };
+                    let summary = result.counts.summary();
+                    if summary.is_empty() {
+                        println!("[{}] {}", path, status);
+                    } else {
+                        println!("[{}] {} ({})", path, status, summary);
+                    }
+                }
+                Err(e) => eprintln!("error: could not read '{}': {}", path, e),
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(300));
+    }
+}
+
+/// `path` plus every file it transitively imports — the same set
+/// [`matcha::utils::incremental`] fingerprints a compile's cache against.
+fn watched_files(path: &str) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(path)];
+    if let Some(project) = Path::new(path)
+        .parent()
+        .and_then(ProjectManifest::find)
+        .and_then(|manifest_path| ProjectManifest::load(&manifest_path).ok())
+    {
+        files.extend(DependencyGraph::discover(Path::new(path), &project).files());
+    }
+    files
+}
+
+/// Writes each `--emit` target's artifact next to `path`, independent of
+/// whichever command is actually running: `matcha check foo.mt
+/// --emit=symbols,deps` reports diagnostics as usual and also drops
+/// `foo.mt.symbols`/`foo.mt.deps` alongside it.
+fn emit(targets: &str, path: &str, source: &str, module: &Module) {
+    for target in targets.split(',') {
+        match target {
+            "tokens" => write_artifact(&format!("{}.tokens", path), tokens_dump(source)),
+            "tokens-json" => write_artifact(&format!("{}.tokens.json", path), tokens_json_dump(source)),
+            "ast" => write_artifact(&format!("{}.ast", path), ast_dump(module)),
+            "ast-json" => write_artifact(&format!("{}.ast.json", path), matcha::ast::json::to_json(module)),
+            "pretty" => write_artifact(&format!("{}.pretty.mt", path), matcha::ast::printer::print(module)),
+            "symbols" => write_artifact(&format!("{}.symbols", path), symbols_dump(path, source)),
+            "deps" => write_artifact(&format!("{}.deps", path), deps_dump(path)),
+            "depgraph" => write_artifact(&format!("{}.dot", path), depgraph_dump(path)),
+            other => eprintln!("warning: unknown --emit target '{}'", other),
+        }
+    }
+}
+
+fn tokens_dump(source: &str) -> String {
+    let mut lexer = Lexer::new(source);
+    format!("{:#?}", lexer.scan_tokens())
+}
+
+fn tokens_json_dump(source: &str) -> String {
+    let mut lexer = Lexer::new(source);
+    matcha::lexer::json::to_json(&lexer.scan_tokens())
+}
+
+fn ast_dump(module: &Module) -> String {
+    format!("{:#?}", module)
+}
+
+/// Re-resolves `source` from scratch into a fresh [`SymbolTable`] and
+/// renders every top-level declaration it found — this bypasses the
+/// incremental cache [`compile`] relies on, since a debug dump should
+/// reflect this exact run rather than a possibly-stale verdict.
+fn symbols_dump(path: &str, source: &str) -> String {
+    let mut bag = DiagnosticBag::new();
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.scan_tokens();
+    let mut parser = Parser::new(tokens, path, &mut bag);
+    let module = parser.parse();
+
+    let mut symtable = SymbolTable::new();
+    FirstPassResolver::new(&mut symtable).run(&module);
+    Resolver::new(&mut symtable, path, &mut bag).resolve(&module);
+    bag.report_all();
+
+    let mut out = String::new();
+    for symbol in &symtable.functions {
+        let function = symbol.get();
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|param| format!("{}: {:?}", param.name, param.ty.kind))
+            .collect();
+        out.push_str(&format!(
+            "fn {}({}) -> {:?} at {}\n",
+            symbol.name,
+            params.join(", "),
+            function.return_type.kind,
+            symbol.position
+        ));
+    }
+    for symbol in &symtable.structs {
+        out.push_str(&format!("struct {} at {}\n", symbol.name, symbol.position));
+    }
+    for symbol in &symtable.enums {
+        out.push_str(&format!("enum {} at {}\n", symbol.name, symbol.position));
+    }
+    for symbol in &symtable.interfaces {
+        out.push_str(&format!("interface {} at {}\n", symbol.name, symbol.position));
+    }
+    for symbol in &symtable.impls {
+        let imp = symbol.get();
+        out.push_str(&format!(
+            "impl {} for {} at {}\n",
+            imp.interface_name, imp.target_name, symbol.position
+        ));
+    }
+    out
+}
+
+/// Re-resolves `source` from scratch (the same first-pass-only resolve
+/// [`symbols_dump`] and `matcha lsp` use) and renders every top-level
+/// declaration's `///` doc comment as Markdown -- one section per kind,
+/// undocumented declarations included so the output still doubles as a
+/// full API listing.
+fn doc_dump(path: &str, source: &str) -> String {
+    let mut bag = DiagnosticBag::new();
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.scan_tokens();
+    let mut parser = Parser::new(tokens, path, &mut bag);
+    let module = parser.parse();
+
+    let mut symtable = SymbolTable::new();
+    FirstPassResolver::new(&mut symtable).run(&module);
+
+    let mut out = format!("# {}\n", path);
+
+    if !symtable.functions.is_empty() {
+        out.push_str("\n## Functions\n");
+        for symbol in &symtable.functions {
+            let function = symbol.get();
+            let params: Vec<String> = function
+                .params
+                .iter()
+                .map(|param| format!("{}: {:?}", param.name, param.ty.kind))
+                .collect();
+            out.push_str(&format!(
+                "\n### `func {}({}): {:?}`\n",
+                symbol.name,
+                params.join(", "),
+                function.return_type.kind
+            ));
+            if let Some(doc) = &function.doc {
+                out.push_str(&format!("\n{}\n", doc));
+            }
+        }
+    }
+    if !symtable.structs.is_empty() {
+        out.push_str("\n## Structs\n");
+        for symbol in &symtable.structs {
+            let strct = symbol.get();
+            out.push_str(&format!("\n### `struct {}`\n", symbol.name));
+            if let Some(doc) = &strct.doc {
+                out.push_str(&format!("\n{}\n", doc));
+            }
+        }
+    }
+    if !symtable.enums.is_empty() {
+        out.push_str("\n## Enums\n");
+        for symbol in &symtable.enums {
+            let enm = symbol.get();
+            out.push_str(&format!("\n### `enum {}`\n", symbol.name));
+            if let Some(doc) = &enm.doc {
+                out.push_str(&format!("\n{}\n", doc));
+            }
+        }
+    }
+    if !symtable.interfaces.is_empty() {
+        out.push_str("\n## Interfaces\n");
+        for symbol in &symtable.interfaces {
+            out.push_str(&format!("\n### `interface {}`\n", symbol.name));
+        }
+    }
+    out
+}
+
+/// Renders the entry file's import graph, one `path -> [deps]` line per
+/// module. Loose scripts with no `matcha.toml` have no project to
+/// resolve imports against, so there's nothing to discover.
+fn deps_dump(path: &str) -> String {
+    let project = Path::new(path)
+        .parent()
+        .and_then(ProjectManifest::find)
+        .and_then(|manifest_path| ProjectManifest::load(&manifest_path).ok());
+
+    let Some(project) = project else {
+        return "(no project manifest found; single-file scripts have no import graph)\n".to_string();
+    };
+
+    let graph = DependencyGraph::discover(Path::new(path), &project);
+    let mut out = String::new();
+    for (name, deps) in graph.edges() {
+        out.push_str(&format!("{} -> [{}]\n", name, deps.join(", ")));
+    }
+    out
+}
+
+/// Same import graph as [`deps_dump`], rendered as Graphviz DOT instead
+/// of arrow lines, for visualizing rather than reading by eye.
+fn depgraph_dump(path: &str) -> String {
+    let project = Path::new(path)
+        .parent()
+        .and_then(ProjectManifest::find)
+        .and_then(|manifest_path| ProjectManifest::load(&manifest_path).ok());
+
+    let Some(project) = project else {
+        return "digraph deps {\n    // no project manifest found; single-file scripts have no import graph\n}\n".to_string();
+    };
+
+    DependencyGraph::discover(Path::new(path), &project).to_dot()
+}
+
+/// Prints the catalog entry for `code` (`matcha explain E200`) and exits.
+/// An unrecognized code isn't a usage error -- it's just not in the
+/// catalog yet -- so this exits 0 either way.
+fn explain(code: &str) -> ! {
+    match matcha::errors::catalog::lookup(code) {
+        Some(entry) => {
+            println!("{}: {}\n", entry.code, entry.title);
+            println!("{}\n", entry.explanation);
+            println!("example:\n{}", entry.example);
+        }
+        None => println!("no explanation is available for '{}'", code),
+    }
+    process::exit(0);
+}
+
+fn write_artifact(path: &str, contents: impl AsRef<[u8]>) {
+    if let Err(e) = fs::write(path, contents) {
+        eprintln!("error: could not write '{}': {}", path, e);
+        finish(EXIT_INTERNAL_ERROR);
+    }
+}
+
+fn expect_path(args: &mut env::Args, command: &str) -> String {
+    match args.next() {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: matcha {} <file>", command);
+            finish(EXIT_USAGE);
+        }
+    }
+}
@@ -0,0 +1,1470 @@
+//! Recursive-descent parser turning a token stream into an [`ast::Module`].
+
+use crate::ast::*;
+use crate::common::Position;
+use crate::errors::{Diagnostic, DiagnosticBag};
+use crate::lexer::{Token, TokenType};
+use std::sync::Arc;
+
+pub struct Parser<'a> {
+    tokens: Vec<Token>,
+    current: usize,
+    pub had_error: bool,
+    file: String,
+    bag: &'a mut DiagnosticBag,
+    /// The doc comment [`Self::collect_doc_comment`] most recently
+    /// gathered, waiting to be claimed by [`Self::take_doc`] from the
+    /// declaration parsed right after it.
+    pending_doc: Option<String>,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(mut tokens: Vec<Token>, file: impl Into<String>, bag: &'a mut DiagnosticBag) -> Self {
+        // `peek`/`is_at_end`/`previous` all assume there's at least one
+        // token to look at (normally the `Eof` [`Lexer::scan_tokens`]
+        // always appends); guarantee that here too, so a caller that hands
+        // in an empty `Vec` directly can't turn every token helper below
+        // into an out-of-bounds panic.
+        if tokens.is_empty() {
+            tokens.push(Token::new(TokenType::Eof, String::new(), Position::new(1, 1, 0)));
+        }
+        Self {
+            tokens,
+            current: 0,
+            had_error: false,
+            file: file.into(),
+            bag,
+            pending_doc: None,
+        }
+    }
+
+    pub fn parse(&mut self) -> Module {
+        let mut statements = Vec::new();
+        while !self.is_at_end() {
+            statements.push(self.declaration());
+        }
+        Module { statements }
+    }
+
+    // ---- declarations ----------------------------------------------
+
+    /// Parses one top-level declaration, then -- if parsing it raised a new
+    /// error -- skips ahead to the next likely declaration boundary before
+    /// returning. Without this, a single malformed declaration's errors
+    /// keep landing on stale tokens as every following declaration tries
+    /// (and fails) to make sense of what's left of the broken one.
+    fn declaration(&mut self) -> Statement {
+        self.collect_doc_comment();
+        let had_error_before = self.had_error;
+        let statement = self.declaration_inner();
+        if self.had_error && !had_error_before {
+            self.synchronize();
+        }
+        statement
+    }
+
+    /// Gathers a run of leading `///` doc comment tokens into
+    /// [`Self::pending_doc`] for [`Self::take_doc`] to claim. Only
+    /// [`Self::function_decl`]/[`Self::struct_decl`]/[`Self::enum_decl`]
+    /// actually read it back; anywhere else (a stray doc comment before a
+    /// statement, a field, a method) it's simply overwritten by the next
+    /// call and never surfaces, since only those three AST nodes carry a
+    /// `doc` field.
+    fn collect_doc_comment(&mut self) {
+        let mut lines = Vec::new();
+        while self.check(TokenType::DocComment) {
+            lines.push(self.peek().lexeme.clone());
+            self.advance();
+        }
+        if !lines.is_empty() {
+            self.pending_doc = Some(lines.join("\n"));
+        }
+    }
+
+    fn take_doc(&mut self) -> Option<String> {
+        self.pending_doc.take()
+    }
+
+    /// `@deprecated` or `@deprecated("message")` immediately before a
+    /// declaration. Returns `None` if there's no `@` here at all,
+    /// `Some(String::new())` for a bare `@deprecated`, or `Some(message)`
+    /// for one with a parenthesized message. `deprecated` isn't reserved
+    /// as its own [`TokenType`] -- it's checked as plain identifier text
+    /// after the `@`, the same way an unknown attribute name is reported
+    /// as an error here rather than by the lexer.
+    fn parse_deprecated_attribute(&mut self) -> Option<String> {
+        if !self.check(TokenType::At) {
+            return None;
+        }
+        self.advance(); // @
+        let name = self.consume_identifier("Expected 'deprecated' after '@'.");
+        if name != "deprecated" {
+            let position = self.previous().position;
+            self.error(&format!("Unknown attribute '@{}'.", name), position);
+            return Some(String::new());
+        }
+        if self.match_token(&[TokenType::LParen]).is_some() {
+            let message = self.consume(TokenType::String, "Expected a string message.").lexeme;
+            self.consume(TokenType::RParen, "Expected ')' after deprecation message.");
+            Some(message)
+        } else {
+            Some(String::new())
+        }
+    }
+
+    /// `<T: Bound1 + Bound2, U>` immediately after a function name, before
+    /// its parameter list. Returns an empty `Vec` if there's no `<` here
+    /// at all. Bound names are stored as plain strings and checked against
+    /// declared interfaces later, in [`crate::semantic::Resolver`] -- this
+    /// is pure syntax, so an unknown bound isn't an error yet.
+    fn parse_type_params(&mut self) -> Vec<TypeParam> {
+        if self.match_token(&[TokenType::Lt]).is_none() {
+            return Vec::new();
+        }
+        let mut params = Vec::new();
+        if !self.check(TokenType::Gt) {
+            loop {
+                let position = self.peek().position;
+                let name = self.consume_identifier("Expected type parameter name.");
+                let mut bounds = Vec::new();
+                if self.match_token(&[TokenType::Colon]).is_some() {
+                    loop {
+                        bounds.push(self.consume_identifier("Expected bound name."));
+                        if self.match_token(&[TokenType::Plus]).is_none() {
+                            break;
+                        }
+                    }
+                }
+                params.push(TypeParam { name, bounds, position });
+                if self.match_token(&[TokenType::Comma]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::Gt, "Expected '>' after type parameters.");
+        params
+    }
+
+    /// Skips tokens until just past a `;` or right before a keyword that
+    /// starts a new declaration or statement, whichever comes first.
+    fn synchronize(&mut self) {
+        self.advance();
+        while !self.is_at_end() {
+            if self.previous().kind == TokenType::Semicolon {
+                return;
+            }
+            if matches!(
+                self.peek().kind,
+                TokenType::Func
+                    | TokenType::Struct
+                    | TokenType::Enum
+                    | TokenType::Interface
+                    | TokenType::Impl
+                    | TokenType::Import
+                    | TokenType::Export
+                    | TokenType::Module
+                    | TokenType::Pub
+                    | TokenType::Extern
+                    | TokenType::At
+                    | TokenType::If
+                    | TokenType::While
+                    | TokenType::For
+                    | TokenType::Return
+            ) {
+                return;
+            }
+            self.advance();
+        }
+    }
+
+    fn declaration_inner(&mut self) -> Statement {
+        let deprecated = self.parse_deprecated_attribute();
+        let is_pub = self.match_token(&[TokenType::Pub]).is_some();
+        if self.check(TokenType::Func) {
+            return self.function_decl(is_pub, deprecated);
+        }
+        if self.check(TokenType::Extern) {
+            return self.extern_decl(is_pub, deprecated);
+        }
+        if deprecated.is_some() {
+            let position = self.peek().position;
+            self.error("'@deprecated' is only supported on function declarations.", position);
+        }
+        if self.check(TokenType::Struct) {
+            return self.struct_decl(is_pub);
+        }
+        if self.check(TokenType::Enum) {
+            return self.enum_decl(is_pub);
+        }
+        if self.check(TokenType::Interface) {
+            return self.interface_decl(is_pub);
+        }
+        if is_pub {
+            let position = self.peek().position;
+            self.error("Expected a declaration after 'pub'.", position);
+        }
+        if self.check(TokenType::Impl) {
+            return self.impl_decl();
+        }
+        if self.check(TokenType::Import) {
+            return self.import_decl();
+        }
+        if self.check(TokenType::Export) {
+            return self.export_block();
+        }
+        if self.check(TokenType::Module) {
+            return self.module_decl();
+        }
+        self.statement()
+    }
+
+    fn function_decl(&mut self, is_pub: bool, deprecated: Option<String>) -> Statement {
+        let pos = self.peek().position;
+        // Taken before `self.block()` below runs -- parsing the body
+        // walks nested statements through `declaration()`, which would
+        // otherwise overwrite `pending_doc` with a doc comment from
+        // inside the body before this function got to claim its own.
+        let doc = self.take_doc();
+        self.advance(); // func
+        let (receiver, receiver_name) = if self.check(TokenType::LParen) {
+            self.advance(); // (
+            let receiver = self.consume_identifier("Expected receiver type name.");
+            // `func (Point) new(...)` (static, no binding) vs.
+            // `func (Int32 n) abs(...)` (extension method, `n` bound to
+            // the receiver value inside the body).
+            let receiver_name = if !self.check(TokenType::RParen) {
+                Some(self.consume_identifier("Expected receiver binding name."))
+            } else {
+                None
+            };
+            self.consume(TokenType::RParen, "Expected ')' after receiver.");
+            (Some(receiver), receiver_name)
+        } else {
+            (None, None)
+        };
+        let name = self.consume_identifier("Expected function name.");
+        let type_params = self.parse_type_params();
+        self.consume(TokenType::LParen, "Expected '(' after function name.");
+        let mut params = Vec::new();
+        if !self.check(TokenType::RParen) {
+            loop {
+                let pname = self.consume_identifier("Expected parameter name.");
+                self.consume(TokenType::Colon, "Expected ':' after parameter name.");
+                let ty = self.type_();
+                params.push(Param { name: pname, ty });
+                if self.match_token(&[TokenType::Comma]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RParen, "Expected ')' after parameters.");
+        let return_type = if self.match_token(&[TokenType::Colon]).is_some() {
+            self.type_()
+        } else {
+            Type::new(TypeKind::Void, pos)
+        };
+        let body = self.block();
+        let func = Arc::new(Function {
+            name,
+            params,
+            return_type,
+            body,
+            is_pub,
+            position: pos,
+            doc,
+            receiver,
+            receiver_name,
+            extern_info: None,
+            deprecated,
+            type_params,
+        });
+        Statement::new(StatementKind::FunctionDecl(func), pos)
+    }
+
+    /// `extern "ABI" func name(params): RetType [from "lib"] [as "symbol"];`
+    /// -- a function with no Matcha body, implemented by a native library
+    /// instead. `from`/`as` mirror the wording `import ... as alias`
+    /// already uses for renaming a bound name.
+    fn extern_decl(&mut self, is_pub: bool, deprecated: Option<String>) -> Statement {
+        let pos = self.peek().position;
+        let doc = self.take_doc();
+        self.advance(); // extern
+        let abi = self.consume(TokenType::String, "Expected ABI string after 'extern'.").lexeme;
+        self.consume(TokenType::Func, "Expected 'func' after extern ABI.");
+        let name = self.consume_identifier("Expected function name.");
+        self.consume(TokenType::LParen, "Expected '(' after function name.");
+        let mut params = Vec::new();
+        if !self.check(TokenType::RParen) {
+            loop {
+                let pname = self.consume_identifier("Expected parameter name.");
+                self.consume(TokenType::Colon, "Expected ':' after parameter name.");
+                let ty = self.type_();
+                params.push(Param { name: pname, ty });
+                if self.match_token(&[TokenType::Comma]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RParen, "Expected ')' after parameters.");
+        let return_type = if self.match_token(&[TokenType::Colon]).is_some() {
+            self.type_()
+        } else {
+            Type::new(TypeKind::Void, pos)
+        };
+        let library = if self.match_token(&[TokenType::From]).is_some() {
+            Some(self.consume(TokenType::String, "Expected library name string after 'from'.").lexeme)
+        } else {
+            None
+        };
+        let symbol = if self.match_token(&[TokenType::As]).is_some() {
+            Some(self.consume(TokenType::String, "Expected symbol name string after 'as'.").lexeme)
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expected ';' after extern function declaration.");
+        let func = Arc::new(Function {
+            name,
+            params,
+            return_type,
+            body: Vec::new(),
+            is_pub,
+            position: pos,
+            doc,
+            receiver: None,
+            receiver_name: None,
+            extern_info: Some(ExternInfo { abi, library, symbol }),
+            deprecated,
+            type_params: Vec::new(),
+        });
+        Statement::new(StatementKind::FunctionDecl(func), pos)
+    }
+
+    fn struct_decl(&mut self, is_pub: bool) -> Statement {
+        let pos = self.peek().position;
+        let doc = self.take_doc();
+        self.advance(); // struct
+        let name = self.consume_identifier("Expected struct name.");
+        let type_params = self.parse_type_params();
+        self.consume(TokenType::LBrace, "Expected '{' before struct body.");
+        let mut fields = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            // Fields don't carry their own doc text yet -- just make sure
+            // a `///` above one doesn't trip up field parsing.
+            self.collect_doc_comment();
+            let field_is_pub = self.match_token(&[TokenType::Pub]).is_some();
+            let fname = self.consume_identifier("Expected field name.");
+            self.consume(TokenType::Colon, "Expected ':' after field name.");
+            let ty = self.type_();
+            fields.push(Field {
+                name: fname,
+                ty,
+                is_pub: field_is_pub,
+            });
+            self.match_token(&[TokenType::Comma]);
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after struct body.");
+        let strct = Arc::new(Struct {
+            name,
+            fields,
+            is_pub,
+            position: pos,
+            doc,
+            type_params,
+        });
+        Statement::new(StatementKind::StructDecl(strct), pos)
+    }
+
+    fn enum_decl(&mut self, is_pub: bool) -> Statement {
+        let pos = self.peek().position;
+        let doc = self.take_doc();
+        self.advance(); // enum
+        let name = self.consume_identifier("Expected enum name.");
+        let underlying_type = if self.match_token(&[TokenType::Colon]).is_some() {
+            Some(self.type_())
+        } else {
+            None
+        };
+        self.consume(TokenType::LBrace, "Expected '{' before enum body.");
+        let mut variants = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            self.collect_doc_comment();
+            let vname = self.consume_identifier("Expected variant name.");
+            let value = if self.match_token(&[TokenType::Eq]).is_some() {
+                Some(self.expression())
+            } else {
+                None
+            };
+            variants.push(EnumVariant { name: vname, value });
+            self.match_token(&[TokenType::Comma]);
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after enum body.");
+        let enm = Arc::new(Enum {
+            name,
+            variants,
+            is_pub,
+            position: pos,
+            doc,
+            underlying_type,
+        });
+        Statement::new(StatementKind::EnumDecl(enm), pos)
+    }
+
+    fn interface_decl(&mut self, is_pub: bool) -> Statement {
+        let pos = self.peek().position;
+        self.advance(); // interface
+        let name = self.consume_identifier("Expected interface name.");
+        self.consume(TokenType::LBrace, "Expected '{' before interface body.");
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            self.collect_doc_comment();
+            self.consume(TokenType::Func, "Expected method signature.");
+            let mpos = self.peek().position;
+            let mname = self.consume_identifier("Expected method name.");
+            self.consume(TokenType::LParen, "Expected '(' after method name.");
+            let mut params = Vec::new();
+            if !self.check(TokenType::RParen) {
+                loop {
+                    let pname = self.consume_identifier("Expected parameter name.");
+                    self.consume(TokenType::Colon, "Expected ':' after parameter name.");
+                    let ty = self.type_();
+                    params.push(Param { name: pname, ty });
+                    if self.match_token(&[TokenType::Comma]).is_none() {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RParen, "Expected ')' after parameters.");
+            let return_type = if self.match_token(&[TokenType::Colon]).is_some() {
+                self.type_()
+            } else {
+                Type::new(TypeKind::Void, pos)
+            };
+            // A default implementation is written with a `{ ... }` body
+            // right here instead of a bare `;`, the same distinction
+            // `extern func ...;` vs. an ordinary `func ...() { ... }`
+            // makes for top-level functions.
+            let default_body = if self.check(TokenType::LBrace) {
+                Some(self.block())
+            } else {
+                self.consume(TokenType::Semicolon, "Expected ';' after method signature.");
+                None
+            };
+            methods.push(InterfaceMethodSig {
+                name: mname,
+                params,
+                return_type,
+                position: mpos,
+                default_body,
+            });
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after interface body.");
+        let interface = Arc::new(Interface {
+            name,
+            methods,
+            is_pub,
+            position: pos,
+        });
+        Statement::new(StatementKind::InterfaceDecl(interface), pos)
+    }
+
+    fn impl_decl(&mut self) -> Statement {
+        let pos = self.peek().position;
+        self.advance(); // impl
+        let interface_name = self.consume_identifier("Expected interface name.");
+        self.consume(TokenType::For, "Expected 'for' after interface name.");
+        let target_name = self.consume_identifier("Expected target type name.");
+        self.consume(TokenType::LBrace, "Expected '{' before impl body.");
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            self.collect_doc_comment();
+            let method_deprecated = self.parse_deprecated_attribute();
+            let method_is_pub = self.match_token(&[TokenType::Pub]).is_some();
+            match self.function_decl(method_is_pub, method_deprecated).kind {
+                StatementKind::FunctionDecl(func) => methods.push(func),
+                _ => unreachable!(),
+            }
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after impl body.");
+        let imp = Arc::new(Impl {
+            interface_name,
+            target_name,
+            methods,
+            position: pos,
+        });
+        Statement::new(StatementKind::ImplBlock(imp), pos)
+    }
+
+    /// `module Name { <decl>* }`: reuses [`Self::declaration`] for the
+    /// body, the same as the top-level `parse` loop, so a module block
+    /// can hold anything a file can (including another `module` block,
+    /// for arbitrarily deep nesting).
+    fn module_decl(&mut self) -> Statement {
+        let pos = self.peek().position;
+        let doc = self.take_doc();
+        self.advance(); // module
+        let name = self.consume_identifier("Expected module name.");
+        self.consume(TokenType::LBrace, "Expected '{' before module body.");
+        let mut statements = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            statements.push(self.declaration());
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after module body.");
+        let block = Arc::new(ModuleBlock {
+            name,
+            statements,
+            position: pos,
+            doc,
+        });
+        Statement::new(StatementKind::ModuleDecl(block), pos)
+    }
+
+    fn import_decl(&mut self) -> Statement {
+        let pos = self.peek().position;
+        self.advance(); // import
+        let mut path = vec![self.consume_identifier("Expected module path.")];
+        while self.match_token(&[TokenType::Dot]).is_some() {
+            path.push(self.consume_identifier("Expected module path segment."));
+        }
+        let alias = if self.match_token(&[TokenType::As]).is_some() {
+            Some(self.consume_identifier("Expected alias name."))
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expected ';' after import.");
+        Statement::new(StatementKind::Import(Import { path, alias, position: pos }), pos)
+    }
+
+    fn export_block(&mut self) -> Statement {
+        let pos = self.peek().position;
+        self.advance(); // export
+        self.consume(TokenType::LBrace, "Expected '{' after 'export'.");
+        let mut names = Vec::new();
+        if !self.check(TokenType::RBrace) {
+            loop {
+                names.push(self.consume_identifier("Expected exported name."));
+                if self.match_token(&[TokenType::Comma]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after export block.");
+        Statement::new(StatementKind::Export(Export { names, position: pos }), pos)
+    }
+
+    // ---- statements ---------------------------------------------------
+
+    fn statement(&mut self) -> Statement {
+        let pos = self.peek().position;
+        if self.match_token(&[TokenType::If]).is_some() {
+            return self.if_statement(pos);
+        }
+        if self.match_token(&[TokenType::While]).is_some() {
+            return self.while_statement(pos);
+        }
+        if self.match_token(&[TokenType::For]).is_some() {
+            return self.for_statement(pos);
+        }
+        if self.match_token(&[TokenType::Return]).is_some() {
+            return self.return_statement(pos);
+        }
+        if self.match_token(&[TokenType::Match]).is_some() {
+            return self.match_statement(pos);
+        }
+        if self.match_token(&[TokenType::Break]).is_some() {
+            self.consume(TokenType::Semicolon, "Expected ';' after 'break'.");
+            return Statement::new(StatementKind::Break, pos);
+        }
+        if self.match_token(&[TokenType::Continue]).is_some() {
+            self.consume(TokenType::Semicolon, "Expected ';' after 'continue'.");
+            return Statement::new(StatementKind::Continue, pos);
+        }
+        if self.check(TokenType::Var) || self.check(TokenType::Let) || self.check(TokenType::Const) {
+            return self.let_statement();
+        }
+        if self.check(TokenType::LBrace) {
+            return Statement::new(StatementKind::Block(self.block()), pos);
+        }
+        self.expression_statement()
+    }
+
+    fn let_statement(&mut self) -> Statement {
+        let pos = self.peek().position;
+        let is_const = self.check(TokenType::Const);
+        self.advance(); // var/let/const
+        let name = self.consume_identifier("Expected variable name.");
+        let ty = if self.match_token(&[TokenType::Colon]).is_some() {
+            Some(self.type_())
+        } else {
+            None
+        };
+        let value = if self.match_token(&[TokenType::Eq]).is_some() {
+            Some(self.expression())
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expected ';' after variable declaration.");
+        Statement::new(
+            StatementKind::Let {
+                name,
+                ty,
+                value,
+                is_const,
+            },
+            pos,
+        )
+    }
+
+    fn if_statement(&mut self, pos: Position) -> Statement {
+        self.consume(TokenType::LParen, "Expected '(' after 'if'.");
+        let condition = self.expression();
+        self.consume(TokenType::RParen, "Expected ')' after condition.");
+        let then_branch = self.block();
+        let else_branch = if self.match_token(&[TokenType::Else]).is_some() {
+            if self.check(TokenType::If) {
+                self.advance();
+                Some(vec![self.if_statement(self.peek().position)])
+            } else {
+                Some(self.block())
+            }
+        } else {
+            None
+        };
+        Statement::new(
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            },
+            pos,
+        )
+    }
+
+    fn while_statement(&mut self, pos: Position) -> Statement {
+        self.consume(TokenType::LParen, "Expected '(' after 'while'.");
+        let condition = self.expression();
+        self.consume(TokenType::RParen, "Expected ')' after condition.");
+        let body = self.block();
+        Statement::new(StatementKind::While { condition, body }, pos)
+    }
+
+    fn for_statement(&mut self, pos: Position) -> Statement {
+        self.consume(TokenType::LParen, "Expected '(' after 'for'.");
+        if self.check(TokenType::Identifier) && self.check_next(TokenType::In) {
+            let variable = self.consume_identifier("Expected loop variable name.");
+            self.advance(); // in
+            let iterable = self.expression();
+            self.consume(TokenType::RParen, "Expected ')' after 'for ... in' clause.");
+            let body = self.block();
+            return Statement::new(
+                StatementKind::ForEach {
+                    variable,
+                    iterable,
+                    body,
+                },
+                pos,
+            );
+        }
+        let init = if self.check(TokenType::Semicolon) {
+            self.advance();
+            None
+        } else {
+            let stmt = self.let_statement();
+            Some(Box::new(stmt))
+        };
+        let condition = if self.check(TokenType::Semicolon) {
+            None
+        } else {
+            Some(self.expression())
+        };
+        self.consume(TokenType::Semicolon, "Expected ';' after loop condition.");
+        let update = if self.check(TokenType::RParen) {
+            None
+        } else {
+            Some(self.expression())
+        };
+        self.consume(TokenType::RParen, "Expected ')' after for clauses.");
+        let body = self.block();
+        Statement::new(
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            },
+            pos,
+        )
+    }
+
+    fn match_statement(&mut self, pos: Position) -> Statement {
+        self.consume(TokenType::LParen, "Expected '(' after 'match'.");
+        let subject = self.expression();
+        self.consume(TokenType::RParen, "Expected ')' after match subject.");
+        let arms = self.match_arms();
+        Statement::new(StatementKind::Match { subject, arms }, pos)
+    }
+
+    /// Parses `match (subject) { ... }` in expression position, e.g.
+    /// `let x = match (e) { ... };`.
+    fn match_expression(&mut self) -> Expression {
+        let pos = self.peek().position;
+        self.advance(); // match
+        self.consume(TokenType::LParen, "Expected '(' after 'match'.");
+        let subject = self.expression();
+        self.consume(TokenType::RParen, "Expected ')' after match subject.");
+        let arms = self.match_arms();
+        Expression::new(
+            ExpressionKind::Match {
+                subject: Box::new(subject),
+                arms,
+            },
+            pos,
+        )
+    }
+
+    /// Parses the `{ pattern => { ... }, ... }` body shared by match
+    /// statements and match expressions.
+    fn match_arms(&mut self) -> Vec<MatchArm> {
+        self.consume(TokenType::LBrace, "Expected '{' before match arms.");
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            let arm_pos = self.peek().position;
+            let pattern = self.pattern();
+            self.consume(TokenType::FatArrow, "Expected '=>' after match pattern.");
+            let body = self.block();
+            arms.push(MatchArm {
+                pattern,
+                body,
+                position: arm_pos,
+            });
+            self.match_token(&[TokenType::Comma]);
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after match arms.");
+        arms
+    }
+
+    fn pattern(&mut self) -> Pattern {
+        if self.check(TokenType::Identifier) && self.peek().lexeme == "_" {
+            self.advance();
+            return Pattern::Wildcard;
+        }
+        if let Some(tok) = self.match_token(&[TokenType::Int]) {
+            let (digits, suffix) = split_int_suffix(&tok.lexeme);
+            return Pattern::Literal(LiteralValue::Int(parse_int_literal(digits), suffix));
+        }
+        if let Some(tok) = self.match_token(&[TokenType::String]) {
+            return Pattern::Literal(LiteralValue::String(tok.lexeme));
+        }
+        if self.match_token(&[TokenType::True]).is_some() {
+            return Pattern::Literal(LiteralValue::Bool(true));
+        }
+        if self.match_token(&[TokenType::False]).is_some() {
+            return Pattern::Literal(LiteralValue::Bool(false));
+        }
+        let name = self.consume_identifier("Expected a pattern.");
+        if self.match_token(&[TokenType::Dot]).is_some() {
+            let variant = self.consume_identifier("Expected variant name after '.'.");
+            return Pattern::EnumVariant {
+                enum_name: name,
+                variant,
+            };
+        }
+        Pattern::Identifier(name)
+    }
+
+    fn return_statement(&mut self, pos: Position) -> Statement {
+        let value = if self.check(TokenType::Semicolon) {
+            None
+        } else {
+            Some(self.expression())
+        };
+        self.consume(TokenType::Semicolon, "Expected ';' after return value.");
+        Statement::new(StatementKind::Return(value), pos)
+    }
+
+    fn expression_statement(&mut self) -> Statement {
+        let pos = self.peek().position;
+        let expr = self.expression();
+        self.consume(TokenType::Semicolon, "Expected ';' after expression.");
+        Statement::new(StatementKind::Expression(expr), pos)
+    }
+
+    fn block(&mut self) -> Vec<Statement> {
+        self.consume(TokenType::LBrace, "Expected '{'.");
+        let mut statements = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            statements.push(self.declaration());
+        }
+        self.consume(TokenType::RBrace, "Expected '}'.");
+        statements
+    }
+
+    // ---- types ----------------------------------------------------
+
+    fn type_(&mut self) -> Type {
+        let pos = self.peek().position;
+        if self.match_token(&[TokenType::LParen]).is_some() {
+            let mut elements = Vec::new();
+            if !self.check(TokenType::RParen) {
+                loop {
+                    elements.push(self.type_());
+                    if self.match_token(&[TokenType::Comma]).is_none() {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RParen, "Expected ')' after tuple type.");
+            return Type::new(TypeKind::Tuple(elements), pos);
+        }
+        let name = self.consume_identifier("Expected type name.");
+        let mut ty = if name == "Result" {
+            self.consume(TokenType::Lt, "Expected '<' after 'Result'.");
+            let ok_ty = self.type_();
+            self.consume(TokenType::Comma, "Expected ',' between 'Result' type arguments.");
+            let err_ty = self.type_();
+            self.consume(TokenType::Gt, "Expected '>' after 'Result' type arguments.");
+            Type::new(TypeKind::Result(Box::new(ok_ty), Box::new(err_ty)), pos)
+        } else if name == "Map" {
+            self.consume(TokenType::Lt, "Expected '<' after 'Map'.");
+            let key_ty = self.type_();
+            self.consume(TokenType::Comma, "Expected ',' between 'Map' type arguments.");
+            let value_ty = self.type_();
+            self.consume(TokenType::Gt, "Expected '>' after 'Map' type arguments.");
+            Type::new(TypeKind::Map(Box::new(key_ty), Box::new(value_ty)), pos)
+        } else {
+            let kind = TypeKind::from_string(&name);
+            match kind {
+                TypeKind::UserType(user_name, user_kind, _) if self.check(TokenType::Lt) => {
+                    self.advance(); // <
+                    let mut args = Vec::new();
+                    if !self.check(TokenType::Gt) {
+                        loop {
+                            args.push(self.type_());
+                            if self.match_token(&[TokenType::Comma]).is_none() {
+                                break;
+                            }
+                        }
+                    }
+                    self.consume(TokenType::Gt, "Expected '>' after type arguments.");
+                    Type::new(TypeKind::UserType(user_name, user_kind, args), pos)
+                }
+                other => Type::new(other, pos),
+            }
+        };
+        while self.match_token(&[TokenType::LBracket]).is_some() {
+            // `T[4]`: a single fixed-size dimension, its length evaluated
+            // on the spot since nothing but literal arithmetic is
+            // available this early (no symbol table yet to chase a
+            // `const` reference through).
+            if !self.check(TokenType::RBracket) && !self.check(TokenType::Comma) {
+                let size_expr = self.expression();
+                self.consume(TokenType::RBracket, "Expected ']' after array size.");
+                let size = match const_eval_array_size(&size_expr) {
+                    Some(size) => Some(size),
+                    None => {
+                        // Not `Self::error` -- the size expression and its
+                        // `]` already parsed cleanly, so the token stream
+                        // is exactly where a well-formed `T[<size>]` would
+                        // leave it. Going through `Self::error` would set
+                        // `had_error` and make `Self::declaration` treat
+                        // this like a genuine syntax break, synchronizing
+                        // past (and losing) the statement that follows.
+                        self.bag.push(
+                            Diagnostic::error(
+                                "E100",
+                                "Array size must be a constant integer expression.",
+                                size_expr.position,
+                            ),
+                            self.file.clone(),
+                        );
+                        None
+                    }
+                };
+                ty = Type::new(TypeKind::Array(Box::new(ty), size), pos);
+                continue;
+            }
+            // There's no dedicated multi-dimensional array type -- `T[,]`
+            // is just sugar for `T[][]` (an array of arrays), counted by
+            // the commas between the brackets, so `a[i, j]` below can
+            // desugar to the equally sugar-free `a[i][j]`. This sugar
+            // only ever produces dynamically-sized dimensions -- write
+            // `T[4][3]` instead of `T[3,4]` for a fixed-size one.
+            let mut rank = 1;
+            while self.match_token(&[TokenType::Comma]).is_some() {
+                rank += 1;
+            }
+            self.consume(TokenType::RBracket, "Expected ']' after '[' in array type.");
+            for _ in 0..rank {
+                ty = Type::new(TypeKind::Array(Box::new(ty), None), pos);
+            }
+        }
+        ty
+    }
+
+    // ---- expressions ------------------------------------------------
+
+    fn expression(&mut self) -> Expression {
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Expression {
+        let expr = self.logic_or();
+        if let Some(op) = self.match_token(&[
+            TokenType::Eq,
+            TokenType::PlusEq,
+            TokenType::MinusEq,
+            TokenType::StarEq,
+            TokenType::SlashEq,
+            TokenType::PercentEq,
+            TokenType::AmpEq,
+            TokenType::PipeEq,
+            TokenType::CaretEq,
+            TokenType::LtLtEq,
+            TokenType::GtGtEq,
+        ]) {
+            let pos = expr.position;
+            let value = self.assignment();
+            return Expression::new(
+                ExpressionKind::Assignment {
+                    target: Box::new(expr),
+                    op: op.lexeme,
+                    value: Box::new(value),
+                },
+                pos,
+            );
+        }
+        expr
+    }
+
+    fn logic_or(&mut self) -> Expression {
+        let mut expr = self.logic_and();
+        while let Some(op) = self.match_token(&[TokenType::PipePipe]) {
+            let right = self.logic_and();
+            expr = Expression::new(
+                ExpressionKind::Binary {
+                    left: Box::new(expr.clone()),
+                    op: op.lexeme,
+                    right: Box::new(right),
+                },
+                expr.position,
+            );
+        }
+        expr
+    }
+
+    fn logic_and(&mut self) -> Expression {
+        let mut expr = self.bitwise_or();
+        while let Some(op) = self.match_token(&[TokenType::AmpAmp]) {
+            let right = self.bitwise_or();
+            expr = Expression::new(
+                ExpressionKind::Binary {
+                    left: Box::new(expr.clone()),
+                    op: op.lexeme,
+                    right: Box::new(right),
+                },
+                expr.position,
+            );
+        }
+        expr
+    }
+
+    fn bitwise_or(&mut self) -> Expression {
+        let mut expr = self.bitwise_xor();
+        while let Some(op) = self.match_token(&[TokenType::Pipe]) {
+            let right = self.bitwise_xor();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn bitwise_xor(&mut self) -> Expression {
+        let mut expr = self.bitwise_and();
+        while let Some(op) = self.match_token(&[TokenType::Caret]) {
+            let right = self.bitwise_and();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn bitwise_and(&mut self) -> Expression {
+        let mut expr = self.equality();
+        while let Some(op) = self.match_token(&[TokenType::Amp]) {
+            let right = self.equality();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn equality(&mut self) -> Expression {
+        let mut expr = self.comparison();
+        while let Some(op) = self.match_token(&[TokenType::EqEq, TokenType::BangEq]) {
+            let right = self.comparison();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn comparison(&mut self) -> Expression {
+        let mut expr = self.shift();
+        while let Some(op) = self.match_token(&[
+            TokenType::Lt,
+            TokenType::LtEq,
+            TokenType::Gt,
+            TokenType::GtEq,
+        ]) {
+            let right = self.shift();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    /// `<<`/`>>` sit between `comparison` and `term`: looser than `+`/`-`
+    /// (so `a << 1 + 1` shifts by `2`, matching C/Rust) but tighter than
+    /// `<`/`>` (so `1 << 4 > 8` compares a shift result, not `4 > 8`
+    /// first, which would make `<<`'s right-hand side a `Bool`).
+    fn shift(&mut self) -> Expression {
+        let mut expr = self.term();
+        while let Some(op) = self.match_token(&[TokenType::LtLt, TokenType::GtGt]) {
+            let right = self.term();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn term(&mut self) -> Expression {
+        let mut expr = self.factor();
+        while let Some(op) = self.match_token(&[TokenType::Plus, TokenType::Minus]) {
+            let right = self.factor();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn factor(&mut self) -> Expression {
+        let mut expr = self.unary();
+        while let Some(op) = self.match_token(&[TokenType::Star, TokenType::Slash, TokenType::Percent]) {
+            let right = self.unary();
+            expr = self.binary(expr, op, right);
+        }
+        expr
+    }
+
+    fn unary(&mut self) -> Expression {
+        if let Some(op) = self.match_token(&[
+            TokenType::Bang,
+            TokenType::Minus,
+            TokenType::PlusPlus,
+            TokenType::MinusMinus,
+        ]) {
+            let pos = op.position;
+            let operand = self.unary();
+            return Expression::new(
+                ExpressionKind::Unary {
+                    op: op.lexeme,
+                    operand: Box::new(operand),
+                },
+                pos,
+            );
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Expression {
+        let mut expr = self.call();
+        if let Some(op) = self.match_token(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let pos = expr.position;
+            expr = Expression::new(
+                ExpressionKind::Postfix {
+                    op: op.lexeme,
+                    operand: Box::new(expr),
+                },
+                pos,
+            );
+        }
+        expr
+    }
+
+    fn call(&mut self) -> Expression {
+        let mut expr = self.primary();
+        loop {
+            if self.match_token(&[TokenType::LParen]).is_some() {
+                let mut args = Vec::new();
+                if !self.check(TokenType::RParen) {
+                    loop {
+                        args.push(self.expression());
+                        if self.match_token(&[TokenType::Comma]).is_none() {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RParen, "Expected ')' after arguments.");
+                let pos = expr.position;
+                expr = Expression::new(
+                    ExpressionKind::Call {
+                        callee: Box::new(expr),
+                        args,
+                    },
+                    pos,
+                );
+            } else if self.match_token(&[TokenType::Dot]).is_some() {
+                let name = if let Some(tok) = self.match_token(&[TokenType::Int]) {
+                    tok.lexeme
+                } else {
+                    self.consume_identifier("Expected property name after '.'.")
+                };
+                let pos = expr.position;
+                expr = Expression::new(
+                    ExpressionKind::Get {
+                        object: Box::new(expr),
+                        name,
+                    },
+                    pos,
+                );
+            } else if self.match_token(&[TokenType::LBracket]).is_some() {
+                // `a[i, j]` desugars to `a[i][j]`, the same way `T[,]`
+                // desugars to `T[][]` in `Self::type_` -- each comma-
+                // separated index nests another `Index` node, so checking
+                // it is exactly checking a chain of single-index
+                // expressions the semantic layer already knows how to
+                // type (dimension mismatches surface as indexing into a
+                // non-`Array`, too few as a leftover `Array` type).
+                loop {
+                    let index = self.expression();
+                    let pos = expr.position;
+                    expr = Expression::new(
+                        ExpressionKind::Index {
+                            object: Box::new(expr),
+                            index: Box::new(index),
+                        },
+                        pos,
+                    );
+                    if self.match_token(&[TokenType::Comma]).is_none() {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RBracket, "Expected ']' after index.");
+            } else if self.match_token(&[TokenType::Question]).is_some() {
+                let pos = expr.position;
+                expr = Expression::new(ExpressionKind::Try(Box::new(expr)), pos);
+            } else {
+                break;
+            }
+        }
+        expr
+    }
+
+    fn primary(&mut self) -> Expression {
+        let pos = self.peek().position;
+        if self.match_token(&[TokenType::True]).is_some() {
+            return Expression::new(ExpressionKind::Literal(LiteralValue::Bool(true)), pos);
+        }
+        if self.match_token(&[TokenType::False]).is_some() {
+            return Expression::new(ExpressionKind::Literal(LiteralValue::Bool(false)), pos);
+        }
+        if let Some(tok) = self.match_token(&[TokenType::Int]) {
+            let (digits, suffix) = split_int_suffix(&tok.lexeme);
+            let value = parse_int_literal(digits);
+            return Expression::new(ExpressionKind::Literal(LiteralValue::Int(value, suffix)), pos);
+        }
+        if let Some(tok) = self.match_token(&[TokenType::Float]) {
+            let (digits, suffix) = split_float_suffix(&tok.lexeme);
+            let value = digits.parse::<f64>().unwrap_or(0.0);
+            return Expression::new(ExpressionKind::Literal(LiteralValue::Float(value, suffix)), pos);
+        }
+        if let Some(tok) = self.match_token(&[TokenType::String]) {
+            return Expression::new(ExpressionKind::Literal(LiteralValue::String(tok.lexeme)), pos);
+        }
+        if let Some(tok) = self.match_token(&[TokenType::Char]) {
+            let value = tok.lexeme.chars().next().unwrap_or('\0');
+            return Expression::new(ExpressionKind::Literal(LiteralValue::Char(value)), pos);
+        }
+        if self.check(TokenType::Identifier) && self.check_next(TokenType::LBrace) {
+            return self.struct_init();
+        }
+        if self.check(TokenType::Func) {
+            return self.lambda();
+        }
+        if self.check(TokenType::Match) {
+            return self.match_expression();
+        }
+        if let Some(tok) = self.match_token(&[TokenType::Identifier]) {
+            return Expression::new(ExpressionKind::Identifier(tok.lexeme), pos);
+        }
+        if self.match_token(&[TokenType::LParen]).is_some() {
+            let first = self.expression();
+            if self.match_token(&[TokenType::Comma]).is_some() {
+                let mut items = vec![first];
+                if !self.check(TokenType::RParen) {
+                    loop {
+                        items.push(self.expression());
+                        if self.match_token(&[TokenType::Comma]).is_none() {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RParen, "Expected ')' after tuple expression.");
+                return Expression::new(ExpressionKind::Tuple(items), pos);
+            }
+            self.consume(TokenType::RParen, "Expected ')' after expression.");
+            return Expression::new(ExpressionKind::Grouping(Box::new(first)), pos);
+        }
+        if self.match_token(&[TokenType::LBracket]).is_some() {
+            let mut items = Vec::new();
+            if !self.check(TokenType::RBracket) {
+                loop {
+                    items.push(self.expression());
+                    if self.match_token(&[TokenType::Comma]).is_none() {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RBracket, "Expected ']' after array literal.");
+            return Expression::new(ExpressionKind::ArrayLiteral(items), pos);
+        }
+        if self.check(TokenType::LBrace) {
+            return self.map_literal();
+        }
+
+        self.error("Expected expression.", pos);
+        self.advance();
+        Expression::new(ExpressionKind::Error, pos)
+    }
+
+    fn lambda(&mut self) -> Expression {
+        let pos = self.peek().position;
+        self.advance(); // func
+        self.consume(TokenType::LParen, "Expected '(' after 'func' in lambda.");
+        let mut params = Vec::new();
+        if !self.check(TokenType::RParen) {
+            loop {
+                let pname = self.consume_identifier("Expected parameter name.");
+                self.consume(TokenType::Colon, "Expected ':' after parameter name.");
+                let ty = self.type_();
+                params.push(Param { name: pname, ty });
+                if self.match_token(&[TokenType::Comma]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RParen, "Expected ')' after lambda parameters.");
+        let return_type = if self.match_token(&[TokenType::Colon]).is_some() {
+            self.type_()
+        } else {
+            Type::new(TypeKind::Void, pos)
+        };
+        let body = self.block();
+        Expression::new(
+            ExpressionKind::Lambda {
+                params,
+                return_type,
+                body,
+            },
+            pos,
+        )
+    }
+
+    fn map_literal(&mut self) -> Expression {
+        let pos = self.peek().position;
+        self.advance(); // {
+        let mut entries = Vec::new();
+        if !self.check(TokenType::RBrace) {
+            loop {
+                let key = self.expression();
+                self.consume(TokenType::Colon, "Expected ':' after map key.");
+                let value = self.expression();
+                entries.push((key, value));
+                if self.match_token(&[TokenType::Comma]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after map literal.");
+        Expression::new(ExpressionKind::MapLiteral(entries), pos)
+    }
+
+    fn struct_init(&mut self) -> Expression {
+        let pos = self.peek().position;
+        let name = self.consume_identifier("Expected struct name.");
+        self.consume(TokenType::LBrace, "Expected '{' after struct name.");
+        let mut fields = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            let fname = self.consume_identifier("Expected field name.");
+            self.consume(TokenType::Colon, "Expected ':' after field name.");
+            let value = self.expression();
+            fields.push((fname, value));
+            self.match_token(&[TokenType::Comma]);
+        }
+        self.consume(TokenType::RBrace, "Expected '}' after struct initializer.");
+        Expression::new(ExpressionKind::StructInit { name, fields }, pos)
+    }
+
+    fn binary(&mut self, left: Expression, op: Token, right: Expression) -> Expression {
+        let pos = left.position;
+        Expression::new(
+            ExpressionKind::Binary {
+                left: Box::new(left),
+                op: op.lexeme,
+                right: Box::new(right),
+            },
+            pos,
+        )
+    }
+
+    // ---- token helpers ------------------------------------------------
+
+    fn match_token(&mut self, kinds: &[TokenType]) -> Option<Token> {
+        if kinds.iter().any(|k| self.check(*k)) {
+            return Some(self.advance());
+        }
+        None
+    }
+
+    fn check(&self, kind: TokenType) -> bool {
+        self.peek().kind == kind
+    }
+
+    fn check_next(&self, kind: TokenType) -> bool {
+        self.tokens
+            .get(self.current + 1)
+            .map(|t| t.kind == kind)
+            .unwrap_or(false)
+    }
+
+    /// Consumes and returns the current token. Moves its `lexeme` out of
+    /// `tokens` rather than cloning it -- `kind`/`position` are cheap
+    /// `Copy` fields, so the lexeme's heap allocation was the only real
+    /// cost of the clone this replaces, and nothing ever looks a consumed
+    /// token's `lexeme` up again (the parser never backtracks).
+    fn advance(&mut self) -> Token {
+        let index = self.current;
+        if !self.is_at_end() {
+            self.current += 1;
+        }
+        let slot = &mut self.tokens[index];
+        Token {
+            kind: slot.kind,
+            lexeme: std::mem::take(&mut slot.lexeme),
+            position: slot.position,
+        }
+    }
+
+    fn consume(&mut self, kind: TokenType, message: &str) -> Token {
+        if self.check(kind) {
+            return self.advance();
+        }
+        let pos = self.peek().position;
+        self.error(message, pos);
+        // Advance even on failure so a missing token always makes forward
+        // progress -- otherwise a loop checking for this same token (e.g.
+        // a struct body's field list) never terminates.
+        self.advance()
+    }
+
+    fn consume_identifier(&mut self, message: &str) -> String {
+        self.consume(TokenType::Identifier, message).lexeme
+    }
+
+    /// The current token, or the trailing `Eof` if `current` has run past
+    /// the end -- `tokens` is never empty (see [`Self::new`]), so there's
+    /// always a last token to fall back to.
+    fn peek(&self) -> &Token {
+        self.tokens.get(self.current).unwrap_or_else(|| &self.tokens[self.tokens.len() - 1])
+    }
+
+    /// The token just consumed by the last [`Self::advance`] call. Only
+    /// its `kind`/`position` are meaningful here -- `lexeme` was moved out
+    /// of this slot when it was consumed.
+    fn previous(&self) -> &Token {
+        &self.tokens[self.current.saturating_sub(1)]
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.peek().kind == TokenType::Eof
+    }
+
+    fn error(&mut self, message: &str, position: Position) {
+        self.had_error = true;
+        self.bag.push(Diagnostic::error("E100", message, position), self.file.clone());
+    }
+}
+
+/// Evaluates a fixed array-size expression (`T[<this>]`) down to a
+/// non-negative length, reusing the same literal arithmetic
+/// [`crate::semantic::constant_fold`] folds `const` initializers with --
+/// this can't chase a named `const` the way that later pass can (there's
+/// no symbol table yet at parse time), so a reference to one, a call, or
+/// anything else that isn't literal arithmetic is rejected here.
+fn const_eval_array_size(expr: &Expression) -> Option<usize> {
+    let value = const_eval_literal(expr)?;
+    match value {
+        LiteralValue::Int(n, _) if n >= 0 => usize::try_from(n).ok(),
+        _ => None,
+    }
+}
+
+fn const_eval_literal(expr: &Expression) -> Option<LiteralValue> {
+    match &expr.kind {
+        ExpressionKind::Literal(value) => Some(value.clone()),
+        ExpressionKind::Grouping(inner) => const_eval_literal(inner),
+        ExpressionKind::Unary { op, operand } => {
+            crate::semantic::constant_fold::fold_unary(op, &const_eval_literal(operand)?)
+        }
+        ExpressionKind::Binary { left, op, right } => crate::semantic::constant_fold::fold_binary(
+            op,
+            &const_eval_literal(left)?,
+            &const_eval_literal(right)?,
+        ),
+        _ => None,
+    }
+}
+
+/// Splits a trailing `i8`/`i16`/.../`u64` type suffix the lexer appended
+/// to an `Int` token's lexeme (see [`crate::lexer::Lexer::consume_suffix`])
+/// back off, returning the plain digits and the suffix's type, if any.
+fn split_int_suffix(text: &str) -> (&str, Option<TypeKind>) {
+    const SUFFIXES: &[(&str, TypeKind)] = &[
+        ("i8", TypeKind::Int8),
+        ("i16", TypeKind::Int16),
+        ("i32", TypeKind::Int32),
+        ("i64", TypeKind::Int64),
+        ("u8", TypeKind::UInt8),
+        ("u16", TypeKind::UInt16),
+        ("u32", TypeKind::UInt32),
+        ("u64", TypeKind::UInt64),
+    ];
+    for (suffix, kind) in SUFFIXES {
+        if let Some(digits) = text.strip_suffix(suffix) {
+            return (digits, Some(kind.clone()));
+        }
+    }
+    (text, None)
+}
+
+/// See [`split_int_suffix`] -- the `f32`/`f64` equivalent for a `Float`
+/// token's lexeme.
+fn split_float_suffix(text: &str) -> (&str, Option<TypeKind>) {
+    for (suffix, kind) in [("f32", TypeKind::Float32), ("f64", TypeKind::Float64)] {
+        if let Some(digits) = text.strip_suffix(suffix) {
+            return (digits, Some(kind));
+        }
+    }
+    (text, None)
+}
+
+/// Parses an `Int` token's lexeme, which the lexer has already stripped
+/// of `_` digit separators and left with its `0x`/`0b`/`0o` prefix intact
+/// (if any) so the correct radix can be picked here. Parsed as `i128`,
+/// wider than any integer type the language has, so a literal too big for
+/// its target type is still parsed exactly instead of wrapping -- letting
+/// [`crate::semantic::Typechecker::check_int_range`] report `E210` against
+/// the real value rather than an already-truncated one.
+fn parse_int_literal(text: &str) -> i128 {
+    if let Some(rest) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        i128::from_str_radix(rest, 16).unwrap_or(0)
+    } else if let Some(rest) = text.strip_prefix("0b").or_else(|| text.strip_prefix("0B")) {
+        i128::from_str_radix(rest, 2).unwrap_or(0)
+    } else if let Some(rest) = text.strip_prefix("0o").or_else(|| text.strip_prefix("0O")) {
+        i128::from_str_radix(rest, 8).unwrap_or(0)
+    } else {
+        text.parse().unwrap_or(0)
+    }
+}
@@ -0,0 +1,239 @@
+//! Compile-time evaluation of global `const` initializers and enum
+//! variant values.
+//!
+//! [`crate::semantic::constant_fold`] already collapses arithmetic over
+//! literals and runs before this pass, but it leaves a reference to
+//! another `const` alone -- it has no notion of "this identifier names a
+//! constant" or a way to notice `const A = B; const B = A;` looping
+//! forever if it tried to chase one down. This pass builds the table of
+//! every global `const`'s initializer up front, then evaluates each one
+//! (and each enum variant's value) down to a [`LiteralValue`], tracking
+//! which name is mid-evaluation so a cycle through any number of
+//! `const`s is caught rather than overflowing the stack. Anything that
+//! isn't a literal, a reference to another `const`, or an operator this
+//! pass's borrowed [`constant_fold::fold_unary`]/[`constant_fold::fold_binary`]
+//! know how to fold is rejected -- a call, a field access, a runtime
+//! variable -- since none of those have a value yet at this point in
+//! compilation.
+//!
+//! This is also where an enum's discriminants get their final shape: a
+//! variant with no `= value` is auto-numbered one past the previous
+//! variant's (starting at `0`), every discriminant is checked against
+//! the enum's `underlying_type` (`Int32` when it names none) the same
+//! way [`crate::semantic::Typechecker::check_int_range`] checks a
+//! declared numeric binding, and a value repeated across variants is
+//! reported as `E209`.
+
+use crate::ast::{Enum, EnumVariant, Expression, ExpressionKind, LiteralValue, Module, StatementKind, Type, TypeKind};
+use crate::common::Position;
+use crate::errors::{Diagnostic, DiagnosticBag};
+use crate::semantic::constant_fold::{fold_binary, fold_unary};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+enum Slot {
+    Evaluating,
+    Value(LiteralValue),
+}
+
+/// Evaluates every global `const`'s initializer and enum variant value in
+/// `module` down to a literal in place, reporting `E207` for anything
+/// that isn't compile-time evaluable and `E208` for a `const` defined in
+/// terms of itself.
+pub fn analyze(module: &mut Module, file: &str, bag: &mut DiagnosticBag) {
+    let mut consts = HashMap::new();
+    for statement in &module.statements {
+        if let StatementKind::Let {
+            name,
+            is_const: true,
+            value: Some(value),
+            ..
+        } = &statement.kind
+        {
+            consts.insert(name.clone(), value.clone());
+        }
+    }
+
+    let mut resolved = HashMap::new();
+    for statement in &mut module.statements {
+        match &mut statement.kind {
+            StatementKind::Let {
+                is_const: true,
+                value: Some(value),
+                ..
+            } => fold_in_place(value, &consts, &mut resolved, file, bag),
+            StatementKind::EnumDecl(enm) => {
+                let enm = Arc::make_mut(enm);
+                number_variants(enm, &consts, &mut resolved, file, bag);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn fold_in_place(
+    expr: &mut Expression,
+    consts: &HashMap<String, Expression>,
+    resolved: &mut HashMap<String, Slot>,
+    file: &str,
+    bag: &mut DiagnosticBag,
+) {
+    if let Some(value) = eval(expr, consts, resolved, file, bag) {
+        expr.kind = ExpressionKind::Literal(value);
+    }
+}
+
+/// Folds every variant's explicit `= value`, fills in an omitted one as
+/// one past the previous variant's (`0` for the first), then checks the
+/// resulting discriminant against `enm.underlying_type` (`E210` if it
+/// doesn't fit) and against every earlier variant's (`E209` on a repeat).
+fn number_variants(
+    enm: &mut Enum,
+    consts: &HashMap<String, Expression>,
+    resolved: &mut HashMap<String, Slot>,
+    file: &str,
+    bag: &mut DiagnosticBag,
+) {
+    let underlying_type = enm.underlying_type.clone().unwrap_or(Type::new(TypeKind::Int32, enm.position));
+    let mut next_value: i128 = 0;
+    let mut seen: HashMap<i128, String> = HashMap::new();
+    for variant in &mut enm.variants {
+        match &mut variant.value {
+            Some(value) => {
+                fold_in_place(value, consts, resolved, file, bag);
+                if let ExpressionKind::Literal(LiteralValue::Int(n, _)) = &value.kind {
+                    next_value = *n;
+                }
+            }
+            None => {
+                variant.value = Some(Expression::new(
+                    ExpressionKind::Literal(LiteralValue::Int(next_value, None)),
+                    enm.position,
+                ));
+            }
+        }
+        check_discriminant_range(&underlying_type, variant, file, bag);
+        check_duplicate_discriminant(&mut seen, variant, file, bag);
+        next_value = next_value.wrapping_add(1);
+    }
+}
+
+fn check_discriminant_range(underlying_type: &Type, variant: &EnumVariant, file: &str, bag: &mut DiagnosticBag) {
+    let bounds = match underlying_type.kind {
+        TypeKind::Int8 => i8::MIN as i128..=i8::MAX as i128,
+        TypeKind::Int16 => i16::MIN as i128..=i16::MAX as i128,
+        TypeKind::Int32 => i32::MIN as i128..=i32::MAX as i128,
+        TypeKind::Int64 => i64::MIN as i128..=i64::MAX as i128,
+        TypeKind::UInt8 => u8::MIN as i128..=u8::MAX as i128,
+        TypeKind::UInt16 => u16::MIN as i128..=u16::MAX as i128,
+        TypeKind::UInt32 => u32::MIN as i128..=u32::MAX as i128,
+        TypeKind::UInt64 => u64::MIN as i128..=u64::MAX as i128,
+        _ => return,
+    };
+    let Some(ExpressionKind::Literal(LiteralValue::Int(n, _))) = variant.value.as_ref().map(|v| &v.kind) else {
+        return;
+    };
+    if !bounds.contains(n) {
+        bag.push(
+            Diagnostic::error(
+                "E210",
+                format!("Discriminant '{}' does not fit in '{:?}'", n, underlying_type.kind),
+                variant.value.as_ref().unwrap().position,
+            ),
+            file,
+        );
+    }
+}
+
+fn check_duplicate_discriminant(seen: &mut HashMap<i128, String>, variant: &EnumVariant, file: &str, bag: &mut DiagnosticBag) {
+    let Some(value) = &variant.value else { return };
+    let ExpressionKind::Literal(LiteralValue::Int(n, _)) = &value.kind else {
+        return;
+    };
+    if let Some(earlier) = seen.insert(*n, variant.name.clone()) {
+        bag.push(
+            Diagnostic::error(
+                "E209",
+                format!("Variant '{}' has the same discriminant ({}) as '{}'", variant.name, n, earlier),
+                value.position,
+            ),
+            file,
+        );
+    }
+}
+
+fn eval(
+    expr: &Expression,
+    consts: &HashMap<String, Expression>,
+    resolved: &mut HashMap<String, Slot>,
+    file: &str,
+    bag: &mut DiagnosticBag,
+) -> Option<LiteralValue> {
+    match &expr.kind {
+        ExpressionKind::Literal(value) => Some(value.clone()),
+        ExpressionKind::Grouping(inner) => eval(inner, consts, resolved, file, bag),
+        ExpressionKind::Unary { op, operand } => {
+            let operand = eval(operand, consts, resolved, file, bag)?;
+            fold_unary(op, &operand).or_else(|| {
+                report_non_const(expr.position, file, bag);
+                None
+            })
+        }
+        ExpressionKind::Binary { left, op, right } => {
+            let left = eval(left, consts, resolved, file, bag)?;
+            let right = eval(right, consts, resolved, file, bag)?;
+            fold_binary(op, &left, &right).or_else(|| {
+                report_non_const(expr.position, file, bag);
+                None
+            })
+        }
+        ExpressionKind::Identifier(name) => resolve_const(name, expr.position, consts, resolved, file, bag),
+        _ => {
+            report_non_const(expr.position, file, bag);
+            None
+        }
+    }
+}
+
+fn resolve_const(
+    name: &str,
+    position: Position,
+    consts: &HashMap<String, Expression>,
+    resolved: &mut HashMap<String, Slot>,
+    file: &str,
+    bag: &mut DiagnosticBag,
+) -> Option<LiteralValue> {
+    match resolved.get(name) {
+        Some(Slot::Value(value)) => return Some(value.clone()),
+        Some(Slot::Evaluating) => {
+            bag.push(
+                Diagnostic::error("E208", format!("`{}` is defined in terms of itself", name), position),
+                file,
+            );
+            return None;
+        }
+        None => {}
+    }
+    let Some(initializer) = consts.get(name) else {
+        report_non_const(position, file, bag);
+        return None;
+    };
+    resolved.insert(name.to_string(), Slot::Evaluating);
+    let value = eval(initializer, consts, resolved, file, bag);
+    match &value {
+        Some(value) => {
+            resolved.insert(name.to_string(), Slot::Value(value.clone()));
+        }
+        None => {
+            resolved.remove(name);
+        }
+    }
+    value
+}
+
+fn report_non_const(position: Position, file: &str, bag: &mut DiagnosticBag) {
+    bag.push(
+        Diagnostic::error("E207", "not a compile-time constant expression".to_string(), position),
+        file,
+    );
+}
@@ -0,0 +1,305 @@
+//! Constant folding over the parsed AST.
+//!
+//! Runs right after parsing, before resolution: evaluating `1 + 2 * 3`,
+//! `!true`, or `"a" + "b"` down to a literal doesn't need names or types
+//! resolved first, and doing it this early means the resolver, the
+//! typechecker and every backend all see the folded literal instead of
+//! separately reducing the same constant expression. This is also the
+//! only place global `const` initializers and enum variant values get
+//! evaluated — nothing else in the pipeline looks at them again once
+//! they're stored.
+//!
+//! Only same-kind (or int/float-mixed numeric) literal operands fold;
+//! anything touching a variable, a call, or an operator this pass
+//! doesn't know is left alone for the backends to evaluate at runtime.
+//! Division and modulo by a literal zero are deliberately left unfolded
+//! so the existing runtime division-by-zero diagnostics still fire.
+
+use crate::ast::{Expression, ExpressionKind, LiteralValue, Module, Statement, StatementKind, TypeKind};
+use std::sync::Arc;
+
+pub fn fold(module: &mut Module) {
+    for statement in &mut module.statements {
+        fold_statement(statement);
+    }
+}
+
+fn fold_statement(statement: &mut Statement) {
+    match &mut statement.kind {
+        StatementKind::Expression(expr) => fold_expression(expr),
+        StatementKind::Let { value, .. } => {
+            if let Some(value) = value {
+                fold_expression(value);
+            }
+        }
+        StatementKind::Return(value) => {
+            if let Some(value) = value {
+                fold_expression(value);
+            }
+        }
+        StatementKind::If { condition, then_branch, else_branch } => {
+            fold_expression(condition);
+            for stmt in then_branch {
+                fold_statement(stmt);
+            }
+            if let Some(else_branch) = else_branch {
+                for stmt in else_branch {
+                    fold_statement(stmt);
+                }
+            }
+        }
+        StatementKind::While { condition, body } => {
+            fold_expression(condition);
+            for stmt in body {
+                fold_statement(stmt);
+            }
+        }
+        StatementKind::For { init, condition, update, body } => {
+            if let Some(init) = init {
+                fold_statement(init);
+            }
+            if let Some(condition) = condition {
+                fold_expression(condition);
+            }
+            if let Some(update) = update {
+                fold_expression(update);
+            }
+            for stmt in body {
+                fold_statement(stmt);
+            }
+        }
+        StatementKind::ForEach { iterable, body, .. } => {
+            fold_expression(iterable);
+            for stmt in body {
+                fold_statement(stmt);
+            }
+        }
+        StatementKind::Block(statements) => {
+            for stmt in statements {
+                fold_statement(stmt);
+            }
+        }
+        StatementKind::FunctionDecl(function) => {
+            let function = Arc::make_mut(function);
+            for stmt in &mut function.body {
+                fold_statement(stmt);
+            }
+        }
+        StatementKind::ImplBlock(imp) => {
+            let imp = Arc::make_mut(imp);
+            for method in &mut imp.methods {
+                let method = Arc::make_mut(method);
+                for stmt in &mut method.body {
+                    fold_statement(stmt);
+                }
+            }
+        }
+        StatementKind::EnumDecl(enm) => {
+            let enm = Arc::make_mut(enm);
+            for variant in &mut enm.variants {
+                if let Some(value) = &mut variant.value {
+                    fold_expression(value);
+                }
+            }
+        }
+        StatementKind::Match { subject, arms } => {
+            fold_expression(subject);
+            for arm in arms {
+                for stmt in &mut arm.body {
+                    fold_statement(stmt);
+                }
+            }
+        }
+        StatementKind::ModuleDecl(block) => {
+            let block = Arc::make_mut(block);
+            for stmt in &mut block.statements {
+                fold_statement(stmt);
+            }
+        }
+        StatementKind::StructDecl(_)
+        | StatementKind::InterfaceDecl(_)
+        | StatementKind::Import(_)
+        | StatementKind::Export(_)
+        | StatementKind::Break
+        | StatementKind::Continue
+        | StatementKind::Error => {}
+    }
+}
+
+fn fold_expression(expr: &mut Expression) {
+    match &mut expr.kind {
+        ExpressionKind::Grouping(inner) => {
+            fold_expression(inner);
+            if let ExpressionKind::Literal(value) = &inner.kind {
+                expr.kind = ExpressionKind::Literal(value.clone());
+            }
+        }
+        ExpressionKind::Unary { op, operand } => {
+            fold_expression(operand);
+            if let ExpressionKind::Literal(value) = &operand.kind {
+                if let Some(folded) = fold_unary(op, value) {
+                    expr.kind = ExpressionKind::Literal(folded);
+                }
+            }
+        }
+        ExpressionKind::Binary { left, op, right } => {
+            fold_expression(left);
+            fold_expression(right);
+            if let (ExpressionKind::Literal(l), ExpressionKind::Literal(r)) = (&left.kind, &right.kind) {
+                if let Some(folded) = fold_binary(op, l, r) {
+                    expr.kind = ExpressionKind::Literal(folded);
+                }
+            }
+        }
+        ExpressionKind::Postfix { operand, .. } | ExpressionKind::Try(operand) => fold_expression(operand),
+        ExpressionKind::Call { callee, args } => {
+            fold_expression(callee);
+            for arg in args {
+                fold_expression(arg);
+            }
+        }
+        ExpressionKind::Get { object, .. } => fold_expression(object),
+        ExpressionKind::Index { object, index } => {
+            fold_expression(object);
+            fold_expression(index);
+        }
+        ExpressionKind::Assignment { target, value, .. } => {
+            fold_expression(target);
+            fold_expression(value);
+        }
+        ExpressionKind::ArrayLiteral(items) | ExpressionKind::Tuple(items) => {
+            for item in items {
+                fold_expression(item);
+            }
+        }
+        ExpressionKind::MapLiteral(pairs) => {
+            for (key, value) in pairs {
+                fold_expression(key);
+                fold_expression(value);
+            }
+        }
+        ExpressionKind::StructInit { fields, .. } => {
+            for (_, value) in fields {
+                fold_expression(value);
+            }
+        }
+        ExpressionKind::Lambda { body, .. } => {
+            for stmt in body {
+                fold_statement(stmt);
+            }
+        }
+        ExpressionKind::Match { subject, arms } => {
+            fold_expression(subject);
+            for arm in arms {
+                for stmt in &mut arm.body {
+                    fold_statement(stmt);
+                }
+            }
+        }
+        ExpressionKind::Literal(_) | ExpressionKind::Identifier(_) | ExpressionKind::Error => {}
+    }
+}
+
+/// Evaluates a unary operator over an already-literal operand. `pub(crate)`
+/// so [`crate::semantic::const_eval`] can reuse the same arithmetic this
+/// pass uses, rather than duplicating it, once it's resolved a `const`
+/// reference down to a literal itself.
+pub(crate) fn fold_unary(op: &str, value: &LiteralValue) -> Option<LiteralValue> {
+    match (op, value) {
+        ("-", LiteralValue::Int(n, suffix)) => Some(LiteralValue::Int(-n, suffix.clone())),
+        ("-", LiteralValue::Float(n, suffix)) => Some(LiteralValue::Float(-n, suffix.clone())),
+        ("!", LiteralValue::Bool(b)) => Some(LiteralValue::Bool(!b)),
+        _ => None,
+    }
+}
+
+/// Evaluates a binary operator over two already-literal operands. See
+/// [`fold_unary`] for why this is `pub(crate)`.
+pub(crate) fn fold_binary(op: &str, left: &LiteralValue, right: &LiteralValue) -> Option<LiteralValue> {
+    use LiteralValue::*;
+
+    match (left, right) {
+        (Int(a, a_suffix), Int(b, b_suffix)) => {
+            fold_int(op, *a, *b, a_suffix.clone().or_else(|| b_suffix.clone()))
+        }
+        (Float(..), _) | (_, Float(..)) => {
+            let suffix = literal_suffix(left).or_else(|| literal_suffix(right));
+            let a = as_float(left)?;
+            let b = as_float(right)?;
+            fold_float(op, a, b, suffix)
+        }
+        (Bool(a), Bool(b)) => fold_bool(op, *a, *b),
+        (String(a), String(b)) if op == "+" => Some(String(format!("{}{}", a, b))),
+        (String(a), String(b)) => fold_eq(op, a == b),
+        (Char(a), Char(b)) => fold_eq(op, a == b),
+        _ => None,
+    }
+}
+
+fn as_float(value: &LiteralValue) -> Option<f64> {
+    match value {
+        LiteralValue::Int(n, _) => Some(*n as f64),
+        LiteralValue::Float(n, _) => Some(*n),
+        _ => None,
+    }
+}
+
+/// The suffix carried by an `Int`/`Float` literal, if any -- used to keep
+/// a folded result's explicit type when either operand named one.
+fn literal_suffix(value: &LiteralValue) -> Option<TypeKind> {
+    match value {
+        LiteralValue::Int(_, suffix) | LiteralValue::Float(_, suffix) => suffix.clone(),
+        _ => None,
+    }
+}
+
+fn fold_eq(op: &str, equal: bool) -> Option<LiteralValue> {
+    match op {
+        "==" => Some(LiteralValue::Bool(equal)),
+        "!=" => Some(LiteralValue::Bool(!equal)),
+        _ => None,
+    }
+}
+
+fn fold_int(op: &str, a: i128, b: i128, suffix: Option<TypeKind>) -> Option<LiteralValue> {
+    match op {
+        "+" => Some(LiteralValue::Int(a + b, suffix)),
+        "-" => Some(LiteralValue::Int(a - b, suffix)),
+        "*" => Some(LiteralValue::Int(a * b, suffix)),
+        "/" if b != 0 => Some(LiteralValue::Int(a / b, suffix)),
+        "%" if b != 0 => Some(LiteralValue::Int(a % b, suffix)),
+        "==" => Some(LiteralValue::Bool(a == b)),
+        "!=" => Some(LiteralValue::Bool(a != b)),
+        "<" => Some(LiteralValue::Bool(a < b)),
+        "<=" => Some(LiteralValue::Bool(a <= b)),
+        ">" => Some(LiteralValue::Bool(a > b)),
+        ">=" => Some(LiteralValue::Bool(a >= b)),
+        _ => None,
+    }
+}
+
+fn fold_float(op: &str, a: f64, b: f64, suffix: Option<TypeKind>) -> Option<LiteralValue> {
+    match op {
+        "+" => Some(LiteralValue::Float(a + b, suffix)),
+        "-" => Some(LiteralValue::Float(a - b, suffix)),
+        "*" => Some(LiteralValue::Float(a * b, suffix)),
+        "/" if b != 0.0 => Some(LiteralValue::Float(a / b, suffix)),
+        "==" => Some(LiteralValue::Bool(a == b)),
+        "!=" => Some(LiteralValue::Bool(a != b)),
+        "<" => Some(LiteralValue::Bool(a < b)),
+        "<=" => Some(LiteralValue::Bool(a <= b)),
+        ">" => Some(LiteralValue::Bool(a > b)),
+        ">=" => Some(LiteralValue::Bool(a >= b)),
+        _ => None,
+    }
+}
+
+fn fold_bool(op: &str, a: bool, b: bool) -> Option<LiteralValue> {
+    match op {
+        "&&" => Some(LiteralValue::Bool(a && b)),
+        "||" => Some(LiteralValue::Bool(a || b)),
+        "==" => Some(LiteralValue::Bool(a == b)),
+        "!=" => Some(LiteralValue::Bool(a != b)),
+        _ => None,
+    }
+}
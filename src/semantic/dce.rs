@@ -0,0 +1,424 @@
+//! Dead-code reachability analysis over the parsed AST.
+//!
+//! Seeds a "live" set from `main`, every `pub` function/struct, and every
+//! name listed in an `export` statement, then grows it by following calls,
+//! struct construction and identifier reads out from each live item's
+//! body. Whatever top-level function or struct is left over is reported
+//! as an `E010` unused warning.
+//!
+//! This is a per-module analysis: it has no visibility into which of
+//! another file's imports resolve here, so a `pub` item is always treated
+//! as live even when this file's own code never touches it. Interface and
+//! `impl` bodies are also always treated as live, since a method called
+//! through dynamic dispatch (`value.method()`) can't be traced back to its
+//! declaration without full type information — reachability here only
+//! prunes what's both private *and* unused within this module.
+
+use crate::ast::{
+    Expression, ExpressionKind, Module, Pattern, Statement, StatementKind, Type, TypeKind,
+};
+use crate::common::Position;
+use crate::errors::{Diagnostic, DiagnosticBag};
+use std::collections::{HashSet, VecDeque};
+
+enum DeclKind {
+    Function,
+    Struct,
+    Global,
+}
+
+struct Decl {
+    name: String,
+    kind: DeclKind,
+    is_root: bool,
+    position: Position,
+    references: HashSet<String>,
+}
+
+/// Names of every unreachable top-level function, struct and global
+/// constant found by [`analyze`], alongside where each was declared.
+#[derive(Default)]
+pub struct DeadCode {
+    pub functions: Vec<(String, Position)>,
+    pub structs: Vec<(String, Position)>,
+    pub globals: Vec<(String, Position)>,
+}
+
+impl DeadCode {
+    fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.structs.is_empty() && self.globals.is_empty()
+    }
+}
+
+/// Finds top-level functions, structs and global constants unreachable
+/// from `main` or an exported symbol, warning about each into `bag` --
+/// the same bag `compile_source` builds, so a caller reading
+/// `CompileResult.diagnostics` (e.g. `matcha lsp`) sees these warnings
+/// too -- and returning their names so callers can strip them under
+/// `--strip-dead-code`.
+pub fn analyze(module: &Module, file: &str, bag: &mut DiagnosticBag) -> DeadCode {
+    let exported = exported_names(module);
+    let mut decls = Vec::new();
+    let mut always_live = HashSet::new();
+
+    for statement in &module.statements {
+        match &statement.kind {
+            // Static methods are called as `Type.method(...)`, a `Get`
+            // callee this analysis can't trace back to the declaration by
+            // name the way a plain `identifier(...)` call is traced
+            // below; treat their bodies as always executing, same as an
+            // impl method's.
+            StatementKind::FunctionDecl(function) if function.receiver.is_some() => {
+                for stmt in &function.body {
+                    collect_statement_refs(stmt, &mut always_live);
+                }
+            }
+            StatementKind::FunctionDecl(function) => {
+                let mut references = HashSet::new();
+                for param in &function.params {
+                    collect_type_refs(&param.ty, &mut references);
+                }
+                collect_type_refs(&function.return_type, &mut references);
+                for stmt in &function.body {
+                    collect_statement_refs(stmt, &mut references);
+                }
+                decls.push(Decl {
+                    name: function.name.clone(),
+                    kind: DeclKind::Function,
+                    is_root: function.name == "main"
+                        || function.is_pub
+                        || exported.contains(&function.name),
+                    position: function.position,
+                    references,
+                });
+            }
+            StatementKind::StructDecl(strukt) => {
+                let mut references = HashSet::new();
+                for field in &strukt.fields {
+                    collect_type_refs(&field.ty, &mut references);
+                }
+                decls.push(Decl {
+                    name: strukt.name.clone(),
+                    kind: DeclKind::Struct,
+                    is_root: strukt.is_pub || exported.contains(&strukt.name),
+                    position: strukt.position,
+                    references,
+                });
+            }
+            StatementKind::Let { name, value, is_const: true, .. } => {
+                let mut references = HashSet::new();
+                if let Some(value) = value {
+                    collect_expr_refs(value, &mut references);
+                }
+                decls.push(Decl {
+                    name: name.clone(),
+                    kind: DeclKind::Global,
+                    is_root: exported.contains(name),
+                    position: statement.position,
+                    references,
+                });
+            }
+            // Impl methods are dispatched dynamically, so there's no
+            // syntactic call site to trace them from; treat their bodies
+            // as always executing, the same way `main`'s statements do.
+            StatementKind::ImplBlock(imp) => {
+                for method in &imp.methods {
+                    for stmt in &method.body {
+                        collect_statement_refs(stmt, &mut always_live);
+                    }
+                }
+            }
+            StatementKind::EnumDecl(_) | StatementKind::InterfaceDecl(_) | StatementKind::Export(_) => {}
+            _ => collect_statement_refs(statement, &mut always_live),
+        }
+    }
+
+    let mut live: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = VecDeque::new();
+    for name in always_live {
+        if live.insert(name.clone()) {
+            queue.push_back(name);
+        }
+    }
+    for decl in &decls {
+        if decl.is_root && live.insert(decl.name.clone()) {
+            queue.push_back(decl.name.clone());
+        }
+    }
+    while let Some(name) = queue.pop_front() {
+        if let Some(decl) = decls.iter().find(|decl| decl.name == name) {
+            for reference in &decl.references {
+                if live.insert(reference.clone()) {
+                    queue.push_back(reference.clone());
+                }
+            }
+        }
+    }
+
+    let mut dead = DeadCode::default();
+    for decl in &decls {
+        if live.contains(&decl.name) {
+            continue;
+        }
+        match decl.kind {
+            DeclKind::Function => dead.functions.push((decl.name.clone(), decl.position)),
+            DeclKind::Struct => dead.structs.push((decl.name.clone(), decl.position)),
+            DeclKind::Global => dead.globals.push((decl.name.clone(), decl.position)),
+        }
+        bag.push(
+            Diagnostic::warning("E010", format!("`{}` is never used", decl.name), decl.position),
+            file,
+        );
+    }
+    dead
+}
+
+/// Removes every declaration `analyze` found unreachable, so backends
+/// never see them. Only safe to call with the exact [`DeadCode`] an
+/// `analyze` call on this same module just produced.
+pub fn strip(module: &mut Module, dead: &DeadCode) {
+    if dead.is_empty() {
+        return;
+    }
+    let dead_names: HashSet<&str> = dead
+        .functions
+        .iter()
+        .chain(&dead.structs)
+        .chain(&dead.globals)
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    module.statements.retain(|statement| match &statement.kind {
+        StatementKind::FunctionDecl(function) => !dead_names.contains(function.name.as_str()),
+        StatementKind::StructDecl(strukt) => !dead_names.contains(strukt.name.as_str()),
+        StatementKind::Let { name, is_const: true, .. } => !dead_names.contains(name.as_str()),
+        _ => true,
+    });
+}
+
+fn exported_names(module: &Module) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for statement in &module.statements {
+        if let StatementKind::Export(export) = &statement.kind {
+            names.extend(export.names.iter().cloned());
+        }
+    }
+    names
+}
+
+fn collect_type_refs(ty: &Type, out: &mut HashSet<String>) {
+    match &ty.kind {
+        TypeKind::UserType(name, _, args) => {
+            out.insert(name.clone());
+            for arg in args {
+                collect_type_refs(arg, out);
+            }
+        }
+        TypeKind::Array(inner, _) | TypeKind::Map(_, inner) => collect_type_refs(inner, out),
+        TypeKind::Function(params, ret) => {
+            for param in params {
+                collect_type_refs(param, out);
+            }
+            collect_type_refs(ret, out);
+        }
+        TypeKind::Result(ok, err) => {
+            collect_type_refs(ok, out);
+            collect_type_refs(err, out);
+        }
+        TypeKind::Tuple(items) => {
+            for item in items {
+                collect_type_refs(item, out);
+            }
+        }
+        TypeKind::Int8
+        | TypeKind::Int16
+        | TypeKind::Int32
+        | TypeKind::Int64
+        | TypeKind::UInt8
+        | TypeKind::UInt16
+        | TypeKind::UInt32
+        | TypeKind::UInt64
+        | TypeKind::Float32
+        | TypeKind::Float64
+        | TypeKind::Bool
+        | TypeKind::String
+        | TypeKind::Char
+        | TypeKind::Void
+        | TypeKind::Error => {}
+    }
+}
+
+/// Every name a statement's body touches — identifiers read, structs
+/// constructed, types named, functions/structs/enums declared. Shared
+/// with [`crate::semantic::lint`]'s unused-import check, which needs the
+/// same "does anything in this module still refer to this name" answer.
+pub(crate) fn collect_statement_refs(statement: &Statement, out: &mut HashSet<String>) {
+    match &statement.kind {
+        StatementKind::Expression(expr) => collect_expr_refs(expr, out),
+        StatementKind::Let { ty, value, .. } => {
+            if let Some(ty) = ty {
+                collect_type_refs(ty, out);
+            }
+            if let Some(value) = value {
+                collect_expr_refs(value, out);
+            }
+        }
+        StatementKind::Return(value) => {
+            if let Some(value) = value {
+                collect_expr_refs(value, out);
+            }
+        }
+        StatementKind::If { condition, then_branch, else_branch } => {
+            collect_expr_refs(condition, out);
+            for stmt in then_branch {
+                collect_statement_refs(stmt, out);
+            }
+            if let Some(else_branch) = else_branch {
+                for stmt in else_branch {
+                    collect_statement_refs(stmt, out);
+                }
+            }
+        }
+        StatementKind::While { condition, body } => {
+            collect_expr_refs(condition, out);
+            for stmt in body {
+                collect_statement_refs(stmt, out);
+            }
+        }
+        StatementKind::For { init, condition, update, body } => {
+            if let Some(init) = init {
+                collect_statement_refs(init, out);
+            }
+            if let Some(condition) = condition {
+                collect_expr_refs(condition, out);
+            }
+            if let Some(update) = update {
+                collect_expr_refs(update, out);
+            }
+            for stmt in body {
+                collect_statement_refs(stmt, out);
+            }
+        }
+        StatementKind::ForEach { iterable, body, .. } => {
+            collect_expr_refs(iterable, out);
+            for stmt in body {
+                collect_statement_refs(stmt, out);
+            }
+        }
+        StatementKind::Block(statements) => {
+            for stmt in statements {
+                collect_statement_refs(stmt, out);
+            }
+        }
+        StatementKind::Match { subject, arms } => {
+            collect_expr_refs(subject, out);
+            for arm in arms {
+                if let Pattern::EnumVariant { enum_name, .. } = &arm.pattern {
+                    out.insert(enum_name.clone());
+                }
+                for stmt in &arm.body {
+                    collect_statement_refs(stmt, out);
+                }
+            }
+        }
+        StatementKind::FunctionDecl(function) => {
+            out.insert(function.name.clone());
+        }
+        StatementKind::StructDecl(strukt) => {
+            out.insert(strukt.name.clone());
+        }
+        StatementKind::EnumDecl(enm) => {
+            out.insert(enm.name.clone());
+        }
+        // A module block's own declarations aren't individually
+        // trackable -- see the comment on the top-level match in
+        // `analyze` -- but references inside its body still count
+        // toward whatever it calls out to.
+        StatementKind::ModuleDecl(block) => {
+            for stmt in &block.statements {
+                collect_statement_refs(stmt, out);
+            }
+        }
+        StatementKind::InterfaceDecl(_)
+        | StatementKind::ImplBlock(_)
+        | StatementKind::Import(_)
+        | StatementKind::Export(_)
+        | StatementKind::Break
+        | StatementKind::Continue
+        | StatementKind::Error => {}
+    }
+}
+
+pub(crate) fn collect_expr_refs(expr: &Expression, out: &mut HashSet<String>) {
+    match &expr.kind {
+        ExpressionKind::Identifier(name) => {
+            out.insert(name.clone());
+        }
+        ExpressionKind::Grouping(inner) | ExpressionKind::Try(inner) => collect_expr_refs(inner, out),
+        ExpressionKind::Unary { operand, .. } | ExpressionKind::Postfix { operand, .. } => {
+            collect_expr_refs(operand, out)
+        }
+        ExpressionKind::Binary { left, right, .. } => {
+            collect_expr_refs(left, out);
+            collect_expr_refs(right, out);
+        }
+        ExpressionKind::Call { callee, args } => {
+            // `x.f(y)` might be an impl/intrinsic method call, but could
+            // also be UFCS resolving to a top-level `func f`, which
+            // `collect_expr_refs` on `callee` alone wouldn't catch (a
+            // `Get`'s `name` isn't an identifier reference) -- mark it
+            // referenced too rather than risk a false "never used" E010
+            // for a function only ever called this way.
+            if let ExpressionKind::Get { name, .. } = &callee.kind {
+                out.insert(name.clone());
+            }
+            collect_expr_refs(callee, out);
+            for arg in args {
+                collect_expr_refs(arg, out);
+            }
+        }
+        ExpressionKind::Get { object, .. } => collect_expr_refs(object, out),
+        ExpressionKind::Index { object, index } => {
+            collect_expr_refs(object, out);
+            collect_expr_refs(index, out);
+        }
+        ExpressionKind::Assignment { target, value, .. } => {
+            collect_expr_refs(target, out);
+            collect_expr_refs(value, out);
+        }
+        ExpressionKind::ArrayLiteral(items) | ExpressionKind::Tuple(items) => {
+            for item in items {
+                collect_expr_refs(item, out);
+            }
+        }
+        ExpressionKind::MapLiteral(pairs) => {
+            for (key, value) in pairs {
+                collect_expr_refs(key, out);
+                collect_expr_refs(value, out);
+            }
+        }
+        ExpressionKind::StructInit { name, fields } => {
+            out.insert(name.clone());
+            for (_, value) in fields {
+                collect_expr_refs(value, out);
+            }
+        }
+        ExpressionKind::Lambda { body, .. } => {
+            for stmt in body {
+                collect_statement_refs(stmt, out);
+            }
+        }
+        ExpressionKind::Match { subject, arms } => {
+            collect_expr_refs(subject, out);
+            for arm in arms {
+                if let Pattern::EnumVariant { enum_name, .. } = &arm.pattern {
+                    out.insert(enum_name.clone());
+                }
+                for stmt in &arm.body {
+                    collect_statement_refs(stmt, out);
+                }
+            }
+        }
+        ExpressionKind::Literal(_) | ExpressionKind::Error => {}
+    }
+}
@@ -0,0 +1,427 @@
+//! Symbol storage for the resolver and typechecker.
+//!
+//! Local variable scopes are kept as a flat stack of [`Environment`]s
+//! addressed by index rather than as a linked chain of clones: entering a
+//! block pushes a new entry that merely records its parent's index (an
+//! `Option<usize>`, not a boxed clone of the parent's contents), so
+//! `push`/`pop` are O(1) regardless of how much has been declared so far
+//! and a child scope's [`SymbolTable::define`] can never write through to
+//! -- or accidentally diverge from -- its parent's bindings.
+
+use crate::ast::{Enum, Function, Impl, Interface, ModuleBlock, Struct, Type, TypeKind, UserTypeKind};
+use crate::common::Position;
+use crate::utils::intern::Symbol as Interned;
+use crate::utils::module::{ExportedKind, ExportedSymbol};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A declaration stored in the symbol table. Holding an `Arc` to the
+/// declaration node means copying a `Symbol` around (e.g. into a lookup
+/// result) is pointer-sized rather than proportional to the size of the
+/// function/struct body it names.
+#[derive(Debug, Clone)]
+pub struct Symbol<T> {
+    pub name: String,
+    node: Arc<T>,
+    pub position: Position,
+}
+
+impl<T> Symbol<T> {
+    pub fn new(name: String, node: Arc<T>, position: Position) -> Self {
+        Self {
+            name,
+            node,
+            position,
+        }
+    }
+
+    /// Returns a cheap handle to the underlying declaration.
+    pub fn get(&self) -> Arc<T> {
+        Arc::clone(&self.node)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    pub ty: Type,
+}
+
+/// What a name refers to, as returned by [`SymbolTable::resolve`] -- a
+/// single answer covering every category a [`SymbolTable`] indexes,
+/// instead of a caller checking `lookup`/`get_function`/`get_struct`/...
+/// one at a time.
+pub enum SymbolRef<'a> {
+    Variable(&'a Variable),
+    Function(&'a Symbol<Function>),
+    Struct(&'a Symbol<Struct>),
+    Enum(&'a Symbol<Enum>),
+    Interface(&'a Symbol<Interface>),
+}
+
+#[derive(Debug, Default)]
+struct Environment {
+    variables: HashMap<Interned, Variable>,
+    parent: Option<usize>,
+}
+
+/// Global declarations plus the local-scope stack used while resolving a
+/// function body.
+#[derive(Default)]
+pub struct SymbolTable {
+    pub functions: Vec<Symbol<Function>>,
+    pub structs: Vec<Symbol<Struct>>,
+    pub enums: Vec<Symbol<Enum>>,
+    pub interfaces: Vec<Symbol<Interface>>,
+    pub impls: Vec<Symbol<Impl>>,
+    /// Static/associated functions (`func (Point) new(...)`), kept out of
+    /// `functions` entirely so a bare, unqualified call can never resolve
+    /// one -- they're only reachable as `Point.new(...)`, indexed here by
+    /// `"Point.new"`.
+    pub static_methods: Vec<Symbol<Function>>,
+    /// `module Name { ... }` blocks, indexed by their fully-qualified
+    /// dotted path (`"Outer.Inner"` for a module nested inside another).
+    /// Members declared inside one are registered into `functions`/
+    /// `structs`/`enums` under that same qualified path -- this only
+    /// tracks the namespace itself existing, so [`Resolver::visit_get`]
+    /// can tell "unknown namespace" apart from "known namespace, unknown
+    /// member" when walking a dotted path.
+    pub namespaces: Vec<Symbol<ModuleBlock>>,
+    pub exported: Vec<String>,
+    pub exported_symbols: Vec<ExportedSymbol>,
+
+    /// Functions in declaration order, queued up for the typechecker.
+    /// Holding `Arc<Function>` here means queueing a declaration is a
+    /// pointer copy rather than a clone of its whole body.
+    pub decl_queue: Vec<Arc<Function>>,
+
+    /// Interned name -> index into `functions`/`structs`/`enums`/
+    /// `interfaces`, so `get_function`/`get_struct`/`get_enum`/
+    /// `get_interface` are an integer lookup instead of a linear scan
+    /// comparing `String`s.
+    function_index: HashMap<Interned, usize>,
+    struct_index: HashMap<Interned, usize>,
+    enum_index: HashMap<Interned, usize>,
+    interface_index: HashMap<Interned, usize>,
+    /// Interned `"Receiver.method"` -> index into `static_methods`.
+    static_method_index: HashMap<Interned, usize>,
+    /// Interned dotted path -> index into `namespaces`.
+    namespace_index: HashMap<Interned, usize>,
+
+    scopes: Vec<Environment>,
+    current: usize,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self {
+            functions: Vec::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+            interfaces: Vec::new(),
+            impls: Vec::new(),
+            static_methods: Vec::new(),
+            namespaces: Vec::new(),
+            exported: Vec::new(),
+            exported_symbols: Vec::new(),
+            decl_queue: Vec::new(),
+            function_index: HashMap::new(),
+            struct_index: HashMap::new(),
+            enum_index: HashMap::new(),
+            interface_index: HashMap::new(),
+            static_method_index: HashMap::new(),
+            namespace_index: HashMap::new(),
+            scopes: vec![Environment::default()],
+            current: 0,
+        }
+    }
+
+    /// Enters a new lexical scope. O(1): no existing environment is cloned.
+    pub fn push(&mut self) {
+        let parent = self.current;
+        self.scopes.push(Environment {
+            variables: HashMap::new(),
+            parent: Some(parent),
+        });
+        self.current = self.scopes.len() - 1;
+    }
+
+    /// Leaves the current scope, returning to its parent. O(1).
+    pub fn pop(&mut self) {
+        if let Some(parent) = self.scopes[self.current].parent {
+            self.current = parent;
+        }
+    }
+
+    pub fn define(&mut self, name: &str, ty: Type) {
+        self.scopes[self.current].variables.insert(
+            Interned::intern(name),
+            Variable {
+                name: name.to_string(),
+                ty,
+            },
+        );
+    }
+
+    /// Walks the chain of scope indices from the current scope up to the
+    /// root looking for `name`.
+    pub fn lookup(&self, name: &str) -> Option<&Variable> {
+        let name = Interned::intern(name);
+        let mut index = Some(self.current);
+        while let Some(i) = index {
+            if let Some(var) = self.scopes[i].variables.get(&name) {
+                return Some(var);
+            }
+            index = self.scopes[i].parent;
+        }
+        None
+    }
+
+    pub fn declare_function(&mut self, name: String, node: Arc<Function>, position: Position) {
+        self.function_index.entry(Interned::intern(&name)).or_insert(self.functions.len());
+        self.decl_queue.push(Arc::clone(&node));
+        self.functions.push(Symbol::new(name, node, position));
+    }
+
+    pub fn declare_struct(&mut self, name: String, node: Arc<Struct>, position: Position) {
+        self.struct_index.entry(Interned::intern(&name)).or_insert(self.structs.len());
+        self.structs.push(Symbol::new(name, node, position));
+    }
+
+    /// Declares a static/associated function (`node.receiver` must be
+    /// `Some`), queuing its body for the typechecker the same way
+    /// [`Self::declare_function`] does, but indexing it under
+    /// `"Receiver.name"` in [`Self::static_methods`] instead of the plain
+    /// function namespace.
+    pub fn declare_static_method(&mut self, node: Arc<Function>, position: Position) {
+        let receiver = node.receiver.clone().expect("declare_static_method requires a receiver");
+        let key = format!("{}.{}", receiver, node.name);
+        self.static_method_index
+            .entry(Interned::intern(&key))
+            .or_insert(self.static_methods.len());
+        self.decl_queue.push(Arc::clone(&node));
+        let name = node.name.clone();
+        self.static_methods.push(Symbol::new(name, node, position));
+    }
+
+    pub fn get_static_method(&self, receiver: &str, name: &str) -> Option<&Symbol<Function>> {
+        let key = format!("{}.{}", receiver, name);
+        self.static_method_index.get(&Interned::intern(&key)).map(|&i| &self.static_methods[i])
+    }
+
+    /// Registers a `module` block under its fully-qualified dotted `path`
+    /// (already prefixed by any enclosing module).
+    pub fn declare_namespace(&mut self, path: String, node: Arc<ModuleBlock>, position: Position) {
+        self.namespace_index.entry(Interned::intern(&path)).or_insert(self.namespaces.len());
+        self.namespaces.push(Symbol::new(path, node, position));
+    }
+
+    pub fn namespace_exists(&self, path: &str) -> bool {
+        self.namespace_index.contains_key(&Interned::intern(path))
+    }
+
+    pub fn declare_enum(&mut self, name: String, node: Arc<Enum>, position: Position) {
+        self.enum_index.entry(Interned::intern(&name)).or_insert(self.enums.len());
+        self.enums.push(Symbol::new(name, node, position));
+    }
+
+    pub fn get_function(&self, name: &str) -> Option<&Symbol<Function>> {
+        self.function_index.get(&Interned::intern(name)).map(|&i| &self.functions[i])
+    }
+
+    pub fn get_struct(&self, name: &str) -> Option<&Symbol<Struct>> {
+        self.struct_index.get(&Interned::intern(name)).map(|&i| &self.structs[i])
+    }
+
+    pub fn get_enum(&self, name: &str) -> Option<&Symbol<Enum>> {
+        self.enum_index.get(&Interned::intern(name)).map(|&i| &self.enums[i])
+    }
+
+    pub fn declare_interface(&mut self, name: String, node: Arc<Interface>, position: Position) {
+        self.interface_index.entry(Interned::intern(&name)).or_insert(self.interfaces.len());
+        self.interfaces.push(Symbol::new(name, node, position));
+    }
+
+    pub fn get_interface(&self, name: &str) -> Option<&Symbol<Interface>> {
+        self.interface_index.get(&Interned::intern(name)).map(|&i| &self.interfaces[i])
+    }
+
+    /// Resolves `name` against the local scope chain, then each category
+    /// of top-level declaration in turn -- the same priority a bare
+    /// identifier is checked in ([`crate::semantic::Resolver`]: a local
+    /// binding shadows a same-named declaration). One indexed lookup per
+    /// category tried, instead of a caller hand-chaining `lookup`/
+    /// `get_function`/`get_struct`/... itself.
+    pub fn resolve(&self, name: &str) -> Option<SymbolRef<'_>> {
+        if let Some(var) = self.lookup(name) {
+            return Some(SymbolRef::Variable(var));
+        }
+        if let Some(func) = self.get_function(name) {
+            return Some(SymbolRef::Function(func));
+        }
+        if let Some(strukt) = self.get_struct(name) {
+            return Some(SymbolRef::Struct(strukt));
+        }
+        if let Some(enm) = self.get_enum(name) {
+            return Some(SymbolRef::Enum(enm));
+        }
+        if let Some(iface) = self.get_interface(name) {
+            return Some(SymbolRef::Interface(iface));
+        }
+        None
+    }
+
+    /// Returns the kind of top-level declaration named `name`, if any.
+    /// Used to validate `export` blocks. A name bound by `import` resolves
+    /// as a [`SymbolRef::Variable`] rather than a declaration -- it's still
+    /// exportable, just as a re-export ([`ExportedKind::Module`]) rather
+    /// than something declared in this file.
+    pub fn exportable_kind(&self, name: &str) -> Option<ExportedKind> {
+        match self.resolve(name)? {
+            SymbolRef::Function(_) => Some(ExportedKind::Function),
+            SymbolRef::Struct(_) => Some(ExportedKind::Struct),
+            SymbolRef::Enum(_) => Some(ExportedKind::Enum),
+            SymbolRef::Interface(_) => Some(ExportedKind::Interface),
+            SymbolRef::Variable(var) => match &var.ty.kind {
+                TypeKind::UserType(_, UserTypeKind::Module, _) => Some(ExportedKind::Module),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn struct_exists(&self, name: &str) -> bool {
+        self.get_struct(name).is_some()
+    }
+
+    pub fn enum_exists(&self, name: &str) -> bool {
+        self.get_enum(name).is_some()
+    }
+
+    /// The enum that declares `variant` as one of its variants, if
+    /// exactly one does -- lets a bare variant name (`Red`, unqualified)
+    /// resolve the same way `EnumName.Variant` does. `None` both when no
+    /// enum declares it and when more than one does, so an ambiguous bare
+    /// name still has to be qualified.
+    pub fn find_enum_by_variant(&self, variant: &str) -> Option<&Symbol<Enum>> {
+        let mut matches = self.enums.iter().filter(|enu| enu.get().variants.iter().any(|v| v.name == variant));
+        let first = matches.next()?;
+        matches.next().is_none().then_some(first)
+    }
+
+    /// The closest-matching name visible here to `name` -- a declared
+    /// function, or a variable from the current scope chain -- for a "did
+    /// you mean" note on an undefined-symbol diagnostic. `None` if nothing
+    /// is close enough to be worth suggesting.
+    pub fn suggest(&self, name: &str) -> Option<String> {
+        let functions = self.functions.iter().map(|symbol| symbol.name.as_str());
+        let variables = self.visible_variable_names();
+        best_suggestion(name, functions.chain(variables))
+    }
+
+    /// Same as [`Self::suggest`], but only considers struct names -- for
+    /// an undefined-struct diagnostic, where suggesting a function or
+    /// variable wouldn't make sense.
+    pub fn suggest_struct(&self, name: &str) -> Option<String> {
+        best_suggestion(name, self.structs.iter().map(|symbol| symbol.name.as_str()))
+    }
+
+    /// Every variable name visible from the current scope, walking the
+    /// chain up to the root the same way [`Self::lookup`] does.
+    fn visible_variable_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        let mut index = Some(self.current);
+        while let Some(i) = index {
+            names.extend(self.scopes[i].variables.values().map(|var| var.name.as_str()));
+            index = self.scopes[i].parent;
+        }
+        names
+    }
+}
+
+/// Below this [`jaro_winkler`] score, a candidate is considered unrelated
+/// rather than a likely typo -- chosen so a single-character slip on a
+/// short identifier (`fx` vs `fn`) still matches, without suggesting
+/// completely unrelated names for very short, coincidentally-similar
+/// inputs.
+const SUGGESTION_THRESHOLD: f64 = 0.75;
+
+/// The `candidates` entry most similar to `name` by [`jaro_winkler`], as
+/// long as it clears [`SUGGESTION_THRESHOLD`]. `pub(crate)` so
+/// [`crate::semantic::Resolver`] can reuse it for type-name suggestions
+/// that need candidates beyond a single [`SymbolTable`] (an imported
+/// module's exports, say), not just this one.
+pub(crate) fn best_suggestion<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    candidates
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (candidate, jaro_winkler(name, candidate)))
+        .filter(|(_, score)| *score >= SUGGESTION_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(candidate, _)| candidate.to_string())
+}
+
+/// Jaro-Winkler similarity between `a` and `b`, in `0.0..=1.0`. Weighs a
+/// shared prefix more heavily than plain Jaro similarity, which fits
+/// identifiers well: a typo near the end of a name (`resolv` vs
+/// `resolve`) should still score higher than one that changes how the
+/// name starts.
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let jaro = jaro_similarity(a, b);
+    if jaro <= 0.7 {
+        return jaro;
+    }
+    let prefix_len = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).take(4).count();
+    jaro + prefix_len as f64 * 0.1 * (1.0 - jaro)
+}
+
+fn jaro_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let match_distance = a.len().max(b.len()) / 2;
+    let match_distance = match_distance.saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+
+    for i in 0..a.len() {
+        let start = i.saturating_sub(match_distance);
+        let end = (i + match_distance + 1).min(b.len());
+        for (j, matched) in b_matched.iter_mut().enumerate().take(end).skip(start) {
+            if *matched || a[i] != b[j] {
+                continue;
+            }
+            a_matched[i] = true;
+            *matched = true;
+            matches += 1;
+            break;
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let mut transpositions = 0usize;
+    let mut k = 0;
+    for (i, matched) in a_matched.iter().enumerate() {
+        if !matched {
+            continue;
+        }
+        while !b_matched[k] {
+            k += 1;
+        }
+        if a[i] != b[k] {
+            transpositions += 1;
+        }
+        k += 1;
+    }
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - (transpositions as f64 / 2.0)) / m) / 3.0
+}
@@ -0,0 +1,277 @@
+//! Static import dependency graph, discovered by parsing each module's
+//! `import` statements without running the rest of the resolver, and
+//! topologically sorted into layers of mutually-independent modules.
+//!
+//! Modules within a layer only depend on earlier layers (never on each
+//! other), so [`compile_parallel`] compiles each layer's modules on
+//! separate threads and merges the results before moving to the next
+//! layer — a multi-module build no longer pays for reparsing every
+//! dependency serially. [`Compilation`] is the resulting whole-project
+//! verdict: every reachable module's exported interface, plus whether any
+//! of them reported a diagnostic.
+
+use crate::ast::StatementKind;
+use crate::semantic::environment::SymbolTable;
+use crate::semantic::{FirstPassResolver, Resolver, Typechecker};
+use crate::utils::module::MatchaModule;
+use crate::utils::project::ProjectManifest;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+struct Node {
+    file: PathBuf,
+    deps: Vec<String>,
+}
+
+/// The import graph reachable from a compilation's entry file.
+#[derive(Default)]
+pub struct DependencyGraph {
+    nodes: HashMap<String, Node>,
+}
+
+impl DependencyGraph {
+    /// Parses `entry_file` and everything it transitively imports,
+    /// resolving each import path against `project`. An import that
+    /// doesn't resolve to a file on disk is left out of the graph — the
+    /// same "nothing to check" case the sequential loader in
+    /// [`crate::semantic::Resolver`] already tolerates for stdlib paths
+    /// with no manifest.
+    pub fn discover(entry_file: &Path, project: &ProjectManifest) -> Self {
+        let mut graph = DependencyGraph::default();
+        let mut queue: VecDeque<(String, PathBuf)> = VecDeque::new();
+        let mut queued: HashSet<String> = HashSet::new();
+
+        if let Ok(source) = std::fs::read_to_string(entry_file) {
+            for path in direct_imports(&source) {
+                if let Some(file) = resolve(project, &path) {
+                    if queued.insert(path.clone()) {
+                        queue.push_back((path, file));
+                    }
+                }
+            }
+        }
+
+        while let Some((path, file)) = queue.pop_front() {
+            let Ok(source) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            let mut deps = Vec::new();
+            for dep_path in direct_imports(&source) {
+                if let Some(dep_file) = resolve(project, &dep_path) {
+                    deps.push(dep_path.clone());
+                    if queued.insert(dep_path.clone()) {
+                        queue.push_back((dep_path, dep_file));
+                    }
+                }
+            }
+            graph.nodes.insert(path, Node { file, deps });
+        }
+
+        graph
+    }
+
+    /// Kahn's algorithm: each returned layer depends only on earlier
+    /// layers, so it can be compiled once those finish, and every module
+    /// within a layer can be compiled concurrently. `Err` carries the
+    /// names still unresolved once no more progress can be made, i.e. a
+    /// cycle — the caller falls back to the sequential loader, which
+    /// reports it properly (see [`crate::semantic::Resolver::visit_import`]).
+    pub fn topo_layers(&self) -> Result<Vec<Vec<String>>, Vec<String>> {
+        let mut in_degree: HashMap<&str, usize> = self.nodes.keys().map(|n| (n.as_str(), 0)).collect();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (name, node) in &self.nodes {
+            for dep in &node.deps {
+                if let Some(count) = in_degree.get_mut(name.as_str()) {
+                    *count += 1;
+                }
+                dependents.entry(dep.as_str()).or_default().push(name.as_str());
+            }
+        }
+
+        let mut remaining: HashSet<&str> = self.nodes.keys().map(String::as_str).collect();
+        let mut layers = Vec::new();
+        while !remaining.is_empty() {
+            let layer: Vec<&str> = remaining
+                .iter()
+                .copied()
+                .filter(|n| in_degree[n] == 0)
+                .collect();
+            if layer.is_empty() {
+                return Err(remaining.into_iter().map(String::from).collect());
+            }
+            for name in &layer {
+                remaining.remove(name);
+                for dependent in dependents.get(name).into_iter().flatten() {
+                    if let Some(count) = in_degree.get_mut(dependent) {
+                        *count -= 1;
+                    }
+                }
+            }
+            layers.push(layer.into_iter().map(String::from).collect());
+        }
+        Ok(layers)
+    }
+
+    /// Every source file reachable from the entry file this graph was
+    /// discovered from.
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.nodes.values().map(|node| node.file.clone()).collect()
+    }
+
+    /// Every module's import path alongside the paths it directly
+    /// imports, sorted by name so callers get a stable rendering.
+    pub fn edges(&self) -> Vec<(String, Vec<String>)> {
+        let mut edges: Vec<(String, Vec<String>)> = self
+            .nodes
+            .iter()
+            .map(|(name, node)| (name.clone(), node.deps.clone()))
+            .collect();
+        edges.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, deps) in &mut edges {
+            deps.sort();
+        }
+        edges
+    }
+
+    /// Renders the same edges as [`Self::edges`] as Graphviz DOT, for
+    /// piping into `dot -Tpng` (or any other renderer) instead of reading
+    /// `path -> [deps]` lines by eye.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph deps {\n");
+        for (name, deps) in self.edges() {
+            if deps.is_empty() {
+                out.push_str(&format!("    {:?};\n", name));
+                continue;
+            }
+            for dep in deps {
+                out.push_str(&format!("    {:?} -> {:?};\n", name, dep));
+            }
+        }
+        out.push('}');
+        out.push('\n');
+        out
+    }
+}
+
+fn resolve(project: &ProjectManifest, path: &str) -> Option<PathBuf> {
+    let segments: Vec<String> = path.split('.').map(String::from).collect();
+    project.resolve_import(&segments)
+}
+
+fn direct_imports(source: &str) -> Vec<String> {
+    let mut bag = crate::errors::DiagnosticBag::new();
+    let mut lexer = crate::lexer::Lexer::new(source);
+    let tokens = lexer.scan_tokens();
+    let mut parser = crate::parser::Parser::new(tokens, "", &mut bag);
+    let module = parser.parse();
+    module
+        .statements
+        .iter()
+        .filter_map(|statement| match &statement.kind {
+            StatementKind::Import(import) => Some(import.path.join(".")),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The result of resolving and type-checking every module reachable from
+/// an entry file's imports, in dependency order: the whole-project
+/// counterpart to compiling a single file's own body.
+///
+/// `modules` is the exported interface of every module that resolved to a
+/// readable file, keyed by import path, for seeding a nested [`Resolver`]
+/// the way [`compile_parallel`] always has. `had_error` additionally rolls
+/// up whether *any* of them reported a diagnostic — resolving a module in
+/// isolation only ever produced its interface and discarded that verdict,
+/// so a broken dependency could sit behind a clean `matcha check` on the
+/// file that imports it. `Compilation` makes that count.
+#[derive(Default)]
+pub struct Compilation {
+    pub modules: HashMap<String, MatchaModule>,
+    pub had_error: bool,
+}
+
+impl Compilation {
+    /// Discovers every module `entry_file` transitively imports and
+    /// resolves them together, in topological order. A cyclic import
+    /// graph can't be laid out into layers at all: `discover`'s caller
+    /// falls back to the sequential loader, which detects and reports the
+    /// cycle itself (see [`crate::semantic::Resolver::visit_import`]), so
+    /// this simply reports no dependencies rather than duplicating that
+    /// diagnostic.
+    pub fn run(entry_file: &Path, project: &ProjectManifest) -> Self {
+        let graph = DependencyGraph::discover(entry_file, project);
+        match graph.topo_layers() {
+            Ok(layers) => compile_parallel(&graph, &layers),
+            Err(_) => Compilation::default(),
+        }
+    }
+}
+
+/// Compiles every module in `graph`, one layer at a time, with the
+/// modules inside each layer compiled on separate threads.
+pub fn compile_parallel(graph: &DependencyGraph, layers: &[Vec<String>]) -> Compilation {
+    let cache: Mutex<HashMap<String, MatchaModule>> = Mutex::new(HashMap::new());
+    let had_error = Mutex::new(false);
+    for layer in layers {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = layer
+                .iter()
+                .filter_map(|name| graph.nodes.get(name).map(|node| (name, node)))
+                .map(|(name, node)| scope.spawn(move || (name.clone(), compile_interface(name, &node.file))))
+                .collect();
+            let mut cache = cache.lock().unwrap();
+            let mut had_error = had_error.lock().unwrap();
+            for handle in handles {
+                if let Ok((name, Some((module, module_had_error)))) = handle.join() {
+                    *had_error |= module_had_error;
+                    cache.insert(name, module);
+                }
+            }
+        });
+    }
+    Compilation {
+        modules: cache.into_inner().unwrap(),
+        had_error: had_error.into_inner().unwrap(),
+    }
+}
+
+/// Resolves and type-checks one dependency module, the same way
+/// [`crate::utils::compile::compile`] treats an entry file, and reports
+/// its exported interface alongside whether it had any errors. A module
+/// whose own source has errors still produces *some* interface (whatever
+/// the resolver managed to build) — those errors are reported here, once,
+/// rather than silently deferred to whichever file happens to import it.
+fn compile_interface(path: &str, file: &Path) -> Option<(MatchaModule, bool)> {
+    let source = std::fs::read_to_string(file).ok()?;
+    let file_name = file.to_string_lossy().into_owned();
+
+    // Each dependency module compiled here runs on its own thread (see
+    // `compile_parallel`), so its diagnostics collect into a bag of their
+    // own rather than one shared across the layer, and are rendered as
+    // soon as this module's own compile finishes.
+    let mut bag = crate::errors::DiagnosticBag::new();
+
+    let mut lexer = crate::lexer::Lexer::new(&source);
+    let tokens = lexer.scan_tokens();
+    let mut parser = crate::parser::Parser::new(tokens, file_name.clone(), &mut bag);
+    let module_ast = parser.parse();
+    let parser_had_error = parser.had_error;
+
+    let mut symtable = SymbolTable::new();
+    FirstPassResolver::new(&mut symtable).run(&module_ast);
+    let resolver_had_error = {
+        let mut resolver = Resolver::new(&mut symtable, file_name.clone(), &mut bag);
+        resolver.resolve(&module_ast);
+        resolver.had_error
+    };
+
+    let mut typechecker = Typechecker::new(file_name, &mut bag);
+    typechecker.seed(&symtable);
+    typechecker.run();
+
+    let had_error = parser_had_error || resolver_had_error || typechecker.had_error;
+    bag.report_all();
+    Some((MatchaModule::from_symtable(path.to_string(), &symtable), had_error))
+}
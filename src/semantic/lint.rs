@@ -0,0 +1,585 @@
+//! Style lints over a resolved module: unused variables, unused function
+//! parameters, unused imports, bindings that shadow an enclosing scope's,
+//! statements that can never run, and calls to a function this module
+//! itself declared `@deprecated`. Unlike [`crate::semantic::dce`], which
+//! asks whether a top-level declaration is reachable at all, these ask
+//! whether a binding *within* a function body ever gets used once it's
+//! in scope, or whether control flow can ever reach a given point in
+//! one.
+//!
+//! Each lint can be silenced with `-A<name>` on the command line, or
+//! re-enabled with `-W<name>` (every lint warns by default) — consulted
+//! here as process-wide state the same way [`crate::errors::format`] is
+//! consulted by `Diagnostic::report`, rather than threaded through every
+//! function body this walks.
+
+use crate::ast::{Expression, ExpressionKind, Function, LiteralValue, Module, Statement, StatementKind};
+use crate::common::Position;
+use crate::errors::{Diagnostic, DiagnosticBag};
+use crate::semantic::dce;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedImport,
+    UnusedParameter,
+    Shadowing,
+    UnreachableCode,
+    LossyConversion,
+    Deprecated,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedVariable,
+        Lint::UnusedImport,
+        Lint::UnusedParameter,
+        Lint::Shadowing,
+        Lint::UnreachableCode,
+        Lint::LossyConversion,
+        Lint::Deprecated,
+    ];
+
+    /// The name matched against `-W<name>`/`-A<name>`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedImport => "unused-import",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::Shadowing => "shadowing",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::LossyConversion => "lossy-conversion",
+            Lint::Deprecated => "deprecated",
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "E011",
+            Lint::UnusedImport => "E012",
+            Lint::UnusedParameter => "E013",
+            Lint::Shadowing => "E014",
+            Lint::UnreachableCode => "E015",
+            Lint::LossyConversion => "E016",
+            Lint::Deprecated => "E017",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+static ALLOWED: AtomicU8 = AtomicU8::new(0);
+
+/// Silences `lint` (`-A<name>`).
+pub fn allow(lint: Lint) {
+    ALLOWED.fetch_or(lint.bit(), Ordering::Relaxed);
+}
+
+/// Re-enables `lint` (`-W<name>`) after an earlier [`allow`] earlier in
+/// the same argument list — every lint warns by default, so this only
+/// matters once something upstream of it already called `allow`.
+pub fn warn(lint: Lint) {
+    ALLOWED.fetch_and(!lint.bit(), Ordering::Relaxed);
+}
+
+fn is_allowed(lint: Lint) -> bool {
+    ALLOWED.load(Ordering::Relaxed) & lint.bit() != 0
+}
+
+/// Warns that an implicit numeric conversion crossed a precision or
+/// signedness boundary -- e.g. `Int8 + Int32` silently widening to
+/// `Int32`, or `Int32 + UInt32` silently picking a side despite neither
+/// containing the other's range. Called from
+/// [`crate::semantic::unify_reporting`] once it already knows two
+/// distinct numeric types were unified rather than rejected, since only
+/// the typechecker has the resolved operand types this needs -- unlike
+/// every other lint in this file, which only needs the parsed AST.
+pub(crate) fn warn_lossy_conversion(
+    from: &crate::ast::TypeKind,
+    to: &crate::ast::TypeKind,
+    position: Position,
+    file: &str,
+    bag: &mut DiagnosticBag,
+) {
+    if is_allowed(Lint::LossyConversion) {
+        return;
+    }
+    bag.push(
+        Diagnostic::warning(
+            Lint::LossyConversion.code(),
+            format!(
+                "implicit conversion from '{:?}' to '{:?}' crosses a precision or signedness boundary; make it explicit",
+                from, to
+            ),
+            position,
+        ),
+        file,
+    );
+}
+
+/// A function this module itself declares `@deprecated`, keyed by name --
+/// the message from `@deprecated("...")` (empty if bare) plus the
+/// declaration's own position, attached to a use-site warning as a
+/// `note:` pointing back at it (see [`warn_deprecated`]).
+struct DeprecatedFn {
+    message: String,
+    position: Position,
+}
+
+/// Runs every lint over `module`, pushing each finding as a warning
+/// against `file` into `bag` -- the same bag `compile_source` builds, so
+/// a caller (e.g. `matcha lsp`'s `publish_diagnostics`) that reads
+/// `CompileResult.diagnostics` sees these warnings too, instead of only
+/// whatever `Diagnostic::report` printed straight to stderr.
+pub fn analyze(module: &Module, file: &str, bag: &mut DiagnosticBag) {
+    let deprecated = if is_allowed(Lint::Deprecated) {
+        HashMap::new()
+    } else {
+        collect_deprecated_functions(&module.statements)
+    };
+    analyze_statements(&module.statements, &deprecated, file, bag);
+    check_unused_imports(module, file, bag);
+}
+
+/// Collects every `@deprecated` function declared anywhere in `statements`
+/// (including nested `module` blocks), by name. Only this module's own
+/// declarations are ever known here -- a call reaching a deprecated
+/// function through an `import` is invisible to this pass, which has no
+/// symbol table to resolve it against.
+fn collect_deprecated_functions(statements: &[Statement]) -> HashMap<String, DeprecatedFn> {
+    let mut out = HashMap::new();
+    collect_deprecated_functions_into(statements, &mut out);
+    out
+}
+
+fn collect_deprecated_functions_into(statements: &[Statement], out: &mut HashMap<String, DeprecatedFn>) {
+    for statement in statements {
+        match &statement.kind {
+            StatementKind::FunctionDecl(function) => {
+                if let Some(message) = &function.deprecated {
+                    out.insert(
+                        function.name.clone(),
+                        DeprecatedFn {
+                            message: message.clone(),
+                            position: function.position,
+                        },
+                    );
+                }
+            }
+            StatementKind::ModuleDecl(block) => collect_deprecated_functions_into(&block.statements, out),
+            _ => {}
+        }
+    }
+}
+
+fn warn_deprecated(name: &str, deprecated: &DeprecatedFn, position: Position, file: &str, bag: &mut DiagnosticBag) {
+    let message = if deprecated.message.is_empty() {
+        format!("use of deprecated function `{}`", name)
+    } else {
+        format!("use of deprecated function `{}`: {}", name, deprecated.message)
+    };
+    bag.push(
+        Diagnostic::warning(Lint::Deprecated.code(), message, position)
+            .with_note(format!("`{}` is declared here ({}:{})", name, file, deprecated.position)),
+        file,
+    );
+}
+
+fn analyze_statements(statements: &[Statement], deprecated: &HashMap<String, DeprecatedFn>, file: &str, bag: &mut DiagnosticBag) {
+    for statement in statements {
+        match &statement.kind {
+            StatementKind::FunctionDecl(function) => check_function(function, deprecated, file, bag),
+            StatementKind::ImplBlock(imp) => {
+                for method in &imp.methods {
+                    check_function(method, deprecated, file, bag);
+                }
+            }
+            StatementKind::ModuleDecl(block) => analyze_statements(&block.statements, deprecated, file, bag),
+            _ => {}
+        }
+    }
+}
+
+enum LocalKind {
+    Variable,
+    Parameter,
+}
+
+struct Local {
+    name: String,
+    position: Position,
+    kind: LocalKind,
+    used: bool,
+}
+
+fn check_function(function: &Function, deprecated: &HashMap<String, DeprecatedFn>, file: &str, bag: &mut DiagnosticBag) {
+    // An `extern` function's parameters describe a foreign signature, not
+    // bindings that get used or shadowed in a body -- there is no body.
+    if function.extern_info.is_some() {
+        return;
+    }
+    let mut scopes: Vec<Vec<Local>> = vec![Vec::new()];
+    for param in &function.params {
+        declare(&mut scopes, param.name.clone(), param.ty.position, LocalKind::Parameter, file, bag);
+    }
+    // The body gets its own scope, distinct from the parameter list, so a
+    // local that reuses a parameter's name is flagged as shadowing it
+    // rather than treated as a same-scope redeclaration.
+    visit_block(&function.body, &mut scopes, deprecated, file, bag);
+    finish_scope(scopes.pop().unwrap(), file, bag);
+}
+
+/// Declares `name` in the innermost scope, warning first if it shadows a
+/// binding already visible from an enclosing one — same-scope
+/// redeclaration (`let x = 1; let x = 2;` in one block) isn't shadowing,
+/// just the usual last-write-wins a fresh `let` already gets.
+fn declare(scopes: &mut [Vec<Local>], name: String, position: Position, kind: LocalKind, file: &str, bag: &mut DiagnosticBag) {
+    if !is_allowed(Lint::Shadowing) {
+        let (_, enclosing) = scopes.split_last().unwrap();
+        if enclosing.iter().any(|scope| scope.iter().any(|local| local.name == name)) {
+            bag.push(
+                Diagnostic::warning(
+                    Lint::Shadowing.code(),
+                    format!("`{}` shadows a binding from an enclosing scope", name),
+                    position,
+                ),
+                file,
+            );
+        }
+    }
+    scopes.last_mut().unwrap().push(Local {
+        name,
+        position,
+        kind,
+        used: false,
+    });
+}
+
+/// Marks the nearest (innermost-scope-first) binding named `name` as used.
+fn mark_used(scopes: &mut [Vec<Local>], name: &str) {
+    for scope in scopes.iter_mut().rev() {
+        if let Some(local) = scope.iter_mut().rev().find(|local| local.name == name) {
+            local.used = true;
+            return;
+        }
+    }
+}
+
+fn finish_scope(scope: Vec<Local>, file: &str, bag: &mut DiagnosticBag) {
+    for local in scope {
+        if local.used {
+            continue;
+        }
+        let (lint, noun) = match local.kind {
+            LocalKind::Variable => (Lint::UnusedVariable, "variable"),
+            LocalKind::Parameter => (Lint::UnusedParameter, "parameter"),
+        };
+        if is_allowed(lint) {
+            continue;
+        }
+        bag.push(
+            Diagnostic::warning(lint.code(), format!("unused {} `{}`", noun, local.name), local.position),
+            file,
+        );
+    }
+}
+
+fn visit_block(statements: &[Statement], scopes: &mut Vec<Vec<Local>>, deprecated: &HashMap<String, DeprecatedFn>, file: &str, bag: &mut DiagnosticBag) {
+    check_unreachable(statements, file, bag);
+    scopes.push(Vec::new());
+    for statement in statements {
+        visit_statement(statement, scopes, deprecated, file, bag);
+    }
+    finish_scope(scopes.pop().unwrap(), file, bag);
+}
+
+/// Warns once about the first statement made unreachable by an earlier
+/// `return`/`break`/`continue` in the same block, and once about the
+/// first statement of a branch whose condition constant-folds (see
+/// [`crate::semantic::constant_fold`], which runs before this lint does)
+/// to a literal that rules it out. Nested blocks get their own call to
+/// this function as `visit_block` recurses into them, so a branch that's
+/// itself unreachable can still be flagged again for what's unreachable
+/// inside it.
+fn check_unreachable(statements: &[Statement], file: &str, bag: &mut DiagnosticBag) {
+    if is_allowed(Lint::UnreachableCode) {
+        return;
+    }
+    if let Some(terminator) = statements.iter().position(is_terminator) {
+        if let Some(first_dead) = statements.get(terminator + 1) {
+            let cause = match &statements[terminator].kind {
+                StatementKind::Return(_) => "return",
+                StatementKind::Break => "break",
+                StatementKind::Continue => "continue",
+                _ => unreachable!("is_terminator only matches these three kinds"),
+            };
+            warn_unreachable(first_dead.position, &format!("after `{}`", cause), file, bag);
+        }
+    }
+    for statement in statements {
+        match &statement.kind {
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if is_literal_bool(condition, false) {
+                    warn_dead_branch(then_branch, "condition is always `false`", file, bag);
+                } else if is_literal_bool(condition, true) {
+                    if let Some(else_branch) = else_branch {
+                        warn_dead_branch(else_branch, "condition is always `true`", file, bag);
+                    }
+                }
+            }
+            StatementKind::While { condition, body } if is_literal_bool(condition, false) => {
+                warn_dead_branch(body, "condition is always `false`", file, bag);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_terminator(statement: &Statement) -> bool {
+    matches!(
+        statement.kind,
+        StatementKind::Return(_) | StatementKind::Break | StatementKind::Continue
+    )
+}
+
+fn is_literal_bool(expr: &Expression, value: bool) -> bool {
+    matches!(expr.kind, ExpressionKind::Literal(LiteralValue::Bool(b)) if b == value)
+}
+
+fn warn_dead_branch(branch: &[Statement], reason: &str, file: &str, bag: &mut DiagnosticBag) {
+    if let Some(first) = branch.first() {
+        warn_unreachable(first.position, reason, file, bag);
+    }
+}
+
+fn warn_unreachable(position: Position, reason: &str, file: &str, bag: &mut DiagnosticBag) {
+    bag.push(
+        Diagnostic::warning(Lint::UnreachableCode.code(), format!("unreachable code: {}", reason), position),
+        file,
+    );
+}
+
+fn visit_statement(statement: &Statement, scopes: &mut Vec<Vec<Local>>, deprecated: &HashMap<String, DeprecatedFn>, file: &str, bag: &mut DiagnosticBag) {
+    match &statement.kind {
+        StatementKind::Expression(expr) => visit_expression(expr, scopes, deprecated, file, bag),
+        StatementKind::Let { name, value, .. } => {
+            if let Some(value) = value {
+                visit_expression(value, scopes, deprecated, file, bag);
+            }
+            declare(scopes, name.clone(), statement.position, LocalKind::Variable, file, bag);
+        }
+        StatementKind::Return(Some(expr)) => visit_expression(expr, scopes, deprecated, file, bag),
+        StatementKind::Return(None) => {}
+        StatementKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            visit_expression(condition, scopes, deprecated, file, bag);
+            visit_block(then_branch, scopes, deprecated, file, bag);
+            if let Some(else_branch) = else_branch {
+                visit_block(else_branch, scopes, deprecated, file, bag);
+            }
+        }
+        StatementKind::While { condition, body } => {
+            visit_expression(condition, scopes, deprecated, file, bag);
+            visit_block(body, scopes, deprecated, file, bag);
+        }
+        StatementKind::For {
+            init,
+            condition,
+            update,
+            body,
+        } => {
+            scopes.push(Vec::new());
+            if let Some(init) = init {
+                visit_statement(init, scopes, deprecated, file, bag);
+            }
+            if let Some(condition) = condition {
+                visit_expression(condition, scopes, deprecated, file, bag);
+            }
+            if let Some(update) = update {
+                visit_expression(update, scopes, deprecated, file, bag);
+            }
+            for stmt in body {
+                visit_statement(stmt, scopes, deprecated, file, bag);
+            }
+            finish_scope(scopes.pop().unwrap(), file, bag);
+        }
+        StatementKind::ForEach {
+            variable,
+            iterable,
+            body,
+        } => {
+            visit_expression(iterable, scopes, deprecated, file, bag);
+            scopes.push(Vec::new());
+            declare(scopes, variable.clone(), statement.position, LocalKind::Variable, file, bag);
+            for stmt in body {
+                visit_statement(stmt, scopes, deprecated, file, bag);
+            }
+            finish_scope(scopes.pop().unwrap(), file, bag);
+        }
+        StatementKind::Block(stmts) => visit_block(stmts, scopes, deprecated, file, bag),
+        StatementKind::Match { subject, arms } => {
+            visit_expression(subject, scopes, deprecated, file, bag);
+            for arm in arms {
+                // A bare-identifier pattern may bind the matched value or
+                // may just be an enum-variant tag test; telling them apart
+                // needs the symbol table this pass doesn't have, so its
+                // name is left unchecked here rather than risking a false
+                // "unused variable" on a tag it never actually declared.
+                visit_block(&arm.body, scopes, deprecated, file, bag);
+            }
+        }
+        StatementKind::StructDecl(_)
+        | StatementKind::EnumDecl(_)
+        | StatementKind::InterfaceDecl(_)
+        | StatementKind::ImplBlock(_)
+        | StatementKind::ModuleDecl(_)
+        | StatementKind::Import(_)
+        | StatementKind::Export(_)
+        | StatementKind::FunctionDecl(_)
+        | StatementKind::Break
+        | StatementKind::Continue
+        | StatementKind::Error => {}
+    }
+}
+
+fn visit_expression(expr: &Expression, scopes: &mut Vec<Vec<Local>>, deprecated: &HashMap<String, DeprecatedFn>, file: &str, bag: &mut DiagnosticBag) {
+    match &expr.kind {
+        ExpressionKind::Identifier(name) => mark_used(scopes, name),
+        ExpressionKind::Binary { left, right, .. } => {
+            visit_expression(left, scopes, deprecated, file, bag);
+            visit_expression(right, scopes, deprecated, file, bag);
+        }
+        ExpressionKind::Unary { operand, .. } | ExpressionKind::Postfix { operand, .. } => {
+            visit_expression(operand, scopes, deprecated, file, bag);
+        }
+        ExpressionKind::Grouping(inner) | ExpressionKind::Try(inner) => visit_expression(inner, scopes, deprecated, file, bag),
+        ExpressionKind::Call { callee, args } => {
+            // Only a direct, unqualified call (`name(...)`) is checked --
+            // one routed through a `Get` (`mod.name(...)`) or an imported
+            // binding names a declaration this pass never reads, so
+            // there'd be no `deprecated` field to check in the first
+            // place.
+            if let ExpressionKind::Identifier(name) = &callee.kind {
+                if let Some(dep) = deprecated.get(name) {
+                    warn_deprecated(name, dep, callee.position, file, bag);
+                }
+            }
+            visit_expression(callee, scopes, deprecated, file, bag);
+            for arg in args {
+                visit_expression(arg, scopes, deprecated, file, bag);
+            }
+        }
+        ExpressionKind::Get { object, .. } => visit_expression(object, scopes, deprecated, file, bag),
+        ExpressionKind::Index { object, index } => {
+            visit_expression(object, scopes, deprecated, file, bag);
+            visit_expression(index, scopes, deprecated, file, bag);
+        }
+        ExpressionKind::Assignment { target, value, .. } => {
+            visit_expression(target, scopes, deprecated, file, bag);
+            visit_expression(value, scopes, deprecated, file, bag);
+        }
+        ExpressionKind::ArrayLiteral(items) | ExpressionKind::Tuple(items) => {
+            for item in items {
+                visit_expression(item, scopes, deprecated, file, bag);
+            }
+        }
+        ExpressionKind::MapLiteral(entries) => {
+            for (key, value) in entries {
+                visit_expression(key, scopes, deprecated, file, bag);
+                visit_expression(value, scopes, deprecated, file, bag);
+            }
+        }
+        ExpressionKind::StructInit { fields, .. } => {
+            for (_, value) in fields {
+                visit_expression(value, scopes, deprecated, file, bag);
+            }
+        }
+        ExpressionKind::Lambda { params, body, .. } => {
+            scopes.push(Vec::new());
+            for param in params {
+                declare(scopes, param.name.clone(), param.ty.position, LocalKind::Parameter, file, bag);
+            }
+            visit_block(body, scopes, deprecated, file, bag);
+            finish_scope(scopes.pop().unwrap(), file, bag);
+        }
+        ExpressionKind::Match { subject, arms } => {
+            visit_expression(subject, scopes, deprecated, file, bag);
+            for arm in arms {
+                visit_block(&arm.body, scopes, deprecated, file, bag);
+            }
+        }
+        ExpressionKind::Literal(_) | ExpressionKind::Error => {}
+    }
+}
+
+/// Warns about every `import` whose bound name (its alias, or the last
+/// path segment) is never referenced anywhere in `module`.
+fn check_unused_imports(module: &Module, file: &str, bag: &mut DiagnosticBag) {
+    if is_allowed(Lint::UnusedImport) {
+        return;
+    }
+    let mut used = HashSet::new();
+    for statement in &module.statements {
+        match &statement.kind {
+            StatementKind::FunctionDecl(function) => {
+                for stmt in &function.body {
+                    dce::collect_statement_refs(stmt, &mut used);
+                }
+            }
+            StatementKind::ImplBlock(imp) => {
+                for method in &imp.methods {
+                    for stmt in &method.body {
+                        dce::collect_statement_refs(stmt, &mut used);
+                    }
+                }
+            }
+            StatementKind::Let { value: Some(value), .. } => {
+                dce::collect_expr_refs(value, &mut used);
+            }
+            // Re-exporting an imported name (`import io; export { io }`)
+            // counts as a use -- a facade module never references the
+            // import from a function body, only from its export block.
+            StatementKind::Export(export) => {
+                for name in &export.names {
+                    used.insert(name.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    for statement in &module.statements {
+        let StatementKind::Import(import) = &statement.kind else {
+            continue;
+        };
+        let bound_name = import
+            .alias
+            .clone()
+            .or_else(|| import.path.last().cloned())
+            .unwrap_or_default();
+        if !used.contains(&bound_name) {
+            bag.push(
+                Diagnostic::warning(
+                    Lint::UnusedImport.code(),
+                    format!("unused import `{}`", import.path.join(".")),
+                    import.position,
+                ),
+                file,
+            );
+        }
+    }
+}
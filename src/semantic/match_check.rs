@@ -0,0 +1,132 @@
+//! Exhaustiveness and reachability analysis for `match` over enums.
+//!
+//! Builds the variant universe for the enum a match's patterns target from
+//! [`SymbolTable`]'s already-resolved `Enum` symbols, so an enum imported
+//! from another module is checked exactly like one declared locally, then
+//! reports:
+//! - `E204` if any variant of that enum has no arm at all, naming the
+//!   missing variant(s), and
+//! - `E205` for each arm that can never run: an exact-duplicate
+//!   `EnumName.Variant` pattern already handled by an earlier arm, or any
+//!   arm at all following a catch-all (`Wildcard`, or a bare `Identifier`
+//!   that doesn't name a variant).
+//!
+//! Both checks only fire once a match has at least one `EnumVariant`
+//! pattern; a match purely on literals or bindings has no enum universe to
+//! check against. A bare `Identifier` arm that *does* name some enum's
+//! variant (a tag test written without the `EnumName.` prefix) still
+//! counts toward exhaustiveness, matching how the rest of this pass
+//! resolves that ambiguity -- but isn't tracked for duplicate-arm
+//! reachability, since which enum it tags isn't known without also
+//! knowing the match's subject type.
+
+use crate::ast::{MatchArm, Pattern};
+use crate::common::Position;
+use crate::semantic::environment::SymbolTable;
+use std::collections::HashSet;
+
+/// One problem [`analyze`] found: the diagnostic code, message and
+/// position to report it at. Kept separate from
+/// [`crate::errors::Diagnostic`] so this module needs neither a
+/// `DiagnosticBag` nor a file name of its own -- the caller (the
+/// resolver, which already has both) decides how each becomes a real
+/// diagnostic.
+pub struct Problem {
+    pub code: &'static str,
+    pub message: String,
+    pub position: Position,
+}
+
+fn is_catch_all(symtable: &SymbolTable, pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Wildcard => true,
+        Pattern::Identifier(name) => !symtable
+            .enums
+            .iter()
+            .any(|s| s.get().variants.iter().any(|v| &v.name == name)),
+        _ => false,
+    }
+}
+
+/// Checks a single `match`'s `arms` for unreachable and missing variants,
+/// given `symtable`'s view of every enum currently in scope.
+pub fn analyze(symtable: &SymbolTable, arms: &[MatchArm]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut caught_all = false;
+    let mut covered_variants: HashSet<(&str, &str)> = HashSet::new();
+
+    for arm in arms {
+        if caught_all {
+            let message = match &arm.pattern {
+                Pattern::EnumVariant { enum_name, variant } => format!(
+                    "Unreachable match arm for variant '{}.{}': an earlier catch-all arm already covers every case",
+                    enum_name, variant
+                ),
+                _ => "Unreachable match arm: an earlier catch-all arm already covers every case".to_string(),
+            };
+            problems.push(Problem { code: "E205", message, position: arm.position });
+        } else if let Pattern::EnumVariant { enum_name, variant } = &arm.pattern {
+            if !covered_variants.insert((enum_name, variant)) {
+                problems.push(Problem {
+                    code: "E205",
+                    message: format!(
+                        "Unreachable match arm: variant '{}.{}' is already handled by an earlier arm",
+                        enum_name, variant
+                    ),
+                    position: arm.position,
+                });
+            }
+        }
+        if is_catch_all(symtable, &arm.pattern) {
+            caught_all = true;
+        }
+    }
+
+    // A catch-all arm anywhere makes the match exhaustive by definition,
+    // regardless of where it sits relative to the (possibly unreachable)
+    // arms after it.
+    if arms.iter().any(|arm| is_catch_all(symtable, &arm.pattern)) {
+        return problems;
+    }
+
+    let Some(enum_name) = arms.iter().find_map(|arm| match &arm.pattern {
+        Pattern::EnumVariant { enum_name, .. } => Some(enum_name.clone()),
+        _ => None,
+    }) else {
+        // No enum variant patterns at all (a literal/wildcard-less match
+        // on a non-enum value) -- nothing to check exhaustively.
+        return problems;
+    };
+    let Some(sym) = symtable.get_enum(&enum_name) else {
+        return problems; // Already reported as an undefined enum.
+    };
+
+    let covered: HashSet<&str> = arms
+        .iter()
+        .filter_map(|arm| match &arm.pattern {
+            Pattern::EnumVariant { enum_name: e, variant } if e == &enum_name => Some(variant.as_str()),
+            Pattern::Identifier(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    let enum_decl = sym.get();
+    let missing: Vec<&str> = enum_decl
+        .variants
+        .iter()
+        .map(|v| v.name.as_str())
+        .filter(|name| !covered.contains(name))
+        .collect();
+    if !missing.is_empty() {
+        let position = arms.last().map(|arm| arm.position).unwrap_or_default();
+        problems.push(Problem {
+            code: "E204",
+            message: format!(
+                "Non-exhaustive match on enum '{}': missing variant(s) {}",
+                enum_name,
+                missing.join(", ")
+            ),
+            position,
+        });
+    }
+    problems
+}
@@ -0,0 +1,2766 @@
+//! Name resolution and type checking.
+//!
+//! Resolution runs in two passes: [`FirstPassResolver`] walks the module
+//! top level and registers every function/struct/enum declaration into the
+//! [`SymbolTable`] before anything is checked, so forward references work;
+//! [`Resolver`] then walks function bodies resolving identifiers against
+//! that table and a local scope stack.
+//!
+//! Both passes, along with [`lint`] and [`dce`], already walk the AST by
+//! reference (`&Module`, `&[Statement]`, `&Expression`) rather than
+//! cloning nodes to recurse into them -- the only `.clone()`s in this
+//! module are of names and small `Copy`/cheap types (`Position`,
+//! `Arc<Function>`) going into a `SymbolTable` entry or a worklist, not of
+//! AST subtrees.
+
+pub mod const_eval;
+pub mod constant_fold;
+pub mod dce;
+pub mod environment;
+pub mod graph;
+pub mod lint;
+pub mod match_check;
+pub mod returns;
+
+use crate::ast::{
+    Enum, Expression, ExpressionKind, Function, Impl, LiteralValue, Module, Param, Statement,
+    StatementKind, Struct, Type, TypeKind, TypeParam, UserTypeKind,
+};
+use crate::common::Position;
+use crate::errors::{Diagnostic, DiagnosticBag};
+use crate::utils::module::{ExportedKind, MatchaModule};
+use crate::utils::project::ProjectManifest;
+use environment::SymbolTable;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Functions the language provides without a user declaration anywhere
+/// to resolve against — currently just the `print` used by
+/// [`crate::backend`] to produce output. Kept as a name-only allow-list
+/// here rather than a real `Symbol<Function>` since nothing needs to
+/// inspect its signature yet.
+fn is_builtin_function(name: &str) -> bool {
+    matches!(name, "print")
+}
+
+pub struct FirstPassResolver<'a> {
+    symtable: &'a mut SymbolTable,
+}
+
+impl<'a> FirstPassResolver<'a> {
+    pub fn new(symtable: &'a mut SymbolTable) -> Self {
+        Self { symtable }
+    }
+
+    pub fn run(&mut self, module: &Module) {
+        self.declare_statements(&module.statements, "");
+    }
+
+    /// Registers every declaration in `statements`, prefixing each
+    /// top-level name with `prefix` (empty at the real top level, or
+    /// `"Outer"`/`"Outer.Inner"` when recursing into a `module` block) --
+    /// see [`qualify`]. Static methods (`func (Point) name(...)`) are
+    /// left keyed by `"Receiver.name"` alone regardless of `prefix`: a
+    /// module nesting one is a corner case this request doesn't cover.
+    fn declare_statements(&mut self, statements: &[Statement], prefix: &str) {
+        for statement in statements {
+            match &statement.kind {
+                StatementKind::FunctionDecl(func) if func.receiver.is_some() => {
+                    self.symtable.declare_static_method(Arc::clone(func), statement.position);
+                }
+                StatementKind::FunctionDecl(func) => {
+                    self.symtable.declare_function(
+                        qualify(prefix, &func.name),
+                        Arc::clone(func),
+                        statement.position,
+                    );
+                }
+                StatementKind::StructDecl(strct) => {
+                    self.symtable.declare_struct(
+                        qualify(prefix, &strct.name),
+                        Arc::clone(strct),
+                        statement.position,
+                    );
+                }
+                StatementKind::EnumDecl(enm) => {
+                    self.symtable.declare_enum(
+                        qualify(prefix, &enm.name),
+                        Arc::clone(enm),
+                        statement.position,
+                    );
+                }
+                StatementKind::InterfaceDecl(interface) => {
+                    self.symtable.declare_interface(
+                        qualify(prefix, &interface.name),
+                        Arc::clone(interface),
+                        statement.position,
+                    );
+                }
+                StatementKind::ModuleDecl(block) => {
+                    let path = qualify(prefix, &block.name);
+                    self.symtable.declare_namespace(path.clone(), Arc::clone(block), statement.position);
+                    self.declare_statements(&block.statements, &path);
+                }
+                StatementKind::Import(import) => {
+                    // Only the bound name (the alias, or the last path
+                    // segment when there's none) is visible afterwards —
+                    // `import std.net.http as web` makes `web` resolvable
+                    // and leaves the original `http` undefined.
+                    let bound_name = import
+                        .alias
+                        .clone()
+                        .or_else(|| import.path.last().cloned())
+                        .unwrap_or_default();
+                    self.symtable.define(
+                        &bound_name,
+                        crate::ast::Type::new(
+                            crate::ast::TypeKind::UserType(
+                                import.path.join("."),
+                                crate::ast::UserTypeKind::Module,
+                                Vec::new(),
+                            ),
+                            statement.position,
+                        ),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `name` qualified by `prefix` for a namespaced declaration -- `name`
+/// itself at the real top level (`prefix` empty), or `"prefix.name"`
+/// when declared inside a `module` block.
+fn qualify(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// State of an in-progress or finished module load, keyed by import path
+/// in [`Resolver::modules`].
+enum ModuleLoad {
+    /// Currently being loaded further up the call stack — seeing this
+    /// again means the import graph cycles back on itself.
+    Loading,
+    Ready(MatchaModule),
+}
+
+/// Why an imported module's source couldn't be loaded.
+enum ImportError {
+    /// The import path forms a cycle back to a module already being
+    /// loaded. Carries the full chain, e.g. `"a -> b -> c -> a"`.
+    Cycle(String),
+    /// Either there's no project manifest to resolve the path with, or
+    /// the file it resolves to doesn't exist.
+    NotFound,
+}
+
+/// Walks resolved function bodies checking that every identifier refers to
+/// a known variable, function, struct or enum.
+pub struct Resolver<'a> {
+    symtable: &'a mut SymbolTable,
+    pub had_error: bool,
+    file: String,
+    /// Names captured by each lambda, keyed by the lambda's position.
+    pub captures: std::collections::HashMap<Position, Vec<String>>,
+    /// Modules loaded so far to validate `Get`s off an imported name,
+    /// keyed by import path (`"std.net.http"`). Shared (not cloned) with
+    /// every `Resolver` spun up to load a dependency, so an import cycle
+    /// is visible no matter how many resolvers deep it's detected.
+    modules: Rc<RefCell<HashMap<String, ModuleLoad>>>,
+    /// Import paths currently being loaded, in load order, shared with
+    /// every nested `Resolver` the same way `modules` is — this is what
+    /// lets a detected cycle be rendered as the full `a -> b -> c -> a`
+    /// chain rather than just the two paths that closed the loop.
+    loading_stack: Rc<RefCell<Vec<String>>>,
+    /// The enclosing project's manifest, if `compile` found one. Used to
+    /// resolve import paths through the declared std/source/dependency
+    /// layout instead of guessing relative to the current file.
+    pub project: Option<Rc<ProjectManifest>>,
+    bag: &'a mut DiagnosticBag,
+    /// Dotted path of the `module` block(s) currently being walked, from
+    /// outermost to innermost -- `["Outer", "Inner"]` while inside
+    /// `Outer.Inner`'s body. Lets [`Self::visit_get`] tell a namespace
+    /// member's own siblings apart from code outside the module: a
+    /// private (`is_pub: false`) member is only reachable from a
+    /// namespace path this stack is at or nested under.
+    namespace_stack: Vec<String>,
+    /// Names from the `<T: Bound>` list of the function currently being
+    /// visited, so [`Self::check_type`] doesn't reject `T` as an
+    /// undefined type inside that function's own signature and body.
+    /// Replaced (not merged) on entry to each function -- type
+    /// parameters don't nest or leak between sibling functions.
+    generic_params: HashSet<String>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(symtable: &'a mut SymbolTable, file: impl Into<String>, bag: &'a mut DiagnosticBag) -> Self {
+        Self {
+            symtable,
+            had_error: false,
+            file: file.into(),
+            captures: std::collections::HashMap::new(),
+            modules: Rc::new(RefCell::new(HashMap::new())),
+            loading_stack: Rc::new(RefCell::new(Vec::new())),
+            project: None,
+            bag,
+            namespace_stack: Vec::new(),
+            generic_params: HashSet::new(),
+        }
+    }
+
+    pub fn resolve(&mut self, module: &Module) {
+        for statement in &module.statements {
+            self.visit_statement(statement);
+        }
+    }
+
+    /// Pre-populates the module cache with interfaces already compiled
+    /// elsewhere (e.g. by [`graph::compile_parallel`]), so `import`s that
+    /// were warmed up ahead of time skip straight to a cache hit instead
+    /// of reparsing their source.
+    pub fn seed_modules(&mut self, modules: HashMap<String, crate::utils::module::MatchaModule>) {
+        let mut cache = self.modules.borrow_mut();
+        for (path, module) in modules {
+            cache.insert(path, ModuleLoad::Ready(module));
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::FunctionDecl(func) => self.visit_function(func),
+            StatementKind::Expression(expr) => self.visit_expression(expr),
+            StatementKind::Let { name, value, ty, .. } => {
+                if let Some(value) = value {
+                    self.visit_expression(value);
+                }
+                if let Some(ty) = ty {
+                    self.check_type(ty);
+                }
+                let declared = ty.clone().unwrap_or(Type::new(TypeKind::Error, statement.position));
+                self.symtable.define(name, declared);
+            }
+            StatementKind::Return(Some(expr)) => self.visit_expression(expr),
+            StatementKind::Return(None) => {}
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.visit_expression(condition);
+                self.visit_block(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.visit_block(else_branch);
+                }
+            }
+            StatementKind::While { condition, body } => {
+                self.visit_expression(condition);
+                self.visit_block(body);
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.symtable.push();
+                if let Some(init) = init {
+                    self.visit_statement(init);
+                }
+                if let Some(condition) = condition {
+                    self.visit_expression(condition);
+                }
+                if let Some(update) = update {
+                    self.visit_expression(update);
+                }
+                for stmt in body {
+                    self.visit_statement(stmt);
+                }
+                self.symtable.pop();
+            }
+            StatementKind::ForEach {
+                variable,
+                iterable,
+                body,
+            } => {
+                self.visit_expression(iterable);
+                self.symtable.push();
+                self.symtable.define(variable, infer_array_element_type(iterable));
+                for stmt in body {
+                    self.visit_statement(stmt);
+                }
+                self.symtable.pop();
+            }
+            StatementKind::Block(stmts) => self.visit_block(stmts),
+            StatementKind::StructDecl(strct) => {
+                self.check_type_params(&strct.type_params);
+                let outer_generic_params = std::mem::replace(
+                    &mut self.generic_params,
+                    strct.type_params.iter().map(|param| param.name.clone()).collect(),
+                );
+                for field in &strct.fields {
+                    self.check_type(&field.ty);
+                }
+                self.generic_params = outer_generic_params;
+            }
+            StatementKind::EnumDecl(_) => {}
+            StatementKind::InterfaceDecl(interface) => {
+                for method in &interface.methods {
+                    for param in &method.params {
+                        self.check_type(&param.ty);
+                    }
+                    self.check_type(&method.return_type);
+                }
+            }
+            StatementKind::ImplBlock(imp) => self.visit_impl(imp),
+            // Its own declarations were already registered under a
+            // qualified path by `FirstPassResolver`; walking the body
+            // here (rather than treating it as opaque) is what makes a
+            // function nested inside one get its own body checked.
+            StatementKind::ModuleDecl(block) => {
+                self.namespace_stack.push(block.name.clone());
+                for stmt in &block.statements {
+                    self.visit_statement(stmt);
+                }
+                self.namespace_stack.pop();
+            }
+            // The bound name was already defined by `FirstPassResolver` so
+            // it resolves wherever it's referenced; this just checks that
+            // the module it names can actually be loaded.
+            StatementKind::Import(import) => self.visit_import(import, statement.position),
+            StatementKind::Export(export) => self.visit_export(export),
+            StatementKind::Match { subject, arms } => self.visit_match(subject, arms),
+            StatementKind::Break | StatementKind::Continue | StatementKind::Error => {}
+        }
+    }
+
+    fn visit_block(&mut self, statements: &[Statement]) {
+        self.symtable.push();
+        for statement in statements {
+            self.visit_statement(statement);
+        }
+        self.symtable.pop();
+    }
+
+    fn visit_function(&mut self, func: &Arc<Function>) {
+        let outer_generic_params = std::mem::replace(
+            &mut self.generic_params,
+            func.type_params.iter().map(|param| param.name.clone()).collect(),
+        );
+        self.check_type_params(&func.type_params);
+        self.symtable.push();
+        if let (Some(receiver), Some(receiver_name)) = (&func.receiver, &func.receiver_name) {
+            self.symtable.define(receiver_name, Type::new(TypeKind::from_string(receiver), func.position));
+        }
+        for param in &func.params {
+            self.check_type(&param.ty);
+            self.symtable.define(&param.name, param.ty.clone());
+        }
+        self.check_type(&func.return_type);
+        if func.extern_info.is_some() {
+            self.check_ffi_safety(func);
+        }
+        for statement in &func.body {
+            self.visit_statement(statement);
+        }
+        self.symtable.pop();
+        self.generic_params = outer_generic_params;
+    }
+
+    /// Validates that every bound named in a `<T: Bound>` list (a
+    /// function's or a struct's) is a declared interface. This is the
+    /// only "constraint" checking this compiler can do -- there's no
+    /// instantiation step (no monomorphization, no call-site type
+    /// arguments, no inference) to verify a bound against the concrete
+    /// type a call or a `List<...>` reference ends up using, so an
+    /// unsatisfied bound is never reported, only an unknown bound name
+    /// here.
+    fn check_type_params(&mut self, type_params: &[TypeParam]) {
+        for type_param in type_params {
+            for bound in &type_param.bounds {
+                if self.symtable.get_interface(bound).is_none() {
+                    self.error_at(
+                        "E214",
+                        format!("Undefined interface '{}' in bound on '{}'", bound, type_param.name),
+                        type_param.position,
+                    );
+                }
+            }
+        }
+    }
+
+    /// An `extern` function crosses into native code with no marshalling
+    /// layer, so every parameter and the return type has to be one of the
+    /// fixed set of types with a well-defined C-compatible layout --
+    /// see [`TypeKind::is_ffi_safe`].
+    fn check_ffi_safety(&mut self, func: &Arc<Function>) {
+        for param in &func.params {
+            if !param.ty.kind.is_error() && !param.ty.kind.is_ffi_safe() {
+                self.error_at(
+                    "E213",
+                    format!(
+                        "Type '{:?}' cannot cross an extern boundary (parameter '{}')",
+                        param.ty.kind, param.name
+                    ),
+                    func.position,
+                );
+            }
+        }
+        if !func.return_type.kind.is_error()
+            && !matches!(func.return_type.kind, TypeKind::Void)
+            && !func.return_type.kind.is_ffi_safe()
+        {
+            self.error_at(
+                "E213",
+                format!("Type '{:?}' cannot cross an extern boundary (return type)", func.return_type.kind),
+                func.position,
+            );
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        match &expr.kind {
+            ExpressionKind::Identifier(name) => {
+                if self.symtable.lookup(name).is_none()
+                    && self.symtable.get_function(name).is_none()
+                    && !is_builtin_function(name)
+                    && self.symtable.find_enum_by_variant(name).is_none()
+                {
+                    let mut message = format!("Undefined symbol '{}'", name);
+                    if let Some(suggestion) = self.symtable.suggest(name) {
+                        message.push_str(&format!("; did you mean '{}'?", suggestion));
+                    }
+                    self.error(message, expr.position);
+                }
+            }
+            ExpressionKind::Binary { left, right, .. } => {
+                self.visit_expression(left);
+                self.visit_expression(right);
+            }
+            ExpressionKind::Unary { operand, .. } | ExpressionKind::Postfix { operand, .. } => {
+                self.visit_expression(operand);
+            }
+            ExpressionKind::Grouping(inner) => self.visit_expression(inner),
+            ExpressionKind::Call { callee, args } => {
+                self.visit_expression(callee);
+                for arg in args {
+                    self.visit_expression(arg);
+                }
+            }
+            ExpressionKind::Get { object, name } => self.visit_get(object, name, expr.position),
+            ExpressionKind::Index { object, index } => {
+                self.visit_expression(object);
+                self.visit_expression(index);
+            }
+            ExpressionKind::Assignment { target, value, .. } => {
+                self.visit_expression(target);
+                self.visit_expression(value);
+            }
+            ExpressionKind::ArrayLiteral(items) | ExpressionKind::Tuple(items) => {
+                for item in items {
+                    self.visit_expression(item);
+                }
+            }
+            ExpressionKind::StructInit { name, fields } => {
+                if !self.symtable.struct_exists(name) {
+                    let mut message = format!("Undefined struct '{}'", name);
+                    if let Some(suggestion) = self.symtable.suggest_struct(name) {
+                        message.push_str(&format!("; did you mean '{}'?", suggestion));
+                    }
+                    self.error(message, expr.position);
+                }
+                for (_, value) in fields {
+                    self.visit_expression(value);
+                }
+            }
+            ExpressionKind::Lambda {
+                params,
+                return_type,
+                body,
+            } => self.visit_lambda(expr.position, params, return_type, body),
+            ExpressionKind::Try(inner) => self.visit_expression(inner),
+            ExpressionKind::MapLiteral(entries) => {
+                for (key, value) in entries {
+                    self.visit_expression(key);
+                    self.visit_expression(value);
+                }
+            }
+            ExpressionKind::Match { subject, arms } => self.visit_match(subject, arms),
+            ExpressionKind::Literal(_) | ExpressionKind::Error => {}
+        }
+    }
+
+    /// Resolves a lambda body against a fresh scope holding its
+    /// parameters, then records which of the identifiers it references
+    /// are captured from an enclosing scope rather than local to it.
+    fn visit_lambda(&mut self, position: Position, params: &[Param], return_type: &Type, body: &[Statement]) {
+        let mut locals: std::collections::HashSet<String> =
+            params.iter().map(|p| p.name.clone()).collect();
+        collect_locals(body, &mut locals);
+
+        let mut used = std::collections::HashSet::new();
+        collect_identifiers(body, &mut used);
+        let mut captured: Vec<String> = used.difference(&locals).cloned().collect();
+        captured.sort();
+        self.captures.insert(position, captured);
+
+        self.symtable.push();
+        for param in params {
+            self.check_type(&param.ty);
+            self.symtable.define(&param.name, param.ty.clone());
+        }
+        self.check_type(return_type);
+        for statement in body {
+            self.visit_statement(statement);
+        }
+        self.symtable.pop();
+    }
+
+    /// Checks that an `impl Interface for Target { ... }` block provides
+    /// every method the interface requires, with a matching arity, then
+    /// resolves each method body like any other function.
+    fn visit_impl(&mut self, imp: &Arc<crate::ast::Impl>) {
+        let target_exists = self.symtable.struct_exists(&imp.target_name)
+            || self.symtable.enum_exists(&imp.target_name);
+        if !target_exists {
+            let mut message = format!("Undefined type '{}' in impl block", imp.target_name);
+            if let Some(suggestion) = self.suggest_type(&imp.target_name) {
+                message.push_str(&format!("; did you mean '{}'?", suggestion));
+            }
+            self.error(message, imp.position);
+        }
+
+        // Starts as a copy of the methods this `impl` wrote itself; a
+        // required method it omits is merged in below from the
+        // interface's default body, if it has one. This is the
+        // "attaching methods to struct symbols" merge the interface's
+        // defaults are for -- an override in `imp.methods` always wins,
+        // since it's already present and the loop below only fills in
+        // what's missing.
+        let mut merged_methods = imp.methods.clone();
+
+        match self.symtable.get_interface(&imp.interface_name) {
+            None => self.error(
+                format!("Undefined interface '{}'", imp.interface_name),
+                imp.position,
+            ),
+            Some(sym) => {
+                let interface = sym.get();
+                for required in &interface.methods {
+                    let provided = imp
+                        .methods
+                        .iter()
+                        .find(|m| m.name == required.name);
+                    match provided {
+                        None => match &required.default_body {
+                            Some(default_body) => {
+                                merged_methods.push(Arc::new(Function {
+                                    name: required.name.clone(),
+                                    params: required.params.clone(),
+                                    return_type: required.return_type.clone(),
+                                    body: default_body.clone(),
+                                    is_pub: false,
+                                    position: required.position,
+                                    doc: None,
+                                    receiver: Some(imp.target_name.clone()),
+                                    receiver_name: None,
+                                    extern_info: None,
+                                    deprecated: None,
+                                    type_params: Vec::new(),
+                                }));
+                            }
+                            None => {
+                                self.had_error = true;
+                                self.bag.push(
+                                    Diagnostic::error(
+                                        "E001",
+                                        format!(
+                                            "'{}' does not implement '{}' required by interface '{}'",
+                                            imp.target_name, required.name, imp.interface_name
+                                        ),
+                                        imp.position,
+                                    )
+                                    .with_help(format!(
+                                        "add a method `{}` with {} parameter(s) to this `impl`",
+                                        required.name,
+                                        required.params.len()
+                                    )),
+                                    self.file.clone(),
+                                );
+                            }
+                        },
+                        Some(method) if method.params.len() != required.params.len() => {
+                            self.error(
+                                format!(
+                                    "Method '{}' has {} parameter(s), interface '{}' requires {}",
+                                    method.name,
+                                    method.params.len(),
+                                    imp.interface_name,
+                                    required.params.len()
+                                ),
+                                method.position,
+                            );
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+
+        let merged_imp = if merged_methods.len() == imp.methods.len() {
+            Arc::clone(imp)
+        } else {
+            Arc::new(crate::ast::Impl {
+                interface_name: imp.interface_name.clone(),
+                target_name: imp.target_name.clone(),
+                methods: merged_methods.clone(),
+                position: imp.position,
+            })
+        };
+
+        self.symtable.impls.push(environment::Symbol::new(
+            imp.target_name.clone(),
+            merged_imp,
+            imp.position,
+        ));
+
+        for method in &merged_methods {
+            self.visit_function(method);
+        }
+    }
+
+    fn visit_match(&mut self, subject: &Expression, arms: &[crate::ast::MatchArm]) {
+        self.visit_expression(subject);
+        for problem in crate::semantic::match_check::analyze(self.symtable, arms) {
+            self.error_at(problem.code, problem.message, problem.position);
+        }
+        for arm in arms {
+            self.symtable.push();
+            match &arm.pattern {
+                crate::ast::Pattern::Identifier(name) => {
+                    // A bare name binds the matched value unless it names
+                    // an existing enum variant, in which case it's a tag
+                    // test and introduces no binding.
+                    if !self.symtable.enums.iter().any(|s| {
+                        s.get().variants.iter().any(|v| &v.name == name)
+                    }) {
+                        self.symtable.define(name, Type::new(TypeKind::Error, arm.position));
+                    }
+                }
+                crate::ast::Pattern::EnumVariant { enum_name, variant } => {
+                    match self.symtable.get_enum(enum_name) {
+                        None => self.error(format!("Undefined enum '{}'", enum_name), arm.position),
+                        Some(sym) => {
+                            if !sym.get().variants.iter().any(|v| &v.name == variant) {
+                                self.error(
+                                    format!("Enum '{}' has no variant '{}'", enum_name, variant),
+                                    arm.position,
+                                );
+                            }
+                        }
+                    }
+                }
+                crate::ast::Pattern::Wildcard | crate::ast::Pattern::Literal(_) => {}
+            }
+            for statement in &arm.body {
+                self.visit_statement(statement);
+            }
+            self.symtable.pop();
+        }
+    }
+
+    // Exhaustiveness and reachability checking for `match` arms now lives
+    // in `crate::semantic::match_check`, called from `visit_match` above.
+
+    /// The fully-qualified path a chain of `Get`s names, if every level
+    /// is a known `module` block rather than a variable, struct, or
+    /// anything else -- `Outer.Inner` for `Outer.Inner.foo`'s object,
+    /// `Outer` for `Outer.foo`'s. `None` for anything that isn't purely
+    /// namespace segments, so [`Self::visit_get`] falls through to the
+    /// ordinary checks below for a struct field, a variable holding a
+    /// value, an imported module, and so on.
+    fn resolve_namespace_path(&self, expr: &Expression) -> Option<String> {
+        match &expr.kind {
+            ExpressionKind::Identifier(name) if self.symtable.lookup(name).is_none() => {
+                self.symtable.namespace_exists(name).then(|| name.clone())
+            }
+            ExpressionKind::Get { object, name } => {
+                let prefix = self.resolve_namespace_path(object)?;
+                let path = format!("{}.{}", prefix, name);
+                self.symtable.namespace_exists(&path).then_some(path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the code currently being resolved lexically sits inside
+    /// the `module` block named by `namespace` (or one nested inside
+    /// it) -- the one place a private (`is_pub: false`) member of that
+    /// namespace is still reachable from.
+    fn inside_namespace(&self, namespace: &str) -> bool {
+        let current = self.namespace_stack.join(".");
+        current == namespace || current.starts_with(&format!("{}.", namespace))
+    }
+
+    /// Validates `object.name` when `object` is a bare reference to an
+    /// imported module: `name` must be one of that module's exported
+    /// symbols. Anything else (a struct field, a method call target, ...)
+    /// is left to the caller, same as before this check existed.
+    ///
+    /// A bare struct name used as the object -- `Point` in `Point.new(...)`
+    /// -- is the other case checked directly here: it isn't a variable,
+    /// so it would otherwise fail the plain undefined-symbol check below,
+    /// and `name` has to name one of that struct's static methods rather
+    /// than an export. A bare enum name (`Color` in `Color.Red`) is the
+    /// same shape, except `name` can also be one of the enum's variants
+    /// -- an enum has no fields or instance state to speak of, so a
+    /// variant reference is itself a complete, constructible value rather
+    /// than something that only makes sense as a call.
+    fn visit_get(&mut self, object: &Expression, name: &str, position: Position) {
+        if let ExpressionKind::Identifier(type_name) = &object.kind {
+            if self.symtable.lookup(type_name).is_none() && self.symtable.struct_exists(type_name) {
+                if self.symtable.get_static_method(type_name, name).is_none() {
+                    self.error(
+                        format!("Struct '{}' has no static method '{}'", type_name, name),
+                        position,
+                    );
+                }
+                return;
+            }
+            if self.symtable.lookup(type_name).is_none() && self.symtable.enum_exists(type_name) {
+                let enu = self.symtable.get_enum(type_name).expect("enum_exists implies get_enum");
+                let is_variant = enu.get().variants.iter().any(|v| v.name == name);
+                if !is_variant && self.symtable.get_static_method(type_name, name).is_none() {
+                    self.error(
+                        format!("Enum '{}' has no variant or static method '{}'", type_name, name),
+                        position,
+                    );
+                }
+                return;
+            }
+        }
+        if let Some(namespace) = self.resolve_namespace_path(object) {
+            let qualified = format!("{}.{}", namespace, name);
+            let inside_module = self.inside_namespace(&namespace);
+            if let Some(func) = self.symtable.get_function(&qualified) {
+                if !func.get().is_pub && !inside_module {
+                    self.error(
+                        format!("Function '{}' is private to module '{}'", name, namespace),
+                        position,
+                    );
+                }
+            } else if let Some(strukt) = self.symtable.get_struct(&qualified) {
+                if !strukt.get().is_pub && !inside_module {
+                    self.error(
+                        format!("Struct '{}' is private to module '{}'", name, namespace),
+                        position,
+                    );
+                }
+            } else if let Some(enm) = self.symtable.get_enum(&qualified) {
+                if !enm.get().is_pub && !inside_module {
+                    self.error(
+                        format!("Enum '{}' is private to module '{}'", name, namespace),
+                        position,
+                    );
+                }
+            } else if !self.symtable.namespace_exists(&qualified) {
+                self.error(
+                    format!("Module '{}' has no member '{}'", namespace, name),
+                    position,
+                );
+            }
+            return;
+        }
+        self.visit_expression(object);
+        let ExpressionKind::Identifier(module_name) = &object.kind else {
+            return;
+        };
+        let Some(var) = self.symtable.lookup(module_name) else {
+            return;
+        };
+        let TypeKind::UserType(path, UserTypeKind::Module, _) = var.ty.kind.clone() else {
+            return;
+        };
+        // A failure here was already reported against the `import`
+        // statement itself; don't report it again at every use site.
+        let Ok(module) = self.load_module(&path) else {
+            return;
+        };
+        if !module.exported_symbols.iter().any(|s| s.name == name) {
+            self.error(
+                format!("Module '{}' has no exported symbol '{}'", module_name, name),
+                position,
+            );
+        }
+    }
+
+    /// Validates that an `import` names a module that can actually be
+    /// loaded. Without a project manifest there's no reliable way to tell
+    /// a genuine typo from a stdlib path with nothing on disk yet, so
+    /// resolution failures are only reported once a manifest is present
+    /// to resolve against.
+    fn visit_import(&mut self, import: &crate::ast::Import, position: Position) {
+        if self.project.is_none() {
+            return;
+        }
+        let path = import.path.join(".");
+        if let Err(error) = self.load_module(&path) {
+            let message = match error {
+                ImportError::Cycle(chain) => format!("Cyclic import: {}", chain),
+                ImportError::NotFound => format!("Cannot find module '{}'", path),
+            };
+            self.error(message, position);
+        }
+    }
+
+    /// Resolves an import path (`"std.net.http"`) to a source file, using
+    /// the project manifest if one was found, else guessing relative to
+    /// the file currently being resolved; compiles it and caches its
+    /// exported symbols.
+    fn load_module(&mut self, path: &str) -> Result<MatchaModule, ImportError> {
+        match self.modules.borrow().get(path) {
+            Some(ModuleLoad::Loading) => {
+                let mut chain = self.loading_stack.borrow().clone();
+                chain.push(path.to_string());
+                return Err(ImportError::Cycle(chain.join(" -> ")));
+            }
+            Some(ModuleLoad::Ready(module)) => return Ok(module.clone()),
+            None => {}
+        }
+        self.modules
+            .borrow_mut()
+            .insert(path.to_string(), ModuleLoad::Loading);
+        self.loading_stack.borrow_mut().push(path.to_string());
+
+        let segments: Vec<String> = path.split('.').map(String::from).collect();
+        let file_path = self
+            .project
+            .as_ref()
+            .and_then(|project| project.resolve_import(&segments))
+            .unwrap_or_else(|| {
+                std::path::Path::new(&self.file)
+                    .parent()
+                    .unwrap_or_else(|| std::path::Path::new("."))
+                    .join(path.replace('.', "/"))
+                    .with_extension("mt")
+            });
+        let source_mtime = mtime_secs(&file_path);
+        if let Some(mtime) = source_mtime {
+            if let Some(cached) = load_cached_interface(&file_path, mtime) {
+                self.modules
+                    .borrow_mut()
+                    .insert(path.to_string(), ModuleLoad::Ready(cached.clone()));
+                self.loading_stack.borrow_mut().pop();
+                return Ok(cached);
+            }
+        }
+
+        let Ok(source) = std::fs::read_to_string(&file_path) else {
+            self.modules.borrow_mut().remove(path);
+            self.loading_stack.borrow_mut().pop();
+            return Err(ImportError::NotFound);
+        };
+        let file_name = file_path.to_string_lossy().into_owned();
+
+        // A dependency loaded this way is a whole separate compile, not
+        // part of whatever bag the entry file's own resolve is collecting
+        // into -- its diagnostics are reported here, immediately, rather
+        // than merged into the importer's sort order.
+        let mut bag = DiagnosticBag::new();
+        let mut lexer = crate::lexer::Lexer::new(&source);
+        let tokens = lexer.scan_tokens();
+        let mut parser = crate::parser::Parser::new(tokens, &file_name, &mut bag);
+        let module_ast = parser.parse();
+
+        let mut symtable = SymbolTable::new();
+        FirstPassResolver::new(&mut symtable).run(&module_ast);
+        let mut nested_resolver = Resolver::new(&mut symtable, &file_name, &mut bag);
+        nested_resolver.project = self.project.clone();
+        nested_resolver.modules = self.modules.clone();
+        nested_resolver.loading_stack = self.loading_stack.clone();
+        nested_resolver.resolve(&module_ast);
+        bag.report_all();
+
+        let matcha_module = MatchaModule::from_symtable(path.to_string(), &symtable);
+        if let Some(mtime) = source_mtime {
+            let _ = std::fs::write(file_path.with_extension("mti"), matcha_module.to_interface(mtime));
+        }
+        self.modules
+            .borrow_mut()
+            .insert(path.to_string(), ModuleLoad::Ready(matcha_module.clone()));
+        self.loading_stack.borrow_mut().pop();
+        Ok(matcha_module)
+    }
+
+    /// Validates an `export { ... }` block: every name must refer to an
+    /// existing top-level declaration, and none may be exported twice.
+    fn visit_export(&mut self, export: &crate::ast::Export) {
+        for name in &export.names {
+            match self.symtable.exportable_kind(name) {
+                None => {
+                    self.error(format!("Cannot export undefined symbol '{}'", name), export.position);
+                }
+                Some(kind) => {
+                    if self.symtable.exported.contains(name) {
+                        self.error(format!("Symbol '{}' is exported more than once", name), export.position);
+                        continue;
+                    }
+                    self.symtable.exported.push(name.clone());
+                    self.symtable
+                        .exported_symbols
+                        .push(crate::utils::module::ExportedSymbol {
+                            name: name.clone(),
+                            kind,
+                        });
+                }
+            }
+        }
+    }
+
+    /// Recursively checks that every named type reachable from `ty` --
+    /// through arrays, maps, tuples, function signatures and `Result` --
+    /// refers to a struct or enum that actually exists. `UserType`s
+    /// resolved to something other than [`UserTypeKind::Unknown`]
+    /// elsewhere (a module, say) are left alone here.
+    fn check_type(&mut self, ty: &Type) {
+        match &ty.kind {
+            TypeKind::UserType(name, UserTypeKind::Unknown, args) => {
+                if let Some(strukt) = self.symtable.get_struct(name).map(|symbol| symbol.get()) {
+                    if args.len() != strukt.type_params.len() {
+                        self.error_at(
+                            "E215",
+                            format!(
+                                "'{}' expects {} type argument{}, found {}",
+                                name,
+                                strukt.type_params.len(),
+                                if strukt.type_params.len() == 1 { "" } else { "s" },
+                                args.len()
+                            ),
+                            ty.position,
+                        );
+                    }
+                    for arg in args {
+                        self.check_type(arg);
+                    }
+                    return;
+                }
+                if self.symtable.enum_exists(name) {
+                    return;
+                }
+                if self.generic_params.contains(name) {
+                    return;
+                }
+                let mut message = format!("Undefined type '{}'", name);
+                if let Some(suggestion) = self.suggest_type(name) {
+                    message.push_str(&format!("; did you mean '{}'?", suggestion));
+                }
+                self.error(message, ty.position);
+            }
+            TypeKind::UserType(_, _, args) => {
+                for arg in args {
+                    self.check_type(arg);
+                }
+            }
+            TypeKind::Array(inner, _) => self.check_type(inner),
+            TypeKind::Map(key, value) => {
+                self.check_type(key);
+                self.check_type(value);
+            }
+            TypeKind::Function(params, ret) => {
+                for param in params {
+                    self.check_type(param);
+                }
+                self.check_type(ret);
+            }
+            TypeKind::Result(ok, err) => {
+                self.check_type(ok);
+                self.check_type(err);
+            }
+            TypeKind::Tuple(items) => {
+                for item in items {
+                    self.check_type(item);
+                }
+            }
+            TypeKind::Int8
+            | TypeKind::Int16
+            | TypeKind::Int32
+            | TypeKind::Int64
+            | TypeKind::UInt8
+            | TypeKind::UInt16
+            | TypeKind::UInt32
+            | TypeKind::UInt64
+            | TypeKind::Float32
+            | TypeKind::Float64
+            | TypeKind::Bool
+            | TypeKind::String
+            | TypeKind::Char
+            | TypeKind::Void
+            | TypeKind::Error => {}
+        }
+    }
+
+    /// The closest-matching struct or enum name to `name`, drawn from both
+    /// this module's own declarations and every imported module's exports
+    /// -- for a "did you mean" note on an undefined-type diagnostic.
+    fn suggest_type(&self, name: &str) -> Option<String> {
+        let local_structs = self.symtable.structs.iter().map(|symbol| symbol.name.as_str());
+        let local_enums = self.symtable.enums.iter().map(|symbol| symbol.name.as_str());
+        let modules = self.modules.borrow();
+        let imported: Vec<String> = modules
+            .values()
+            .filter_map(|load| match load {
+                ModuleLoad::Ready(module) => Some(module),
+                ModuleLoad::Loading => None,
+            })
+            .flat_map(|module| module.exported_symbols.iter())
+            .filter(|symbol| matches!(symbol.kind, ExportedKind::Struct | ExportedKind::Enum))
+            .map(|symbol| symbol.name.clone())
+            .collect();
+        environment::best_suggestion(name, local_structs.chain(local_enums).chain(imported.iter().map(String::as_str)))
+    }
+
+    fn error(&mut self, message: String, position: Position) {
+        self.had_error = true;
+        self.bag.push(Diagnostic::error("E001", message, position), self.file.clone());
+    }
+
+    /// Same as [`Self::error`], but for call sites (like
+    /// [`crate::semantic::match_check`]'s) that already know which code
+    /// applies instead of always meaning "undefined symbol".
+    fn error_at(&mut self, code: &str, message: String, position: Position) {
+        self.had_error = true;
+        self.bag.push(Diagnostic::error(code, message, position), self.file.clone());
+    }
+}
+
+/// Items waiting to be type-checked, in declaration order.
+pub struct QueueItem {
+    pub function: Arc<Function>,
+}
+
+/// Drains a worklist of declarations, computing and unifying expression
+/// types so `compile()` rejects ill-typed programs.
+pub struct Typechecker<'a> {
+    pub queue: VecDeque<QueueItem>,
+    pub types: std::collections::HashMap<Position, Type>,
+    pub had_error: bool,
+    file: String,
+    /// Return type of whichever function/lambda body is currently being
+    /// checked, consulted by `?` to make sure it's only used where its
+    /// error can actually be propagated.
+    current_return: Option<Type>,
+    /// Impls grouped by `target_name`, so [`Self::check_expression`]'s
+    /// `Binary`/`Index` arms can look up an operator method (`add`, `eq`,
+    /// `index`, ...) for a `UserType` operand without a linear scan of
+    /// [`SymbolTable::impls`] on every operator use. Populated once by
+    /// [`Self::seed`]; `Arc<Impl>` keeps this cheap to clone into each
+    /// worker in [`Self::run_parallel`].
+    impls: HashMap<String, Vec<Arc<Impl>>>,
+    /// Struct declarations by name, so [`Self::check_expression`]'s
+    /// `StructInit` arm can check a literal's fields against the
+    /// declaration's without a `SymbolTable` reference of its own.
+    /// Populated once by [`Self::seed`]; cloned into each worker in
+    /// [`Self::run_parallel`] the same way [`Self::impls`] is.
+    structs: HashMap<String, Arc<Struct>>,
+    /// Enum declarations by name, so [`Self::normalize_user_type`] can
+    /// tell a `UserTypeKind::Unknown` annotation apart from a struct, and
+    /// [`Self::check_expression`]'s `Identifier`/`Get` arms can resolve
+    /// `EnumName.Variant` (or an unqualified variant name) to a
+    /// constructible value -- the enum equivalent of [`Self::structs`].
+    /// Populated once by [`Self::seed`]; cloned into each worker in
+    /// [`Self::run_parallel`] the same way [`Self::structs`] is.
+    enums: HashMap<String, Arc<Enum>>,
+    /// Static/associated functions by receiver type name then method
+    /// name, so [`Self::check_call`] can resolve `Point.new(...)`
+    /// against the declared signature. Populated once by [`Self::seed`];
+    /// cloned into each worker in [`Self::run_parallel`] the same way
+    /// [`Self::impls`]/[`Self::structs`] are.
+    static_methods: HashMap<String, HashMap<String, Arc<Function>>>,
+    /// Ordinary (non-receiver) top-level functions by name, so
+    /// [`Self::check_ufcs_call`] can resolve `x.f(y)` as `f(x, y)` when no
+    /// method named `f` exists on `x`'s type. Populated once by
+    /// [`Self::seed`]; cloned into each worker in [`Self::run_parallel`]
+    /// the same way [`Self::impls`]/[`Self::structs`] are.
+    functions: HashMap<String, Arc<Function>>,
+    /// Declared/inferred types of `let` bindings, function parameters and
+    /// loop variables currently in scope, innermost scope last -- what
+    /// lets [`Self::check_expression`]'s `Identifier` arm (and, through
+    /// it, `Get`/`Call`'s receiver) know a variable's static type instead
+    /// of always typing it as `Error`. Pushed/popped around each block the
+    /// same way [`environment::SymbolTable::push`]/`pop` scope name
+    /// resolution.
+    locals: Vec<HashMap<String, Type>>,
+    bag: &'a mut DiagnosticBag,
+}
+
+impl<'a> Typechecker<'a> {
+    pub fn new(file: impl Into<String>, bag: &'a mut DiagnosticBag) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            types: std::collections::HashMap::new(),
+            had_error: false,
+            file: file.into(),
+            current_return: None,
+            impls: HashMap::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            static_methods: HashMap::new(),
+            functions: HashMap::new(),
+            locals: Vec::new(),
+            bag,
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.locals.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.locals.pop();
+    }
+
+    fn define_local(&mut self, name: &str, ty: Type) {
+        if let Some(scope) = self.locals.last_mut() {
+            scope.insert(name.to_string(), ty);
+        }
+    }
+
+    fn lookup_local(&self, name: &str) -> Option<Type> {
+        self.locals.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    /// Seeds the worklist from the symbol table's declaration queue, in
+    /// the order declarations were collected by the first pass, and
+    /// indexes its impls by target type for operator-method lookup.
+    pub fn seed(&mut self, symtable: &SymbolTable) {
+        for function in &symtable.decl_queue {
+            self.queue.push_back(QueueItem {
+                function: Arc::clone(function),
+            });
+        }
+        for imp in &symtable.impls {
+            let imp = imp.get();
+            self.impls.entry(imp.target_name.clone()).or_default().push(imp);
+        }
+        for strukt in &symtable.structs {
+            self.structs.insert(strukt.name.clone(), strukt.get());
+        }
+        for enu in &symtable.enums {
+            self.enums.insert(enu.name.clone(), enu.get());
+        }
+        for method in &symtable.static_methods {
+            let method = method.get();
+            if let Some(receiver) = method.receiver.clone() {
+                self.static_methods.entry(receiver).or_default().insert(method.name.clone(), method);
+            }
+        }
+        for function in &symtable.functions {
+            let function = function.get();
+            if function.receiver.is_none() {
+                self.functions.insert(function.name.clone(), function);
+            }
+        }
+    }
+
+    /// Drains the worklist, computing and recording a type for every
+    /// expression in each queued function body.
+    pub fn run(&mut self) {
+        while let Some(item) = self.queue.pop_front() {
+            self.current_return = Some(item.function.return_type.clone());
+            self.push_scope();
+            if let (Some(receiver), Some(receiver_name)) = (&item.function.receiver, &item.function.receiver_name) {
+                self.define_local(receiver_name, Type::new(TypeKind::from_string(receiver), item.function.position));
+            }
+            for param in &item.function.params {
+                self.define_local(&param.name, param.ty.clone());
+            }
+            for statement in &item.function.body {
+                self.check_statement(statement, &item.function.return_type);
+            }
+            self.pop_scope();
+        }
+    }
+
+    /// Type-checks the queued functions on separate threads, one chunk of
+    /// the worklist per thread, up to `std::thread::available_parallelism()`
+    /// workers -- the same layer-at-a-time `std::thread::scope` pattern
+    /// [`crate::semantic::graph::compile_parallel`] uses to check
+    /// independent modules concurrently, applied here to the independent
+    /// function bodies within a single one. Each worker checks its chunk
+    /// against its own private [`DiagnosticBag`] and `types` map (a
+    /// function body's positions never overlap another's), and the chunks
+    /// are joined and merged back into `self` in worklist order once every
+    /// thread finishes, so the reported diagnostics come out identical to
+    /// [`Self::run`]'s regardless of which thread happened to finish first.
+    /// Falls back to [`Self::run`] when there's nothing to split across
+    /// more than one thread.
+    pub fn run_parallel(&mut self) {
+        let worker_count = std::thread::available_parallelism()
+            .map(std::num::NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(self.queue.len());
+        if worker_count <= 1 {
+            self.run();
+            return;
+        }
+
+        let items: Vec<QueueItem> = self.queue.drain(..).collect();
+        let chunk_size = items.len().div_ceil(worker_count);
+        let file = self.file.clone();
+        let impls = self.impls.clone();
+        let structs = self.structs.clone();
+        let enums = self.enums.clone();
+        let static_methods = self.static_methods.clone();
+        let functions = self.functions.clone();
+
+        let results: Vec<(DiagnosticBag, HashMap<Position, Type>, bool)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = items
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let file = file.clone();
+                    let impls = impls.clone();
+                    let structs = structs.clone();
+                    let enums = enums.clone();
+                    let static_methods = static_methods.clone();
+                    let functions = functions.clone();
+                    scope.spawn(move || {
+                        let mut bag = DiagnosticBag::new();
+                        let (types, had_error) = {
+                            let mut worker = Typechecker::new(file, &mut bag);
+                            worker.impls = impls;
+                            worker.structs = structs;
+                            worker.enums = enums;
+                            worker.static_methods = static_methods;
+                            worker.functions = functions;
+                            for item in chunk {
+                                worker.current_return = Some(item.function.return_type.clone());
+                                worker.push_scope();
+                                if let (Some(receiver), Some(receiver_name)) =
+                                    (&item.function.receiver, &item.function.receiver_name)
+                                {
+                                    worker.define_local(
+                                        receiver_name,
+                                        Type::new(TypeKind::from_string(receiver), item.function.position),
+                                    );
+                                }
+                                for param in &item.function.params {
+                                    worker.define_local(&param.name, param.ty.clone());
+                                }
+                                for statement in &item.function.body {
+                                    worker.check_statement(statement, &item.function.return_type);
+                                }
+                                worker.pop_scope();
+                            }
+                            (worker.types, worker.had_error)
+                        };
+                        (bag, types, had_error)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        for (bag, types, had_error) in results {
+            for (diagnostic_file, diagnostic) in bag.entries() {
+                self.bag.push(diagnostic, diagnostic_file);
+            }
+            self.types.extend(types);
+            self.had_error |= had_error;
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement, return_type: &Type) {
+        match &statement.kind {
+            StatementKind::Expression(expr) => {
+                self.check_expression(expr);
+            }
+            StatementKind::Let {
+                name,
+                value: Some(value),
+                ty: Some(ty),
+                ..
+            } => {
+                let value_ty = self.check_expression(value);
+                let unified = self.unify_reporting(ty, &value_ty, value.position);
+                self.check_int_range(ty, value);
+                self.check_lossy_literal_suffix(ty, value);
+                self.check_array_length(ty, value);
+                self.define_local(name, unified.clone());
+                self.types.insert(value.position, unified);
+            }
+            StatementKind::Let {
+                name,
+                value: Some(value),
+                ..
+            } => {
+                // No declared annotation to check `value` against, but a
+                // suffixed literal (`300u8`) still names its own type --
+                // check it against that instead of letting an
+                // out-of-range value through just because it wasn't
+                // written on the binding.
+                let value_ty = self.check_expression(value);
+                self.check_int_range(&value_ty, value);
+                self.define_local(name, value_ty);
+            }
+            StatementKind::Return(Some(expr)) => {
+                let expr_ty = self.check_expression(expr);
+                let unified = self.unify_reporting(return_type, &expr_ty, expr.position);
+                self.check_int_range(return_type, expr);
+                self.check_lossy_literal_suffix(return_type, expr);
+                self.types.insert(expr.position, unified);
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check_expression(condition);
+                self.push_scope();
+                for stmt in then_branch {
+                    self.check_statement(stmt, return_type);
+                }
+                self.pop_scope();
+                if let Some(else_branch) = else_branch {
+                    self.push_scope();
+                    for stmt in else_branch {
+                        self.check_statement(stmt, return_type);
+                    }
+                    self.pop_scope();
+                }
+            }
+            StatementKind::While { condition, body } => {
+                self.check_expression(condition);
+                self.push_scope();
+                for stmt in body {
+                    self.check_statement(stmt, return_type);
+                }
+                self.pop_scope();
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.push_scope();
+                if let Some(init) = init {
+                    self.check_statement(init, return_type);
+                }
+                if let Some(condition) = condition {
+                    self.check_expression(condition);
+                }
+                if let Some(update) = update {
+                    self.check_expression(update);
+                }
+                for stmt in body {
+                    self.check_statement(stmt, return_type);
+                }
+                self.pop_scope();
+            }
+            StatementKind::ForEach { variable, iterable, body } => {
+                self.check_expression(iterable);
+                self.push_scope();
+                self.define_local(variable, infer_array_element_type(iterable));
+                for stmt in body {
+                    self.check_statement(stmt, return_type);
+                }
+                self.pop_scope();
+            }
+            StatementKind::Block(stmts) => {
+                self.push_scope();
+                for stmt in stmts {
+                    self.check_statement(stmt, return_type);
+                }
+                self.pop_scope();
+            }
+            StatementKind::Match { subject, arms } => {
+                self.check_expression(subject);
+                for arm in arms {
+                    self.push_scope();
+                    for stmt in &arm.body {
+                        self.check_statement(stmt, return_type);
+                    }
+                    self.pop_scope();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Unifies two types, reporting a diagnostic (and poisoning the
+    /// result) on mismatch. Poisoned inputs are passed through silently.
+    fn unify_reporting(&mut self, left: &Type, right: &Type, position: Position) -> Type {
+        self.unify_reporting_inner(left, right, position, false)
+    }
+
+    /// Same as [`Self::unify_reporting`], but additionally warns
+    /// ([`crate::semantic::lint::Lint::LossyConversion`]) when the
+    /// implicit promotion crosses a precision or signedness boundary.
+    /// Only used for the sites `TypeKind::precedence`'s doc comment
+    /// actually describes -- combining two independently-typed operands
+    /// into one result, e.g. a binary expression or reconciling two
+    /// `match` arms' values -- not a value flowing into an already
+    /// *declared* type (a `let` annotation, a `return`, a map/array
+    /// index), where [`Self::check_int_range`] already covers the one
+    /// thing that can go wrong with an untyped literal, and warning here
+    /// too would fire on every ordinary `let x: UInt8 = 1;`.
+    fn unify_reporting_lossy(&mut self, left: &Type, right: &Type, position: Position) -> Type {
+        self.unify_reporting_inner(left, right, position, true)
+    }
+
+    fn unify_reporting_inner(&mut self, left: &Type, right: &Type, position: Position, check_lossy: bool) -> Type {
+        let left = self.normalize_user_type(left);
+        let right = self.normalize_user_type(right);
+        if left.kind.is_error() || right.kind.is_error() {
+            return Type::new(TypeKind::Error, position);
+        }
+        let result = unify(&left, &right);
+        if result.kind.is_error() {
+            self.error(
+                "E200",
+                format!(
+                    "Type mismatch: expected '{:?}', found '{:?}'",
+                    left.kind, right.kind
+                ),
+                position,
+            );
+        } else if check_lossy && left.kind != right.kind {
+            // `unify` already picked `result` over whichever side isn't
+            // it; if reaching that side implicitly wasn't a lossless
+            // widening (see `TypeKind::is_widening`), flag it -- narrowing
+            // a precision or crossing a signedness boundary should be
+            // written with an explicit `as`, not fall out of whichever
+            // operand happened to be on which side.
+            let from = if left.kind == result.kind { &right } else { &left };
+            if !from.kind.is_widening(&result.kind) {
+                crate::semantic::lint::warn_lossy_conversion(&from.kind, &result.kind, position, &self.file, self.bag);
+            }
+        }
+        result
+    }
+
+    /// Upgrades a `UserTypeKind::Unknown` (what every written type
+    /// annotation parses as -- see [`TypeKind::from_string`]) to
+    /// `Struct`/`Enum` once [`Self::structs`]/[`Self::enums`] confirms
+    /// which one `name` actually names, so an annotation and the
+    /// value checked against it agree on `UserTypeKind` before
+    /// [`unify`]'s exact-match fast path ever sees them -- otherwise
+    /// `let p: Point = Point { .. };` mismatches on `UserTypeKind` alone
+    /// even though both sides name the same struct. A name that's
+    /// neither (a generic parameter, or already-reported as undefined by
+    /// [`Resolver::check_type`]) is left as `Unknown`, same as today.
+    fn normalize_user_type(&self, ty: &Type) -> Type {
+        if let TypeKind::UserType(name, UserTypeKind::Unknown, args) = &ty.kind {
+            if self.structs.contains_key(name) {
+                return Type::new(TypeKind::UserType(name.clone(), UserTypeKind::Struct, args.clone()), ty.position);
+            }
+            if self.enums.contains_key(name) {
+                return Type::new(TypeKind::UserType(name.clone(), UserTypeKind::Enum, args.clone()), ty.position);
+            }
+        }
+        ty.clone()
+    }
+
+    /// The enum that declares `variant` as one of its variants, if
+    /// exactly one does -- lets a bare variant name (`Red`, unqualified)
+    /// type as that enum the same way `EnumName.Variant` does. `None`
+    /// both when no enum declares it and when more than one does, mirroring
+    /// [`environment::SymbolTable::find_enum_by_variant`], which
+    /// `Resolver::visit_expression` already consults to accept the name in
+    /// the first place.
+    fn find_enum_variant(&self, variant: &str) -> Option<String> {
+        let mut matches = self.enums.values().filter(|enu| enu.variants.iter().any(|v| v.name == variant));
+        let first = matches.next()?;
+        matches.next().is_none().then(|| first.name.clone())
+    }
+
+    /// Checks an integer literal assigned/returned as `declared` fits that
+    /// type's range. Literals are parsed into `i128`, wider than every
+    /// integer type this checks, so a literal too big for even `UInt64` or
+    /// `Int64` is still caught exactly instead of relying on truncated
+    /// storage to happen to be out of range.
+    fn check_int_range(&mut self, declared: &Type, value: &Expression) {
+        let bounds = match declared.kind {
+            TypeKind::Int8 => i8::MIN as i128..=i8::MAX as i128,
+            TypeKind::Int16 => i16::MIN as i128..=i16::MAX as i128,
+            TypeKind::Int32 => i32::MIN as i128..=i32::MAX as i128,
+            TypeKind::Int64 => i64::MIN as i128..=i64::MAX as i128,
+            TypeKind::UInt8 => u8::MIN as i128..=u8::MAX as i128,
+            TypeKind::UInt16 => u16::MIN as i128..=u16::MAX as i128,
+            TypeKind::UInt32 => u32::MIN as i128..=u32::MAX as i128,
+            TypeKind::UInt64 => u64::MIN as i128..=u64::MAX as i128,
+            _ => return,
+        };
+        if let ExpressionKind::Literal(LiteralValue::Int(n, _)) = &value.kind {
+            if !bounds.contains(n) {
+                self.error(
+                    "E210",
+                    format!("Integer literal '{}' does not fit in '{:?}'", n, declared.kind),
+                    value.position,
+                );
+            }
+        }
+    }
+
+    /// Checks an array literal's element count against a fixed-size
+    /// declared annotation (`let a: Int32[3] = [1, 2, 3];`). Only an
+    /// array *literal* is checked here -- a variable, a call, or
+    /// anything else that already went through [`Self::unify_reporting`]
+    /// against the same annotation is trusted, since nothing downstream
+    /// of a literal tracks a runtime array's length at all.
+    fn check_array_length(&mut self, declared: &Type, value: &Expression) {
+        let TypeKind::Array(_, Some(size)) = &declared.kind else {
+            return;
+        };
+        let ExpressionKind::ArrayLiteral(items) = &value.kind else {
+            return;
+        };
+        if items.len() != *size {
+            self.error(
+                "E216",
+                format!("Expected an array of length {}, found {}", size, items.len()),
+                value.position,
+            );
+        }
+    }
+
+    /// Warns ([`crate::semantic::lint::Lint::LossyConversion`]) when a
+    /// literal's own suffix (`10i64`, `255u8`, ...) names a numeric type
+    /// that isn't a widening of `declared`'s -- e.g. `let x: Int32 =
+    /// 5u32;`, which [`Self::unify_reporting`] resolves to `Int32`
+    /// without complaint since both are numeric. An un-suffixed literal
+    /// has no type of its own to compare, so this is silent for the
+    /// ordinary `let x: UInt8 = 1;` case [`Self::check_int_range`]
+    /// already covers.
+    fn check_lossy_literal_suffix(&mut self, declared: &Type, value: &Expression) {
+        if declared.kind.is_error() {
+            return;
+        }
+        let suffix = match &value.kind {
+            ExpressionKind::Literal(LiteralValue::Int(_, Some(suffix))) => suffix,
+            ExpressionKind::Literal(LiteralValue::Float(_, Some(suffix))) => suffix,
+            _ => return,
+        };
+        if suffix != &declared.kind && !suffix.is_widening(&declared.kind) {
+            crate::semantic::lint::warn_lossy_conversion(suffix, &declared.kind, value.position, &self.file, self.bag);
+        }
+    }
+
+    fn error(&mut self, code: &str, message: impl Into<String>, position: Position) {
+        self.had_error = true;
+        self.bag.push(Diagnostic::error(code, message, position), self.file.clone());
+    }
+
+    /// Looks up `type_name`'s impl method named `method`, if any -- the
+    /// mechanism [`Self::check_expression`]'s `Binary`/`Index` arms use to
+    /// resolve an operator against a user type, since [`unify`] only knows
+    /// the built-in numeric/`Bool` kinds. Also the lookup [`Self::check_get`]
+    /// and [`Self::check_instance_method_call`] use to resolve a plain
+    /// `value.method(...)` call against the receiver's `impl`s.
+    fn find_operator_method(&self, type_name: &str, method: &str) -> Option<Arc<Function>> {
+        self.impls
+            .get(type_name)?
+            .iter()
+            .find_map(|imp| imp.methods.iter().find(|m| m.name == method).cloned())
+    }
+
+    /// Type-checks a binary operator against its operands -- shared by
+    /// [`ExpressionKind::Binary`] and the desugared compound-assignment
+    /// arm of [`ExpressionKind::Assignment`], so `x %= y` gets exactly the
+    /// same `%`-specific rule as `x = x % y` instead of a second copy of
+    /// it.
+    fn check_binary_op(&mut self, op: &str, left_ty: &Type, right_ty: &Type, position: Position) -> Type {
+        if op == "<<" || op == ">>" {
+            self.check_shift(left_ty, right_ty, position)
+        } else if op == "%" {
+            self.check_modulo(left_ty, right_ty, position)
+        } else {
+            match (&left_ty.kind, &right_ty.kind) {
+                (
+                    TypeKind::UserType(left_name, UserTypeKind::Struct, _),
+                    TypeKind::UserType(right_name, UserTypeKind::Struct, _),
+                ) if left_name == right_name => self.check_operator_method(left_name, op, position),
+                _ => self.unify_reporting_lossy(left_ty, right_ty, position),
+            }
+        }
+    }
+
+    /// Type-checks prefix or postfix `++`/`--`: the operand must be an
+    /// assignment target (same shape [`is_assignment_target`] requires for
+    /// `Assignment`, since incrementing is exactly as much an lvalue
+    /// operation) naming a numeric value. Reports `E212` for the former,
+    /// `E201` for the latter.
+    fn check_increment_decrement(&mut self, op: &str, operand: &Expression, position: Position) -> Type {
+        if !is_assignment_target(operand) {
+            self.error(
+                "E212",
+                "Invalid assignment target -- expected a variable, field or index expression"
+                    .to_string(),
+                operand.position,
+            );
+            return Type::error(position);
+        }
+        let operand_ty = self.check_expression(operand);
+        if operand_ty.kind.is_error() {
+            return Type::error(position);
+        }
+        if !operand_ty.kind.is_numeric() {
+            self.error(
+                "E201",
+                format!("'{}' requires a numeric operand, found '{:?}'", op, operand_ty.kind),
+                position,
+            );
+            return Type::error(position);
+        }
+        operand_ty
+    }
+
+    /// Type-checks `<<`/`>>`: both operands must be integers, and the
+    /// result takes the left operand's type (the width being shifted, not
+    /// the shift amount). Neither side unifies with the other the way
+    /// [`unify`] does for arithmetic -- `Int64 << 3u8` shifts an `Int64`
+    /// by `3`, it doesn't promote `3` to `Int64` first.
+    fn check_shift(&mut self, left: &Type, right: &Type, position: Position) -> Type {
+        if left.kind.is_error() || right.kind.is_error() {
+            return Type::error(position);
+        }
+        if !left.kind.is_integer() || !right.kind.is_integer() {
+            self.error(
+                "E201",
+                format!(
+                    "Bit-shift operands must be integers, found '{:?}' and '{:?}'",
+                    left.kind, right.kind
+                ),
+                position,
+            );
+            return Type::error(position);
+        }
+        left.clone()
+    }
+
+    /// Type-checks `%`: both operands must be integers -- the interpreter
+    /// only ever defines `%` for `Int`/`Int`, never `Float`/`Float`, so
+    /// accepting a float operand here would just defer the failure to a
+    /// runtime "unsupported operator" instead of a real diagnostic.
+    fn check_modulo(&mut self, left: &Type, right: &Type, position: Position) -> Type {
+        if left.kind.is_error() || right.kind.is_error() {
+            return Type::error(position);
+        }
+        if !left.kind.is_integer() || !right.kind.is_integer() {
+            self.error(
+                "E201",
+                format!(
+                    "Modulo operands must be integers, found '{:?}' and '{:?}'",
+                    left.kind, right.kind
+                ),
+                position,
+            );
+            return Type::error(position);
+        }
+        self.unify_reporting_lossy(left, right, position)
+    }
+
+    /// Type-checks a call expression. The shapes that are checked are a
+    /// plain `Identifier` callee (a top-level `func`, or a lambda-typed
+    /// local) against [`Self::functions`]/the local's `Type::Function`
+    /// signature; `<array-typed expr>.method(...)`; `<string-typed
+    /// expr>.method(...)`; `<struct/enum-typed expr>.method(...)` against
+    /// its `impl`s; and `Type.method(...)` where `Type` names a struct
+    /// with a matching static method -- recognized here as a call to the
+    /// built-in intrinsics (or static/instance method) each carries.
+    /// The struct/enum-instance shape only fires once the receiver's type
+    /// actually carries `UserTypeKind::Struct`/`Enum` -- true right after a
+    /// struct literal (`Point { .. }.method()`), a `let`/`for` binding
+    /// inferred from one, or an explicitly-typed `let`/`return` whose
+    /// annotation [`Self::unify_reporting`] already normalized against
+    /// the symbol table -- but not yet for a bare parameter or a
+    /// function call's return value used directly as a receiver, since
+    /// neither ever flows through [`Self::normalize_user_type`].
+    ///
+    /// Before giving up on a `Get` callee (no array/string intrinsic, no
+    /// impl method), a scalar receiver (`Int32`, `Bool`, ...) is first
+    /// checked against [`Self::check_extension_method_call`] -- an
+    /// extension method declared `func (Int32 n) abs(): Int32 { ... }` --
+    /// then [`Self::check_ufcs_call`] is tried as a last resort: uniform
+    /// function call syntax, so `x.f(y)` also resolves against a plain
+    /// top-level `func f(x: T, y: U): V { ... }` whose first parameter
+    /// accepts `x`'s type, the same way it would as `f(x, y)`.
+    fn check_call(&mut self, callee: &Expression, args: &[Expression], position: Position) -> Type {
+        if let ExpressionKind::Identifier(name) = &callee.kind {
+            if let Some(function) = self.functions.get(name).cloned() {
+                return self.check_function_call(name, &function.params, &function.return_type, args, position);
+            }
+            if let Some(Type {
+                kind: TypeKind::Function(param_types, return_type),
+                ..
+            }) = self.lookup_local(name)
+            {
+                let arg_types: Vec<Type> = args.iter().map(|arg| self.check_expression(arg)).collect();
+                if arg_types.len() != param_types.len() {
+                    self.error(
+                        "E202",
+                        format!(
+                            "'{}' takes {} argument{}, found {}",
+                            name,
+                            param_types.len(),
+                            if param_types.len() == 1 { "" } else { "s" },
+                            arg_types.len()
+                        ),
+                        position,
+                    );
+                } else {
+                    for (param_ty, arg_ty) in param_types.iter().zip(&arg_types) {
+                        self.unify_reporting(param_ty, arg_ty, position);
+                    }
+                }
+                return *return_type;
+            }
+        }
+        if let ExpressionKind::Get { object, name } = &callee.kind {
+            if let ExpressionKind::Identifier(type_name) = &object.kind {
+                if self.static_methods.contains_key(type_name) {
+                    return self.check_static_method_call(type_name, name, args, position);
+                }
+            }
+            let object_ty = self.check_expression(object);
+            match object_ty.kind.clone() {
+                TypeKind::Array(element_ty, _) => {
+                    return self.check_array_intrinsic(name, &element_ty, args, position);
+                }
+                TypeKind::String => return self.check_string_intrinsic(name, args, position),
+                TypeKind::UserType(type_name, UserTypeKind::Struct | UserTypeKind::Enum, _) => {
+                    if self.find_operator_method(&type_name, name).is_some() {
+                        return self.check_instance_method_call(&type_name, name, args, position);
+                    }
+                    if let Some(result) = self.check_ufcs_call(&object_ty, name, args, position) {
+                        return result;
+                    }
+                    return self.check_instance_method_call(&type_name, name, args, position);
+                }
+                _ => {
+                    if let Some(result) = self.check_extension_method_call(&object_ty, name, args, position) {
+                        return result;
+                    }
+                    if let Some(result) = self.check_ufcs_call(&object_ty, name, args, position) {
+                        return result;
+                    }
+                    if let Some(primitive_name) = primitive_type_name(&object_ty.kind) {
+                        for arg in args {
+                            self.check_expression(arg);
+                        }
+                        self.error("E203", format!("'{}' has no method '{}'", primitive_name, name), position);
+                        return Type::error(position);
+                    }
+                }
+            }
+        }
+        for arg in args {
+            self.check_expression(arg);
+        }
+        Type::error(position)
+    }
+
+    /// Checks a direct call to a top-level function (`f(x, y)`) against
+    /// its declared signature -- arity, then each argument against its
+    /// parameter's type. Mismatched arity is still reported once rather
+    /// than per missing/extra argument; whichever arguments do line up
+    /// with a parameter are still type-checked, and any beyond that are
+    /// still evaluated for side effects, the same way [`Self::check_ufcs_call`]
+    /// handles its own arity mismatch.
+    fn check_function_call(
+        &mut self,
+        name: &str,
+        params: &[Param],
+        return_type: &Type,
+        args: &[Expression],
+        position: Position,
+    ) -> Type {
+        if args.len() != params.len() {
+            self.error(
+                "E202",
+                format!(
+                    "'{}' takes {} argument{}, found {}",
+                    name,
+                    params.len(),
+                    if params.len() == 1 { "" } else { "s" },
+                    args.len()
+                ),
+                position,
+            );
+        }
+        for (arg, param) in args.iter().zip(params) {
+            let arg_ty = self.check_expression(arg);
+            self.unify_reporting(&param.ty, &arg_ty, arg.position);
+        }
+        for arg in args.iter().skip(params.len()) {
+            self.check_expression(arg);
+        }
+        return_type.clone()
+    }
+
+    /// Uniform function call syntax: resolves `x.f(y, z)` as `f(x, y, z)`
+    /// when no method named `f` exists on `x`'s type, but a top-level
+    /// function `f` does whose first parameter accepts `receiver_ty`.
+    /// Returns `None` (not an error) when no such function exists, so the
+    /// caller can fall back to its own "no method" diagnostic -- this is a
+    /// fallback tried *after* every method-lookup shape, never instead of
+    /// one, so an explicit `impl` method always wins over a same-named
+    /// free function.
+    fn check_ufcs_call(
+        &mut self,
+        receiver_ty: &Type,
+        name: &str,
+        args: &[Expression],
+        position: Position,
+    ) -> Option<Type> {
+        let function = self.functions.get(name)?.clone();
+        let first_param = function.params.first()?;
+        if !ufcs_param_matches(&first_param.ty.kind, &receiver_ty.kind) {
+            return None;
+        }
+        let expected_args = function.params.len() - 1;
+        if args.len() != expected_args {
+            self.error(
+                "E202",
+                format!(
+                    "'{}' takes {} argument{} (plus its receiver), found {}",
+                    name,
+                    expected_args,
+                    if expected_args == 1 { "" } else { "s" },
+                    args.len()
+                ),
+                position,
+            );
+        }
+        for (arg, param) in args.iter().zip(function.params.iter().skip(1)) {
+            let arg_ty = self.check_expression(arg);
+            self.unify_reporting(&param.ty, &arg_ty, arg.position);
+        }
+        for arg in args.iter().skip(expected_args) {
+            self.check_expression(arg);
+        }
+        Some(function.return_type.clone())
+    }
+
+    /// Extension methods on primitive types: resolves `n.abs()` against a
+    /// `func (Int32 n) abs(): Int32 { ... }` declaration -- stored in
+    /// [`Self::static_methods`] exactly like a struct's `func (Point)
+    /// new(...)`, just keyed by a primitive's name (`"Int32"`) instead of
+    /// a struct's, and picked out here by `receiver_name.is_some()` so a
+    /// plain static function of the same primitive (were that ever
+    /// meaningful) wouldn't be mistaken for an instance method. Returns
+    /// `None` when no such method exists, so the caller can move on to
+    /// [`Self::check_ufcs_call`] instead. Arrays and strings keep their
+    /// own dedicated intrinsic tables ([`Self::check_array_intrinsic`]/
+    /// [`Self::check_string_intrinsic`]) and aren't extended through here.
+    fn check_extension_method_call(
+        &mut self,
+        receiver_ty: &Type,
+        name: &str,
+        args: &[Expression],
+        position: Position,
+    ) -> Option<Type> {
+        let receiver_name = primitive_type_name(&receiver_ty.kind)?;
+        let method = self
+            .static_methods
+            .get(receiver_name)
+            .and_then(|methods| methods.get(name))
+            .filter(|method| method.receiver_name.is_some())
+            .cloned()?;
+        let arg_types: Vec<Type> = args.iter().map(|arg| self.check_expression(arg)).collect();
+        if arg_types.len() != method.params.len() {
+            self.error(
+                "E202",
+                format!(
+                    "'{}.{}' takes {} argument{}, found {}",
+                    receiver_name,
+                    name,
+                    method.params.len(),
+                    if method.params.len() == 1 { "" } else { "s" },
+                    arg_types.len()
+                ),
+                position,
+            );
+        } else {
+            for (param, arg_ty) in method.params.iter().zip(&arg_types) {
+                self.unify_reporting(&param.ty, arg_ty, position);
+            }
+        }
+        Some(method.return_type.clone())
+    }
+
+    /// Type-checks a non-call `Get` (`value.member`): a struct field, a
+    /// struct/enum method named without being called, or `EnumName.Variant`
+    /// naming a variant of `EnumName`. `Resolver::visit_get` already
+    /// validates a *static* `Type.member` path and module-namespace
+    /// `Get`s; besides the `EnumName.Variant` case (which needs to produce
+    /// a real value here, not just be accepted), this only covers member
+    /// access on a struct/enum-typed value, which needs a concrete
+    /// receiver type this pass has and the resolver doesn't. Same
+    /// `UserTypeKind::Unknown`-annotation caveat as [`Self::check_call`]
+    /// applies here too.
+    fn check_get(&mut self, object: &Expression, name: &str, position: Position) -> Type {
+        if let ExpressionKind::Identifier(type_name) = &object.kind {
+            if self.lookup_local(type_name).is_none() {
+                if let Some(enu) = self.enums.get(type_name) {
+                    if enu.variants.iter().any(|v| v.name == name) {
+                        return Type::new(TypeKind::UserType(type_name.clone(), UserTypeKind::Enum, Vec::new()), position);
+                    }
+                }
+            }
+        }
+        let object_ty = self.check_expression(object);
+        match &object_ty.kind {
+            TypeKind::Error => Type::error(position),
+            TypeKind::UserType(type_name, UserTypeKind::Struct, _) => {
+                if let Some(field) = self
+                    .structs
+                    .get(type_name)
+                    .and_then(|strukt| strukt.fields.iter().find(|f| f.name == name))
+                {
+                    return field.ty.clone();
+                }
+                match self.find_operator_method(type_name, name) {
+                    Some(method) => method.return_type.clone(),
+                    None => {
+                        self.error(
+                            "E203",
+                            format!("Struct '{}' has no field or method '{}'", type_name, name),
+                            position,
+                        );
+                        Type::error(position)
+                    }
+                }
+            }
+            TypeKind::UserType(type_name, UserTypeKind::Enum, _) => {
+                match self.find_operator_method(type_name, name) {
+                    Some(method) => method.return_type.clone(),
+                    None => {
+                        self.error(
+                            "E203",
+                            format!("Enum '{}' has no method '{}'", type_name, name),
+                            position,
+                        );
+                        Type::error(position)
+                    }
+                }
+            }
+            _ => Type::error(position),
+        }
+    }
+
+    /// Checks a call to an instance method reached through `Get`
+    /// (`value.method(...)`, where `value` is a struct or enum) against
+    /// its declared parameter types and arity, the same shape
+    /// [`Self::check_static_method_call`] checks a static call against.
+    /// An unknown method name *is* reported here, unlike the static-call
+    /// case -- nothing upstream of the typechecker knows what fields or
+    /// methods a struct/enum instance actually carries.
+    fn check_instance_method_call(
+        &mut self,
+        type_name: &str,
+        name: &str,
+        args: &[Expression],
+        position: Position,
+    ) -> Type {
+        let method = self.find_operator_method(type_name, name);
+        let arg_types: Vec<Type> = args.iter().map(|arg| self.check_expression(arg)).collect();
+        let Some(method) = method else {
+            self.error("E203", format!("'{}' has no method '{}'", type_name, name), position);
+            return Type::error(position);
+        };
+        if arg_types.len() != method.params.len() {
+            self.error(
+                "E202",
+                format!(
+                    "'{}.{}' takes {} argument{}, found {}",
+                    type_name,
+                    name,
+                    method.params.len(),
+                    if method.params.len() == 1 { "" } else { "s" },
+                    arg_types.len()
+                ),
+                position,
+            );
+        } else {
+            for (param, arg_ty) in method.params.iter().zip(&arg_types) {
+                self.unify_reporting(&param.ty, arg_ty, position);
+            }
+        }
+        method.return_type.clone()
+    }
+
+    /// Checks a call to a struct's static/associated function --
+    /// `Point.new(1, 2)` for a `func (Point) new(x: Int32, y: Int32):
+    /// Point { ... }` declaration -- against its declared parameter
+    /// types and arity, the same shape of check `check_array_intrinsic`/
+    /// `check_string_intrinsic` do for their own fixed signatures. A
+    /// method name that doesn't exist on `type_name` isn't reported here
+    /// -- `Resolver::visit_get` already caught that before this pass
+    /// runs, the same division of labor `check_expression`'s `StructInit`
+    /// arm relies on for "does this struct exist at all".
+    fn check_static_method_call(
+        &mut self,
+        type_name: &str,
+        name: &str,
+        args: &[Expression],
+        position: Position,
+    ) -> Type {
+        let method = self.static_methods.get(type_name).and_then(|methods| methods.get(name)).cloned();
+        let arg_types: Vec<Type> = args.iter().map(|arg| self.check_expression(arg)).collect();
+        let Some(method) = method else {
+            return Type::error(position);
+        };
+        if arg_types.len() != method.params.len() {
+            self.error(
+                "E202",
+                format!(
+                    "'{}.{}' takes {} argument{}, found {}",
+                    type_name,
+                    name,
+                    method.params.len(),
+                    if method.params.len() == 1 { "" } else { "s" },
+                    arg_types.len()
+                ),
+                position,
+            );
+        } else {
+            for (param, arg_ty) in method.params.iter().zip(&arg_types) {
+                self.unify_reporting(&param.ty, arg_ty, position);
+            }
+        }
+        method.return_type.clone()
+    }
+
+    /// Checks a call to one of the intrinsic methods every array carries:
+    /// `len()` (no arguments, returns `Int32`), `push(x)` (one argument
+    /// matching the array's element type, returns `Void`), and `pop()`
+    /// (no arguments, returns the element type). Anything else is
+    /// reported the same way an unknown struct field/method would be.
+    fn check_array_intrinsic(
+        &mut self,
+        name: &str,
+        element_ty: &Type,
+        args: &[Expression],
+        position: Position,
+    ) -> Type {
+        let arg_types: Vec<Type> = args.iter().map(|arg| self.check_expression(arg)).collect();
+        match name {
+            "len" => {
+                if !arg_types.is_empty() {
+                    self.error(
+                        "E202",
+                        format!("'len' takes no arguments, found {}", arg_types.len()),
+                        position,
+                    );
+                }
+                Type::new(TypeKind::Int32, position)
+            }
+            "push" => {
+                if arg_types.len() != 1 {
+                    self.error(
+                        "E202",
+                        format!("'push' takes 1 argument, found {}", arg_types.len()),
+                        position,
+                    );
+                } else {
+                    self.unify_reporting(element_ty, &arg_types[0], position);
+                }
+                Type::new(TypeKind::Void, position)
+            }
+            "pop" => {
+                if !arg_types.is_empty() {
+                    self.error(
+                        "E202",
+                        format!("'pop' takes no arguments, found {}", arg_types.len()),
+                        position,
+                    );
+                }
+                element_ty.clone()
+            }
+            _ => {
+                self.error("E203", format!("Arrays have no method '{}'", name), position);
+                Type::error(position)
+            }
+        }
+    }
+
+    /// Checks a call to one of the intrinsic methods every string
+    /// carries: `len()` (no arguments, `Int32`), `substring(start, end)`
+    /// (two `Int32` arguments, `String`), `contains(needle)` (one
+    /// `String` argument, `Bool`), `split(sep)` (one `String` argument,
+    /// `String[]`), and `to_int()` (no arguments, `Int32`). Anything else
+    /// is reported the same way an unknown array method would be.
+    fn check_string_intrinsic(&mut self, name: &str, args: &[Expression], position: Position) -> Type {
+        let arg_types: Vec<Type> = args.iter().map(|arg| self.check_expression(arg)).collect();
+        let string_ty = Type::new(TypeKind::String, position);
+        let int_ty = Type::new(TypeKind::Int32, position);
+        match name {
+            "len" => {
+                if !arg_types.is_empty() {
+                    self.error(
+                        "E202",
+                        format!("'len' takes no arguments, found {}", arg_types.len()),
+                        position,
+                    );
+                }
+                int_ty
+            }
+            "substring" => {
+                if arg_types.len() != 2 {
+                    self.error(
+                        "E202",
+                        format!("'substring' takes 2 arguments, found {}", arg_types.len()),
+                        position,
+                    );
+                } else {
+                    self.unify_reporting(&int_ty, &arg_types[0], position);
+                    self.unify_reporting(&int_ty, &arg_types[1], position);
+                }
+                string_ty
+            }
+            "contains" => {
+                if arg_types.len() != 1 {
+                    self.error(
+                        "E202",
+                        format!("'contains' takes 1 argument, found {}", arg_types.len()),
+                        position,
+                    );
+                } else {
+                    self.unify_reporting(&string_ty, &arg_types[0], position);
+                }
+                Type::new(TypeKind::Bool, position)
+            }
+            "split" => {
+                if arg_types.len() != 1 {
+                    self.error(
+                        "E202",
+                        format!("'split' takes 1 argument, found {}", arg_types.len()),
+                        position,
+                    );
+                } else {
+                    self.unify_reporting(&string_ty, &arg_types[0], position);
+                }
+                Type::new(TypeKind::Array(Box::new(string_ty), None), position)
+            }
+            "to_int" => {
+                if !arg_types.is_empty() {
+                    self.error(
+                        "E202",
+                        format!("'to_int' takes no arguments, found {}", arg_types.len()),
+                        position,
+                    );
+                }
+                int_ty
+            }
+            _ => {
+                self.error("E203", format!("Strings have no method '{}'", name), position);
+                Type::error(position)
+            }
+        }
+    }
+
+    /// Resolves `op` against `type_name`'s impls: `E211` if `op` has no
+    /// operator-method mapping at all, or if it does but `type_name` has no
+    /// matching method; otherwise the method's declared return type.
+    fn check_operator_method(&mut self, type_name: &str, op: &str, position: Position) -> Type {
+        let Some(method_name) = operator_method_name(op) else {
+            self.error(
+                "E211",
+                format!("Operator '{}' is not defined for '{}'", op, type_name),
+                position,
+            );
+            return Type::error(position);
+        };
+        match self.find_operator_method(type_name, method_name) {
+            Some(method) => method.return_type.clone(),
+            None => {
+                self.error(
+                    "E211",
+                    format!(
+                        "'{}' has no '{}' method for operator '{}'",
+                        type_name, method_name, op
+                    ),
+                    position,
+                );
+                Type::error(position)
+            }
+        }
+    }
+}
+
+/// Maps a binary/index operator token to the impl method name
+/// [`Typechecker::check_operator_method`] looks for on a `UserType`
+/// operand -- `Vec2 + Vec2` calls `add`, `a[i]` calls `index` (spelled
+/// `"[]"` here since indexing has no infix token of its own), `a == b`
+/// calls `eq`. Operators with no entry (`&&`, `||`, `<`, ...) are never
+/// resolved against user-type impls, only the numeric/`Bool` operands
+/// [`unify`] already handles.
+fn operator_method_name(op: &str) -> Option<&'static str> {
+    match op {
+        "+" => Some("add"),
+        "-" => Some("sub"),
+        "*" => Some("mul"),
+        "/" => Some("div"),
+        "%" => Some("rem"),
+        "==" | "!=" => Some("eq"),
+        "[]" => Some("index"),
+        _ => None,
+    }
+}
+
+/// Whether a UFCS candidate's first parameter accepts a receiver of
+/// `receiver_ty`. `UserType`s compare by name only, ignoring
+/// `UserTypeKind` -- a parameter's declared type always parses as
+/// `UserTypeKind::Unknown` (see [`Typechecker::check_call`]'s doc comment),
+/// while a receiver inferred from a struct literal carries `Struct`/`Enum`;
+/// comparing the full `TypeKind` here would make UFCS never fire for the
+/// exact case it exists for.
+fn ufcs_param_matches(param_ty: &TypeKind, receiver_ty: &TypeKind) -> bool {
+    match (param_ty, receiver_ty) {
+        (TypeKind::UserType(a, _, _), TypeKind::UserType(b, _, _)) => a == b,
+        _ => param_ty == receiver_ty,
+    }
+}
+
+/// The receiver-clause spelling (`"Int32"`, `"Bool"`, ...) a scalar
+/// `TypeKind` was declared with, the inverse of [`TypeKind::from_string`]
+/// restricted to the primitive cases -- what
+/// [`Typechecker::check_extension_method_call`] looks up in
+/// [`Typechecker::static_methods`] against. `None` for every non-scalar
+/// kind (arrays, strings, user types, ...), which don't go through
+/// extension-method resolution.
+fn primitive_type_name(kind: &TypeKind) -> Option<&'static str> {
+    match kind {
+        TypeKind::Int8 => Some("Int8"),
+        TypeKind::Int16 => Some("Int16"),
+        TypeKind::Int32 => Some("Int32"),
+        TypeKind::Int64 => Some("Int64"),
+        TypeKind::UInt8 => Some("UInt8"),
+        TypeKind::UInt16 => Some("UInt16"),
+        TypeKind::UInt32 => Some("UInt32"),
+        TypeKind::UInt64 => Some("UInt64"),
+        TypeKind::Float32 => Some("Float32"),
+        TypeKind::Float64 => Some("Float64"),
+        TypeKind::Bool => Some("Bool"),
+        TypeKind::Char => Some("Char"),
+        _ => None,
+    }
+}
+
+impl<'a> Typechecker<'a> {
+    fn check_expression(&mut self, expr: &Expression) -> Type {
+        let ty = match &expr.kind {
+            ExpressionKind::Literal(LiteralValue::Int(_, suffix)) => {
+                Type::new(suffix.clone().unwrap_or(TypeKind::Int32), expr.position)
+            }
+            ExpressionKind::Literal(LiteralValue::Float(_, suffix)) => {
+                Type::new(suffix.clone().unwrap_or(TypeKind::Float64), expr.position)
+            }
+            ExpressionKind::Literal(LiteralValue::Bool(_)) => Type::new(TypeKind::Bool, expr.position),
+            ExpressionKind::Literal(LiteralValue::String(_)) => {
+                Type::new(TypeKind::String, expr.position)
+            }
+            ExpressionKind::Literal(LiteralValue::Char(_)) => {
+                Type::new(TypeKind::Char, expr.position)
+            }
+            ExpressionKind::Binary { left, right, op } => {
+                let left_ty = self.check_expression(left);
+                let right_ty = self.check_expression(right);
+                self.check_binary_op(op, &left_ty, &right_ty, expr.position)
+            }
+            ExpressionKind::Unary { op, operand } if op == "++" || op == "--" => {
+                self.check_increment_decrement(op, operand, expr.position)
+            }
+            ExpressionKind::Postfix { op, operand } => {
+                self.check_increment_decrement(op, operand, expr.position)
+            }
+            ExpressionKind::Assignment { target, op, value } => {
+                if !is_assignment_target(target) {
+                    self.error(
+                        "E212",
+                        "Invalid assignment target -- expected a variable, field or index expression"
+                            .to_string(),
+                        target.position,
+                    );
+                }
+                let target_ty = self.check_expression(target);
+                let value_ty = self.check_expression(value);
+                if op == "=" {
+                    self.unify_reporting(&target_ty, &value_ty, expr.position)
+                } else {
+                    // Desugars `target op= value` to `target = target op
+                    // value` at the type level: whichever operator-specific
+                    // rule `Binary` would apply to `target op value` (the
+                    // integer-only checks on `%`/`<<`/`>>`, the operator-
+                    // method lookup on a `UserType`, ...) applies here too,
+                    // rather than re-deriving a separate set of rules for
+                    // the compound form.
+                    let bare_op = &op[..op.len() - 1];
+                    self.check_binary_op(bare_op, &target_ty, &value_ty, expr.position)
+                }
+            }
+            ExpressionKind::Grouping(inner) => self.check_expression(inner),
+            ExpressionKind::ArrayLiteral(items) => {
+                let mut element_ty: Option<Type> = None;
+                for item in items {
+                    let item_ty = self.check_expression(item);
+                    element_ty = Some(match element_ty {
+                        Some(existing) => self.unify_reporting(&existing, &item_ty, expr.position),
+                        None => item_ty,
+                    });
+                }
+                let element_ty = element_ty.unwrap_or_else(|| Type::error(expr.position));
+                // Not `Some(items.len())` -- an array literal's own
+                // inferred type is never unified against another array
+                // *value*'s length, only checked against a fixed-size
+                // *annotation* directly (`Self::check_array_length`), so
+                // embedding the length here would only make an ordinary
+                // `T[]`-annotated literal (declared size `None`) stop
+                // unifying with its own inferred type for no benefit.
+                Type::new(TypeKind::Array(Box::new(element_ty), None), expr.position)
+            }
+            ExpressionKind::Tuple(items) => {
+                let element_types = items.iter().map(|item| self.check_expression(item)).collect();
+                Type::new(TypeKind::Tuple(element_types), expr.position)
+            }
+            ExpressionKind::MapLiteral(entries) => {
+                let pairs: Vec<(Type, Type)> = entries
+                    .iter()
+                    .map(|(key, value)| (self.check_expression(key), self.check_expression(value)))
+                    .collect();
+                let mut iter = pairs.into_iter();
+                match iter.next() {
+                    Some((mut key_ty, mut value_ty)) => {
+                        for (next_key, next_value) in iter {
+                            key_ty = self.unify_reporting(&key_ty, &next_key, expr.position);
+                            value_ty = self.unify_reporting(&value_ty, &next_value, expr.position);
+                        }
+                        Type::new(
+                            TypeKind::Map(Box::new(key_ty), Box::new(value_ty)),
+                            expr.position,
+                        )
+                    }
+                    None => Type::error(expr.position),
+                }
+            }
+            ExpressionKind::Index { object, index } => {
+                let object_ty = self.check_expression(object);
+                let index_ty = self.check_expression(index);
+                if object_ty.kind.is_error() || index_ty.kind.is_error() {
+                    Type::error(expr.position)
+                } else {
+                    match &object_ty.kind {
+                        TypeKind::Map(key_ty, value_ty) => {
+                            self.unify_reporting(key_ty, &index_ty, expr.position);
+                            (**value_ty).clone()
+                        }
+                        TypeKind::Array(element_ty, _) => {
+                            self.unify_reporting(
+                                &Type::new(TypeKind::Int32, expr.position),
+                                &index_ty,
+                                expr.position,
+                            );
+                            (**element_ty).clone()
+                        }
+                        TypeKind::UserType(name, UserTypeKind::Struct, _) => {
+                            self.check_operator_method(name, "[]", expr.position)
+                        }
+                        _ => {
+                            self.error(
+                                "E203",
+                                format!("Cannot index into '{:?}'", object_ty.kind),
+                                expr.position,
+                            );
+                            Type::error(expr.position)
+                        }
+                    }
+                }
+            }
+            ExpressionKind::Lambda {
+                params,
+                return_type,
+                body,
+            } => {
+                let outer_return = self.current_return.replace(return_type.clone());
+                for statement in body {
+                    self.check_statement(statement, return_type);
+                }
+                self.current_return = outer_return;
+                Type::new(
+                    TypeKind::Function(
+                        params.iter().map(|p| p.ty.clone()).collect(),
+                        Box::new(return_type.clone()),
+                    ),
+                    expr.position,
+                )
+            }
+            ExpressionKind::Match { subject, arms } => {
+                self.check_expression(subject);
+                let return_type = self
+                    .current_return
+                    .clone()
+                    .unwrap_or_else(|| Type::new(TypeKind::Void, expr.position));
+                let mut value_ty: Option<Type> = None;
+                for arm in arms {
+                    let Some((last, init)) = arm.body.split_last() else {
+                        continue;
+                    };
+                    for stmt in init {
+                        self.check_statement(stmt, &return_type);
+                    }
+                    let arm_ty = if let StatementKind::Expression(value_expr) = &last.kind {
+                        self.check_expression(value_expr)
+                    } else {
+                        self.check_statement(last, &return_type);
+                        Type::new(TypeKind::Void, last.position)
+                    };
+                    value_ty = Some(match value_ty {
+                        Some(existing) => self.unify_reporting_lossy(&existing, &arm_ty, expr.position),
+                        None => arm_ty,
+                    });
+                }
+                value_ty.unwrap_or_else(|| Type::new(TypeKind::Void, expr.position))
+            }
+            ExpressionKind::Try(inner) => {
+                let inner_ty = self.check_expression(inner);
+                if inner_ty.kind.is_error() {
+                    Type::error(expr.position)
+                } else if let TypeKind::Result(ok_ty, err_ty) = &inner_ty.kind {
+                    match &self.current_return {
+                        Some(Type {
+                            kind: TypeKind::Result(_, enclosing_err),
+                            ..
+                        }) if enclosing_err.kind == err_ty.kind => {}
+                        _ => {
+                            self.error(
+                                "E202",
+                                "'?' used in a function whose return type is not a matching 'Result'"
+                                    .to_string(),
+                                expr.position,
+                            );
+                        }
+                    }
+                    (**ok_ty).clone()
+                } else {
+                    self.error(
+                        "E201",
+                        format!(
+                            "'?' can only be used on a 'Result' value, found '{:?}'",
+                            inner_ty.kind
+                        ),
+                        expr.position,
+                    );
+                    Type::error(expr.position)
+                }
+            }
+            ExpressionKind::StructInit { name, fields } => {
+                let field_types: Vec<Type> =
+                    fields.iter().map(|(_, value)| self.check_expression(value)).collect();
+                if let Some(strukt) = self.structs.get(name).cloned() {
+                    let mut provided: std::collections::HashSet<&str> = std::collections::HashSet::new();
+                    for ((field_name, _), field_ty) in fields.iter().zip(&field_types) {
+                        match strukt.fields.iter().find(|f| &f.name == field_name) {
+                            Some(decl_field) => {
+                                if !provided.insert(field_name.as_str()) {
+                                    self.error(
+                                        "E202",
+                                        format!("Field '{}' specified more than once", field_name),
+                                        expr.position,
+                                    );
+                                }
+                                self.unify_reporting(&decl_field.ty, field_ty, expr.position);
+                            }
+                            None => {
+                                let mut message = format!("Struct '{}' has no field '{}'", name, field_name);
+                                if let Some(suggestion) = environment::best_suggestion(
+                                    field_name,
+                                    strukt.fields.iter().map(|f| f.name.as_str()),
+                                ) {
+                                    message.push_str(&format!("; did you mean '{}'?", suggestion));
+                                }
+                                self.error("E203", message, expr.position);
+                            }
+                        }
+                    }
+                    let missing: Vec<&str> = strukt
+                        .fields
+                        .iter()
+                        .map(|f| f.name.as_str())
+                        .filter(|field_name| !provided.contains(field_name))
+                        .collect();
+                    if !missing.is_empty() {
+                        self.error(
+                            "E202",
+                            format!(
+                                "Missing field{} in initializer of '{}': {}",
+                                if missing.len() == 1 { "" } else { "s" },
+                                name,
+                                missing.join(", ")
+                            ),
+                            expr.position,
+                        );
+                    }
+                }
+                Type::new(TypeKind::UserType(name.clone(), UserTypeKind::Struct, Vec::new()), expr.position)
+            }
+            ExpressionKind::Call { callee, args } => self.check_call(callee, args, expr.position),
+            ExpressionKind::Get { object, name } => self.check_get(object, name, expr.position),
+            ExpressionKind::Identifier(name) => self
+                .lookup_local(name)
+                .or_else(|| {
+                    self.find_enum_variant(name)
+                        .map(|enum_name| Type::new(TypeKind::UserType(enum_name, UserTypeKind::Enum, Vec::new()), expr.position))
+                })
+                .unwrap_or_else(|| Type::new(TypeKind::Error, expr.position)),
+            _ => Type::new(TypeKind::Error, expr.position),
+        };
+        self.types.insert(expr.position, ty.clone());
+        ty
+    }
+}
+
+/// Whether `expr` is a valid assignment target: a plain variable, a field
+/// access, or an index expression. Anything else (a literal, a call, a
+/// binary expression, ...) can't sit on the left of `=`/`+=`/...
+fn is_assignment_target(expr: &Expression) -> bool {
+    matches!(
+        expr.kind,
+        ExpressionKind::Identifier(_) | ExpressionKind::Get { .. } | ExpressionKind::Index { .. }
+    )
+}
+
+/// Reconciles two types. Identical kinds unify to themselves; numeric
+/// kinds unify to whichever has the higher [`TypeKind::precedence`]
+/// (implicit widening); anything else is a mismatch, reported by the
+/// caller and represented here as `Type::Error`.
+pub fn unify(left: &Type, right: &Type) -> Type {
+    if left.kind == right.kind {
+        return left.clone();
+    }
+    if left.kind.is_numeric() && right.kind.is_numeric() {
+        return if left.kind.precedence() >= right.kind.precedence() {
+            left.clone()
+        } else {
+            right.clone()
+        };
+    }
+    // An array literal's inferred type is always dynamically-sized
+    // (`Array(_, None)`, see the `ArrayLiteral` arm of
+    // `Typechecker::check_expression`), so unifying it against a
+    // fixed-size annotation would otherwise fail on the size alone even
+    // though the element type matches. Whether the literal actually has
+    // the declared length is checked separately, and exactly once, by
+    // `Typechecker::check_array_length`.
+    if let (TypeKind::Array(l_elem, l_size), TypeKind::Array(r_elem, r_size)) = (&left.kind, &right.kind) {
+        if l_elem.kind == r_elem.kind {
+            let size = match (l_size, r_size) {
+                (a, b) if a == b => *a,
+                (Some(n), None) | (None, Some(n)) => Some(*n),
+                _ => return Type::new(TypeKind::Error, left.position),
+            };
+            return Type::new(TypeKind::Array(l_elem.clone(), size), left.position);
+        }
+    }
+    // A struct literal's inferred type never carries type arguments (see
+    // the `StructInit` arm of `Typechecker::check_expression`) even when
+    // the struct it names declares type parameters, so unifying it
+    // against an annotation that spells one out explicitly (`Box<Int32>`)
+    // would otherwise fail on the argument list alone even though both
+    // sides name the same struct. Whether the annotation's own argument
+    // list has the right arity is checked separately, and exactly once,
+    // by `Resolver::check_type`'s `E215` -- this compiler has no generic
+    // instantiation mechanism to check a literal's arguments against in
+    // the first place.
+    if let (TypeKind::UserType(l_name, l_kind, l_args), TypeKind::UserType(r_name, r_kind, r_args)) =
+        (&left.kind, &right.kind)
+    {
+        if l_name == r_name && l_kind == r_kind && (l_args.is_empty() || r_args.is_empty()) {
+            return left.clone();
+        }
+    }
+    Type::new(TypeKind::Error, left.position)
+}
+
+/// Seconds since the epoch a file was last modified, for `.mti` cache
+/// invalidation. `None` if the file's metadata can't be read (in which
+/// case caching is simply skipped rather than trusted).
+fn mtime_secs(path: &std::path::Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .ok()?
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// Loads the `.mti` interface cached next to `source_path`, if any, and
+/// only if it was written for the source's current mtime.
+fn load_cached_interface(source_path: &std::path::Path, source_mtime: u64) -> Option<MatchaModule> {
+    let text = std::fs::read_to_string(source_path.with_extension("mti")).ok()?;
+    let (module, cached_mtime) = MatchaModule::from_interface(&text)?;
+    (cached_mtime == source_mtime).then_some(module)
+}
+
+/// Infers the element type of a `for (x in iterable)` loop directly from
+/// an array literal. The resolver has no general-purpose type inference,
+/// so anything besides a literal (a variable, a call, ...) falls back to
+/// `Type::Error`, deferred to whatever downstream check needs the real
+/// type once the typechecker gains a variable environment of its own.
+fn infer_array_element_type(iterable: &Expression) -> Type {
+    match &iterable.kind {
+        ExpressionKind::ArrayLiteral(items) => match items.first() {
+            Some(first) => literal_type(first).unwrap_or_else(|| Type::error(first.position)),
+            None => Type::error(iterable.position),
+        },
+        _ => Type::error(iterable.position),
+    }
+}
+
+fn literal_type(expr: &Expression) -> Option<Type> {
+    match &expr.kind {
+        ExpressionKind::Literal(LiteralValue::Int(_, suffix)) => {
+            Some(Type::new(suffix.clone().unwrap_or(TypeKind::Int32), expr.position))
+        }
+        ExpressionKind::Literal(LiteralValue::Float(_, suffix)) => {
+            Some(Type::new(suffix.clone().unwrap_or(TypeKind::Float64), expr.position))
+        }
+        ExpressionKind::Literal(LiteralValue::Bool(_)) => {
+            Some(Type::new(TypeKind::Bool, expr.position))
+        }
+        ExpressionKind::Literal(LiteralValue::String(_)) => {
+            Some(Type::new(TypeKind::String, expr.position))
+        }
+        _ => None,
+    }
+}
+
+/// Collects every name introduced by a `let`/`var`/`const` inside `body`
+/// (not recursing into nested lambdas, whose locals are their own).
+fn collect_locals(body: &[Statement], out: &mut std::collections::HashSet<String>) {
+    for statement in body {
+        match &statement.kind {
+            StatementKind::Let { name, .. } => {
+                out.insert(name.clone());
+            }
+            StatementKind::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                collect_locals(then_branch, out);
+                if let Some(else_branch) = else_branch {
+                    collect_locals(else_branch, out);
+                }
+            }
+            StatementKind::While { body, .. } => collect_locals(body, out),
+            StatementKind::For { body, .. } => collect_locals(body, out),
+            StatementKind::ForEach { variable, body, .. } => {
+                out.insert(variable.clone());
+                collect_locals(body, out);
+            }
+            StatementKind::Block(stmts) => collect_locals(stmts, out),
+            _ => {}
+        }
+    }
+}
+
+/// Collects every identifier referenced anywhere inside `body`, including
+/// inside nested lambdas (a capture of an outer lambda may itself be
+/// captured again by an inner one).
+fn collect_identifiers(body: &[Statement], out: &mut std::collections::HashSet<String>) {
+    for statement in body {
+        match &statement.kind {
+            StatementKind::Expression(expr) => collect_identifiers_expr(expr, out),
+            StatementKind::Let { value: Some(v), .. } => collect_identifiers_expr(v, out),
+            StatementKind::Return(Some(expr)) => collect_identifiers_expr(expr, out),
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                collect_identifiers_expr(condition, out);
+                collect_identifiers(then_branch, out);
+                if let Some(else_branch) = else_branch {
+                    collect_identifiers(else_branch, out);
+                }
+            }
+            StatementKind::While { condition, body } => {
+                collect_identifiers_expr(condition, out);
+                collect_identifiers(body, out);
+            }
+            StatementKind::For { body, .. } => collect_identifiers(body, out),
+            StatementKind::ForEach { iterable, body, .. } => {
+                collect_identifiers_expr(iterable, out);
+                collect_identifiers(body, out);
+            }
+            StatementKind::Block(stmts) => collect_identifiers(stmts, out),
+            _ => {}
+        }
+    }
+}
+
+fn collect_identifiers_expr(expr: &Expression, out: &mut std::collections::HashSet<String>) {
+    match &expr.kind {
+        ExpressionKind::Identifier(name) => {
+            out.insert(name.clone());
+        }
+        ExpressionKind::Binary { left, right, .. } => {
+            collect_identifiers_expr(left, out);
+            collect_identifiers_expr(right, out);
+        }
+        ExpressionKind::Unary { operand, .. } | ExpressionKind::Postfix { operand, .. } => {
+            collect_identifiers_expr(operand, out)
+        }
+        ExpressionKind::Grouping(inner) => collect_identifiers_expr(inner, out),
+        ExpressionKind::Call { callee, args } => {
+            collect_identifiers_expr(callee, out);
+            for arg in args {
+                collect_identifiers_expr(arg, out);
+            }
+        }
+        ExpressionKind::Get { object, .. } => collect_identifiers_expr(object, out),
+        ExpressionKind::Index { object, index } => {
+            collect_identifiers_expr(object, out);
+            collect_identifiers_expr(index, out);
+        }
+        ExpressionKind::Assignment { target, value, .. } => {
+            collect_identifiers_expr(target, out);
+            collect_identifiers_expr(value, out);
+        }
+        ExpressionKind::ArrayLiteral(items) | ExpressionKind::Tuple(items) => {
+            for item in items {
+                collect_identifiers_expr(item, out);
+            }
+        }
+        ExpressionKind::StructInit { fields, .. } => {
+            for (_, value) in fields {
+                collect_identifiers_expr(value, out);
+            }
+        }
+        ExpressionKind::Lambda { body, .. } => collect_identifiers(body, out),
+        ExpressionKind::Try(inner) => collect_identifiers_expr(inner, out),
+        ExpressionKind::MapLiteral(entries) => {
+            for (key, value) in entries {
+                collect_identifiers_expr(key, out);
+                collect_identifiers_expr(value, out);
+            }
+        }
+        ExpressionKind::Match { subject, arms } => {
+            collect_identifiers_expr(subject, out);
+            for arm in arms {
+                collect_identifiers(&arm.body, out);
+            }
+        }
+        ExpressionKind::Literal(_) | ExpressionKind::Error => {}
+    }
+}
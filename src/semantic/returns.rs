@@ -0,0 +1,142 @@
+//! Checks that every execution path through a non-`Void` function ends
+//! in a `return` carrying a value, instead of falling off the end of the
+//! body -- which the interpreter and every backend currently treat as
+//! an implicit `return Void`, silently handing the caller a value of
+//! the wrong type.
+//!
+//! This walks the parsed AST directly, the same way
+//! [`crate::semantic::dce`] and [`crate::semantic::lint`] do, rather
+//! than waiting for the resolver or typechecker: whether a path
+//! terminates is a structural property of the statements themselves, not
+//! something that needs names or types resolved first.
+//!
+//! `match` is only treated as exhaustive here when it has a wildcard (or
+//! bare-binding) catch-all arm -- checking real enum coverage needs the
+//! symbol table [`crate::semantic::match_check`] has and this pass
+//! doesn't, so a `match` that's exhaustive purely by listing every
+//! variant is (conservatively) still flagged unless it also has a
+//! catch-all.
+
+use crate::ast::{Expression, ExpressionKind, Function, LiteralValue, Module, Pattern, Statement, StatementKind, TypeKind};
+use crate::errors::{Diagnostic, DiagnosticBag};
+
+/// Runs the check over every top-level function and `impl` method in
+/// `module`, pushing an error into `bag` for each one that can fall off
+/// its end -- the same bag the parser, resolver and typechecker share,
+/// so this joins their diagnostics in one sorted report instead of
+/// printing ahead of (or behind) them.
+pub fn analyze(module: &Module, file: &str, bag: &mut DiagnosticBag) {
+    analyze_statements(&module.statements, file, bag);
+}
+
+fn analyze_statements(statements: &[Statement], file: &str, bag: &mut DiagnosticBag) {
+    for statement in statements {
+        match &statement.kind {
+            StatementKind::FunctionDecl(function) => check_function(function, file, bag),
+            StatementKind::ImplBlock(imp) => {
+                for method in &imp.methods {
+                    check_function(method, file, bag);
+                }
+            }
+            StatementKind::ModuleDecl(block) => analyze_statements(&block.statements, file, bag),
+            _ => {}
+        }
+    }
+}
+
+fn check_function(function: &Function, file: &str, bag: &mut DiagnosticBag) {
+    if matches!(function.return_type.kind, TypeKind::Void) {
+        return;
+    }
+    // An `extern` function has no body to check -- its "every path
+    // returns" is a property of the native implementation, not something
+    // this compiler can see.
+    if function.extern_info.is_some() {
+        return;
+    }
+    if block_terminates(&function.body) {
+        return;
+    }
+    bag.push(
+        Diagnostic::error(
+            "E206",
+            format!(
+                "function `{}` doesn't return a value on every path",
+                function.name
+            ),
+            function.position,
+        ),
+        file,
+    );
+}
+
+/// Whether control can never fall off the end of `statements` -- either
+/// because one of them unconditionally returns, or because one hands
+/// control elsewhere (an infinite loop with no escaping `break`) and
+/// nothing after it matters.
+fn block_terminates(statements: &[Statement]) -> bool {
+    statements.iter().any(statement_terminates)
+}
+
+fn statement_terminates(statement: &Statement) -> bool {
+    match &statement.kind {
+        StatementKind::Return(_) => true,
+        StatementKind::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            let Some(else_branch) = else_branch else {
+                return false;
+            };
+            block_terminates(then_branch) && block_terminates(else_branch)
+        }
+        StatementKind::While { condition, body } => is_literal_true(condition) && !contains_break(body),
+        StatementKind::Match { arms, .. } => {
+            arms.iter().any(|arm| is_catch_all(&arm.pattern)) && arms.iter().all(|arm| block_terminates(&arm.body))
+        }
+        StatementKind::Block(inner) => block_terminates(inner),
+        StatementKind::For { .. }
+        | StatementKind::ForEach { .. }
+        | StatementKind::Expression(_)
+        | StatementKind::Let { .. }
+        | StatementKind::Break
+        | StatementKind::Continue
+        | StatementKind::StructDecl(_)
+        | StatementKind::EnumDecl(_)
+        | StatementKind::InterfaceDecl(_)
+        | StatementKind::ImplBlock(_)
+        | StatementKind::ModuleDecl(_)
+        | StatementKind::FunctionDecl(_)
+        | StatementKind::Import(_)
+        | StatementKind::Export(_)
+        | StatementKind::Error => false,
+    }
+}
+
+fn is_literal_true(expr: &Expression) -> bool {
+    matches!(expr.kind, ExpressionKind::Literal(LiteralValue::Bool(true)))
+}
+
+fn is_catch_all(pattern: &Pattern) -> bool {
+    matches!(pattern, Pattern::Wildcard | Pattern::Identifier(_))
+}
+
+/// Whether `statements` contains a `break` that would escape the loop
+/// being checked -- not counting one nested inside a loop or `match` of
+/// its own, since that `break` (or, for a `match`, none at all -- this
+/// language has no `break`-out-of-match) escapes the inner one instead.
+fn contains_break(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match &statement.kind {
+        StatementKind::Break => true,
+        StatementKind::If {
+            then_branch,
+            else_branch,
+            ..
+        } => contains_break(then_branch) || else_branch.as_deref().is_some_and(contains_break),
+        StatementKind::Block(inner) => contains_break(inner),
+        StatementKind::Match { arms, .. } => arms.iter().any(|arm| contains_break(&arm.body)),
+        StatementKind::While { .. } | StatementKind::For { .. } | StatementKind::ForEach { .. } => false,
+        _ => false,
+    })
+}
@@ -0,0 +1,231 @@
+//! Ties the lexer, parser and resolver together into a single entry point.
+
+use crate::ast::Module;
+use crate::errors::{Counts, Diagnostic, DiagnosticBag};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::semantic::environment::SymbolTable;
+use crate::semantic::{FirstPassResolver, Resolver, Typechecker};
+use crate::utils::project::ProjectManifest;
+use crate::utils::timings::PhaseTimings;
+use std::rc::Rc;
+
+/// The result of a single [`compile`]/[`compile_source`] call: the parsed
+/// (and, unless something failed, fully resolved) module, whether any
+/// diagnostic reached error severity, and how many of each severity were
+/// reported along the way — everything a driver needs to print a summary
+/// line and choose an exit code without re-deriving either from `module`.
+pub struct CompileResult {
+    pub module: Module,
+    pub had_error: bool,
+    pub counts: Counts,
+    /// Every diagnostic collected along the way, paired with the file it
+    /// belongs to (an import graph can attribute diagnostics to files
+    /// other than `file`/`name` itself) -- for a caller that needs the
+    /// structured data rather than what [`Diagnostic::report`] already
+    /// printed, e.g. `matcha lsp`'s `textDocument/publishDiagnostics`.
+    pub diagnostics: Vec<(String, Diagnostic)>,
+    /// Every top-level declaration the resolver found, for a caller that
+    /// wants to inspect the module's interface (embedders, `matcha lsp`)
+    /// without re-resolving it. Empty when a cache hit skipped resolution
+    /// entirely (see the incremental-cache check below) -- the cached
+    /// verdict didn't need to rebuild one.
+    pub symbols: SymbolTable,
+    /// Wall time spent in each phase (lex, parse, first pass, resolve,
+    /// typecheck, and -- with a project manifest -- resolving/
+    /// type-checking dependencies), for `matcha ... --timings`. Recorded
+    /// unconditionally; nothing here is skipped when the flag is off.
+    pub timings: PhaseTimings,
+}
+
+/// Lexes, parses and resolves `source`, returning the module and whether
+/// any errors were reported along the way.
+///
+/// If `file` sits inside a project (a `matcha.toml` in one of its parent
+/// directories), that manifest's std/source/dependency layout is used to
+/// resolve `import`s; otherwise imports are resolved relative to `file`
+/// itself, so a single loose script still works.
+///
+/// `strip_dead_code` removes any top-level function, struct or global
+/// constant the [`crate::semantic::dce`] pass finds unreachable, instead
+/// of merely warning about it.
+pub fn compile(file: &str, source: &str, strip_dead_code: bool) -> CompileResult {
+    compile_impl(file, source, strip_dead_code, true, false)
+}
+
+/// Same as [`compile`], but type-checks this file's own queued functions
+/// across `std::thread::available_parallelism()` threads instead of one
+/// at a time -- see [`crate::semantic::Typechecker::run_parallel`]. Worth
+/// reaching for once a file has enough top-level functions that spinning
+/// up threads pays for itself; [`compile`] stays the default since most
+/// files don't.
+pub fn compile_parallel(file: &str, source: &str, strip_dead_code: bool) -> CompileResult {
+    compile_impl(file, source, strip_dead_code, true, true)
+}
+
+/// Lexes, parses and resolves `source` as a standalone, in-memory unit:
+/// no `matcha.toml` lookup, no incremental-cache read or write, no import
+/// resolution against a project layout. `name` is only used to label
+/// diagnostics and positions, the way `file` labels them for [`compile`];
+/// it doesn't need to point at a real path. Meant for tooling and tests
+/// that want to compile a string without touching the filesystem, and for
+/// `matcha check -` reading a script from stdin.
+pub fn compile_source(name: &str, source: &str) -> CompileResult {
+    compile_impl(name, source, false, false, false)
+}
+
+fn compile_impl(
+    file: &str,
+    source: &str,
+    strip_dead_code: bool,
+    use_project: bool,
+    parallel_typecheck: bool,
+) -> CompileResult {
+    crate::errors::reset();
+
+    // Shared by the parser, resolver and typechecker below so their
+    // diagnostics all land in one bag: rendered together, sorted by
+    // position, once this file's whole pipeline has run, instead of every
+    // parser error printing ahead of every resolver error regardless of
+    // which comes first in the source.
+    let mut bag = DiagnosticBag::new();
+    let mut timings = PhaseTimings::new();
+
+    let mut lexer = Lexer::new(source);
+    let tokens = timings.time("lex", || lexer.scan_tokens());
+
+    let mut parser = Parser::new(tokens, file, &mut bag);
+    let mut module = timings.time("parse", || parser.parse());
+    let parser_had_error = parser.had_error;
+
+    #[cfg(debug_assertions)]
+    {
+        for problem in crate::ast::validate::validate(&module, parser_had_error) {
+            eprintln!("{}", problem);
+        }
+    }
+
+    crate::semantic::constant_fold::fold(&mut module);
+
+    // Dead-code and lint analysis run on `const` initializers before
+    // `const_eval` folds each one down to a literal: once that's
+    // happened, an initializer that was only `let X = OTHER_CONST * 2`
+    // no longer mentions `OTHER_CONST` by name, so a reference tracked
+    // only through const-folded identifiers would otherwise look dead.
+    let dead_code = crate::semantic::dce::analyze(&module, file, &mut bag);
+    if strip_dead_code {
+        crate::semantic::dce::strip(&mut module, &dead_code);
+    }
+    crate::semantic::lint::analyze(&module, file, &mut bag);
+
+    crate::semantic::const_eval::analyze(&mut module, file, &mut bag);
+    crate::semantic::returns::analyze(&module, file, &mut bag);
+    let pre_resolve_had_error = bag.had_error();
+
+    let project = if use_project {
+        std::path::Path::new(file)
+            .parent()
+            .and_then(ProjectManifest::find)
+            .and_then(|manifest_path| ProjectManifest::load(&manifest_path).ok())
+            .map(Rc::new)
+    } else {
+        None
+    };
+
+    // With a manifest to resolve paths against, the whole import graph is
+    // known upfront: resolve and type-check its independent layers in
+    // parallel before the entry file's own (sequential) import handling
+    // runs, so that pass mostly hits cache instead of reparsing
+    // dependencies one at a time -- and so a broken dependency's
+    // diagnostics count toward this compile's own verdict, rather than
+    // only surfacing whenever that dependency happens to be compiled
+    // directly.
+    let compilation = project.as_deref().map(|project| {
+        timings.time("dependencies", || {
+            crate::semantic::graph::Compilation::run(std::path::Path::new(file), project)
+        })
+    });
+    let dependency_had_error = compilation.as_ref().is_some_and(|compilation| compilation.had_error);
+    let warmed_modules = compilation.map(|compilation| compilation.modules);
+
+    // If this exact file and everything it transitively imports matched
+    // the fingerprint from the last compile, the resolver and
+    // typechecker already ran against unchanged input: reuse that
+    // verdict *and* the diagnostics it found, instead of redoing the
+    // work and reprinting nothing. `compile_source` skips this entirely,
+    // since it has no path on disk to cache against.
+    if use_project {
+        if let Some((cached_had_error, cached_diagnostics)) =
+            crate::utils::incremental::check(std::path::Path::new(file), source, project.as_deref())
+        {
+            for (diagnostic_file, diagnostic) in cached_diagnostics {
+                bag.push(diagnostic, diagnostic_file);
+            }
+            let diagnostics = bag.entries();
+            bag.report_all();
+            return CompileResult {
+                module,
+                had_error: parser_had_error || pre_resolve_had_error || cached_had_error,
+                counts: crate::errors::counts(),
+                diagnostics,
+                symbols: SymbolTable::new(),
+                timings,
+            };
+        }
+    }
+
+    let mut symtable = SymbolTable::new();
+    timings.time("first_pass", || FirstPassResolver::new(&mut symtable).run(&module));
+
+    // Marks where the resolver/typechecker's own diagnostics start, so
+    // only those (not the lexer/parser/pre-resolve ones already covered
+    // by a plain re-run) get written to the `.mtc` cache below.
+    let cacheable_diagnostics_start = bag.len();
+
+    let resolver_had_error = timings.time("resolve", || {
+        let mut resolver = Resolver::new(&mut symtable, file, &mut bag);
+        resolver.project = project.clone();
+        if let Some(warmed_modules) = warmed_modules {
+            resolver.seed_modules(warmed_modules);
+        }
+        resolver.resolve(&module);
+        resolver.had_error
+    });
+
+    let mut typechecker = Typechecker::new(file, &mut bag);
+    typechecker.seed(&symtable);
+    timings.time("typecheck", || {
+        if parallel_typecheck {
+            typechecker.run_parallel();
+        } else {
+            typechecker.run();
+        }
+    });
+
+    let had_error = parser_had_error
+        || pre_resolve_had_error
+        || resolver_had_error
+        || typechecker.had_error
+        || dependency_had_error;
+    if use_project {
+        let cacheable_diagnostics = &bag.entries()[cacheable_diagnostics_start..];
+        crate::utils::incremental::store(
+            std::path::Path::new(file),
+            source,
+            project.as_deref(),
+            had_error,
+            cacheable_diagnostics,
+        );
+    }
+
+    let diagnostics = bag.entries();
+    bag.report_all();
+    CompileResult {
+        module,
+        had_error,
+        counts: crate::errors::counts(),
+        diagnostics,
+        symbols: symtable,
+        timings,
+    }
+}
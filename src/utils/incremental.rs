@@ -0,0 +1,85 @@
+//! On-disk cache that lets [`crate::utils::compile::compile`] skip
+//! re-resolving and re-typechecking an entry file whose content, and
+//! whose transitive dependencies' content, haven't changed since the
+//! last compile. Lexing and parsing still run on every call — the
+//! returned [`crate::ast::Module`] is needed either way — but the
+//! resolver and typechecker are where nearly all of a compile's cost
+//! lives, and those are exactly what a cache hit skips.
+//!
+//! The diagnostics those phases reported the first time are cached
+//! alongside the verdict and replayed verbatim on a hit -- `check`'s
+//! entire purpose is reporting diagnostics, so a hit that only restored
+//! `had_error` and printed nothing would make a second, unchanged
+//! `matcha check` silently report success-shaped output for a file that
+//! still doesn't compile.
+
+use crate::errors::Diagnostic;
+use crate::semantic::graph::DependencyGraph;
+use crate::utils::project::ProjectManifest;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// First line of a `.mtc` compile cache file, bumped if the format
+/// changes so a cache written by an older compiler is never misread.
+const CACHE_MAGIC: &str = "matcha-compile-cache v2";
+
+fn cache_path(file: &Path) -> PathBuf {
+    file.with_extension("mtc")
+}
+
+/// Hashes `source` together with the content of every file `file`
+/// transitively imports, so a change anywhere in the dependency graph
+/// invalidates the cache, not just a change to `file` itself.
+fn fingerprint(file: &Path, source: &str, project: Option<&ProjectManifest>) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    source.hash(&mut hasher);
+    if let Some(project) = project {
+        let graph = DependencyGraph::discover(file, project);
+        let mut dep_files = graph.files();
+        dep_files.sort();
+        for dep_file in dep_files {
+            if let Ok(dep_source) = std::fs::read_to_string(&dep_file) {
+                dep_source.hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+/// Returns `Some((had_error, diagnostics))` from the last compile if
+/// `file` and every file it transitively imports are unchanged since
+/// that compile wrote its `.mtc` cache; `None` means a full
+/// resolve/typecheck is needed. The caller is expected to re-report
+/// `diagnostics` (e.g. via a fresh [`crate::errors::DiagnosticBag`]) the
+/// same way it would have the first time -- a cache hit must look
+/// exactly like the compile it's standing in for, not a silently
+/// cleaner one.
+pub fn check(file: &Path, source: &str, project: Option<&ProjectManifest>) -> Option<(bool, Vec<(String, Diagnostic)>)> {
+    let text = std::fs::read_to_string(cache_path(file)).ok()?;
+    let mut lines = text.lines();
+    if lines.next()? != CACHE_MAGIC {
+        return None;
+    }
+    let mut parts = lines.next()?.split_whitespace();
+    let cached_fingerprint: u64 = parts.next()?.parse().ok()?;
+    let had_error = parts.next()? == "error";
+    if cached_fingerprint != fingerprint(file, source, project) {
+        return None;
+    }
+
+    let diagnostics = lines.map(Diagnostic::from_cache_line).collect::<Option<Vec<_>>>()?;
+    Some((had_error, diagnostics))
+}
+
+/// Writes `file`'s current fingerprint, result and diagnostics to its
+/// `.mtc` cache.
+pub fn store(file: &Path, source: &str, project: Option<&ProjectManifest>, had_error: bool, diagnostics: &[(String, Diagnostic)]) {
+    let fingerprint = fingerprint(file, source, project);
+    let status = if had_error { "error" } else { "ok" };
+    let mut text = format!("{}\n{} {}\n", CACHE_MAGIC, fingerprint, status);
+    for (diagnostic_file, diagnostic) in diagnostics {
+        text.push_str(&diagnostic.to_cache_line(diagnostic_file));
+        text.push('\n');
+    }
+    let _ = std::fs::write(cache_path(file), text);
+}
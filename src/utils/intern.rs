@@ -0,0 +1,58 @@
+//! A process-wide string interner. [`Symbol`] is a `u32` id that compares
+//! and hashes as an integer instead of walking a `String`'s bytes, and
+//! [`SymbolTable`](crate::semantic::environment::SymbolTable) uses it to
+//! index its declarations and local scopes -- `lookup`/`get_struct`/
+//! `get_function` were previously a linear scan comparing `String`s one
+//! byte at a time; interning turns both the comparison and the index
+//! lookup into an integer operation, and collapses however many times a
+//! name like `self` or `i` appears in a file down to one stored copy.
+//!
+//! Only identifier lookups inside the symbol table go through this today
+//! -- token lexemes and AST names stay plain `String`s, since diagnostics,
+//! the printer and the JSON emitters all want the literal text anyway and
+//! interning them would just add a resolve step in front of every one of
+//! those.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// An interned name. Cheap to copy and compare; use [`Symbol::as_str`] to
+/// get the text back for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Interns `name`, returning its id -- the same id every time the same
+    /// text is interned again.
+    pub fn intern(name: &str) -> Self {
+        let mut interner = INTERNER.lock().unwrap();
+        let interner = interner.get_or_insert_with(Interner::default);
+        if let Some(&symbol) = interner.ids.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(interner.strings.len() as u32);
+        interner.strings.push(name.to_string());
+        interner.ids.insert(name.to_string(), symbol);
+        symbol
+    }
+
+    /// The original text this id was interned from.
+    pub fn as_str(self) -> String {
+        let interner = INTERNER.lock().unwrap();
+        interner.as_ref().unwrap().strings[self.0 as usize].clone()
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Default)]
+struct Interner {
+    strings: Vec<String>,
+    ids: HashMap<String, Symbol>,
+}
+
+static INTERNER: Mutex<Option<Interner>> = Mutex::new(None);
@@ -0,0 +1,7 @@
+pub mod compile;
+pub mod incremental;
+pub mod intern;
+pub mod module;
+pub mod project;
+pub mod timings;
+pub mod vfs;
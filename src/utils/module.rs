@@ -0,0 +1,105 @@
+//! The public interface of a compiled module, as consumed by importers.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportedKind {
+    Function,
+    Struct,
+    Enum,
+    Interface,
+    /// A name that was itself brought in via `import` and is being
+    /// re-exported as-is, rather than declared in this file -- lets a
+    /// facade module (e.g. `std` re-exporting `std.io`) pass a name
+    /// through without importers needing to know where it really lives.
+    Module,
+}
+
+impl ExportedKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ExportedKind::Function => "function",
+            ExportedKind::Struct => "struct",
+            ExportedKind::Enum => "enum",
+            ExportedKind::Interface => "interface",
+            ExportedKind::Module => "module",
+        }
+    }
+
+    fn from_str(text: &str) -> Option<Self> {
+        Some(match text {
+            "function" => ExportedKind::Function,
+            "struct" => ExportedKind::Struct,
+            "enum" => ExportedKind::Enum,
+            "interface" => ExportedKind::Interface,
+            "module" => ExportedKind::Module,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedSymbol {
+    pub name: String,
+    pub kind: ExportedKind,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MatchaModule {
+    pub name: String,
+    pub exported_symbols: Vec<ExportedSymbol>,
+}
+
+/// First line of a `.mti` interface file, bumped if the format changes so
+/// a cache written by an older compiler is never misread.
+const INTERFACE_MAGIC: &str = "matcha-module-interface v1";
+
+impl MatchaModule {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            exported_symbols: Vec::new(),
+        }
+    }
+
+    pub fn from_symtable(name: impl Into<String>, symtable: &crate::semantic::environment::SymbolTable) -> Self {
+        Self {
+            name: name.into(),
+            exported_symbols: symtable.exported_symbols.clone(),
+        }
+    }
+
+    /// Serializes this module's interface for a `.mti` cache file, tagged
+    /// with the mtime (seconds since the epoch) of the source it was
+    /// compiled from, so a reader can tell whether the cache is stale.
+    pub fn to_interface(&self, source_mtime: u64) -> String {
+        let mut out = format!("{}\nname {}\nmtime {}\n", INTERFACE_MAGIC, self.name, source_mtime);
+        for symbol in &self.exported_symbols {
+            out.push_str(&format!("symbol {} {}\n", symbol.name, symbol.kind.as_str()));
+        }
+        out
+    }
+
+    /// Parses a `.mti` file written by [`to_interface`](Self::to_interface),
+    /// returning the module and the source mtime it was cached against.
+    pub fn from_interface(text: &str) -> Option<(Self, u64)> {
+        let mut lines = text.lines();
+        if lines.next()? != INTERFACE_MAGIC {
+            return None;
+        }
+        let mut name = String::new();
+        let mut mtime = None;
+        let mut exported_symbols = Vec::new();
+        for line in lines {
+            let mut parts = line.split_whitespace();
+            match parts.next()? {
+                "name" => name = parts.next()?.to_string(),
+                "mtime" => mtime = Some(parts.next()?.parse().ok()?),
+                "symbol" => exported_symbols.push(ExportedSymbol {
+                    name: parts.next()?.to_string(),
+                    kind: ExportedKind::from_str(parts.next()?)?,
+                }),
+                _ => return None,
+            }
+        }
+        Some((Self { name, exported_symbols }, mtime?))
+    }
+}
@@ -0,0 +1,119 @@
+//! Project manifests (`matcha.toml`): package name, source layout, the
+//! standard library location, and path dependencies.
+//!
+//! There's no external TOML crate here — the format only ever needs a
+//! couple of flat `[section]` / `key = "value"` blocks, so a full parser
+//! would be more machinery than the problem calls for.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "matcha.toml";
+
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectManifest {
+    pub name: String,
+    /// Directory containing `matcha.toml`; every other path here is
+    /// resolved relative to this one.
+    pub root: PathBuf,
+    pub source_root: PathBuf,
+    pub std_path: Option<PathBuf>,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl ProjectManifest {
+    /// Walks upward from `start_dir` looking for `matcha.toml`, the same
+    /// way a bare `.matcharoot` marker used to be probed for.
+    pub fn find(start_dir: &Path) -> Option<PathBuf> {
+        let mut dir = Some(start_dir);
+        while let Some(d) = dir {
+            let candidate = d.join(MANIFEST_FILE);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            dir = d.parent();
+        }
+        None
+    }
+
+    pub fn load(manifest_path: &Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(manifest_path)
+            .map_err(|e| format!("could not read '{}': {}", manifest_path.display(), e))?;
+        let root = manifest_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+
+        let mut section = String::new();
+        let mut package: HashMap<String, String> = HashMap::new();
+        let mut dependencies = Vec::new();
+
+        for raw_line in source.lines() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..line.len() - 1].trim().to_string();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_string();
+            let value = value.trim().trim_matches('"').to_string();
+            match section.as_str() {
+                "package" => {
+                    package.insert(key, value);
+                }
+                "dependencies" => dependencies.push(Dependency {
+                    name: key,
+                    path: root.join(value),
+                }),
+                _ => {}
+            }
+        }
+
+        let name = package
+            .get("name")
+            .cloned()
+            .ok_or_else(|| format!("'{}' is missing a [package] name", manifest_path.display()))?;
+        let source_root = root.join(package.get("source").map(String::as_str).unwrap_or("src"));
+        let std_path = package.get("std").map(|p| root.join(p));
+
+        Ok(Self {
+            name,
+            root,
+            source_root,
+            std_path,
+            dependencies,
+        })
+    }
+
+    /// Resolves a dotted import path (`std.net.http`, `mypkg.util`) to a
+    /// `.mt` source file: `std.*` under the manifest's std path, a name
+    /// matching a dependency under that dependency's path, everything else
+    /// under the project's source root.
+    pub fn resolve_import(&self, path: &[String]) -> Option<PathBuf> {
+        let first = path.first()?;
+        if first == "std" {
+            return Some(with_segments(self.std_path.as_ref()?, &path[1..]));
+        }
+        if let Some(dep) = self.dependencies.iter().find(|d| &d.name == first) {
+            return Some(with_segments(&dep.path, &path[1..]));
+        }
+        Some(with_segments(&self.source_root, path))
+    }
+}
+
+fn with_segments(base: &Path, segments: &[String]) -> PathBuf {
+    let mut file = base.join(segments.join("/"));
+    file.set_extension("mt");
+    file
+}
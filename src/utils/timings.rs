@@ -0,0 +1,70 @@
+//! Per-phase wall-clock timing for `matcha ... --timings`.
+//!
+//! [`PhaseTimings::time`] wraps each phase [`crate::utils::compile::compile`]
+//! runs, so a build that suddenly gets slower shows which phase grew
+//! instead of just a bigger overall wall time. Every [`CompileResult`]
+//! carries its own [`PhaseTimings`] regardless of whether `--timings` was
+//! passed -- an `Instant::now()` pair per phase is cheap enough not to
+//! bother gating -- the flag only decides whether `matcha` prints them.
+//!
+//! Only wall time is tracked. Attributing memory to a phase would need a
+//! custom global allocator instrumenting every allocation, which is a lot
+//! of machinery for a CLI flag when `valgrind --tool=massif`/`heaptrack`
+//! already do memory better than anything hand-rolled here would.
+//!
+//! [`CompileResult`]: crate::utils::compile::CompileResult
+
+use std::time::{Duration, Instant};
+
+/// One phase's measured wall time, in the order phases were recorded.
+#[derive(Debug, Default, Clone)]
+pub struct PhaseTimings {
+    phases: Vec<(String, Duration)>,
+}
+
+impl PhaseTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f`, recording its wall time under `name` before returning
+    /// its result.
+    pub fn time<T>(&mut self, name: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.phases.push((name.to_string(), start.elapsed()));
+        result
+    }
+
+    fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Renders one line per phase plus a trailing total, phase names
+    /// padded to the widest one so the millisecond column lines up.
+    pub fn to_table(&self) -> String {
+        let width = self.phases.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("total".len());
+        let mut out = String::new();
+        for (name, duration) in &self.phases {
+            out.push_str(&format!("{:width$}  {:>10.3}ms\n", name, ms(*duration), width = width));
+        }
+        out.push_str(&format!("{:width$}  {:>10.3}ms\n", "total", ms(self.total()), width = width));
+        out
+    }
+
+    /// Renders as a JSON array of `{"phase":...,"ms":...}` objects, plus a
+    /// trailing `"total"` entry.
+    pub fn to_json(&self) -> String {
+        let mut entries: Vec<String> = self
+            .phases
+            .iter()
+            .map(|(name, duration)| format!("{{\"phase\":{:?},\"ms\":{}}}", name, ms(*duration)))
+            .collect();
+        entries.push(format!("{{\"phase\":\"total\",\"ms\":{}}}", ms(self.total())));
+        format!("[{}]", entries.join(","))
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
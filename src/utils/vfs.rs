@@ -0,0 +1,84 @@
+//! A pluggable source of file contents for diagnostic rendering, instead
+//! of the caret snippet under a diagnostic always reading straight from
+//! `std::fs`. `matcha lsp` installs an [`InMemorySourceManager`] over its
+//! open documents so an unsaved edit still gets a snippet; embedders and
+//! tests can do the same to render diagnostics for sources that were
+//! never written to disk at all.
+//!
+//! This only covers where a diagnostic's source line comes from
+//! ([`crate::errors::Diagnostic::report`]) -- import resolution
+//! ([`crate::semantic::Resolver::visit_import`]) and the incremental
+//! cache still read modules straight off disk, since neither has an
+//! in-memory caller today.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub trait SourceManager: Send + Sync {
+    fn read_to_string(&self, path: &str) -> Option<String>;
+}
+
+/// Lets an `Arc<InMemorySourceManager>` be installed via
+/// [`set_source_manager`] while a caller keeps its own handle to update
+/// the overlay afterwards -- `matcha lsp` needs both.
+impl<T: SourceManager + ?Sized> SourceManager for Arc<T> {
+    fn read_to_string(&self, path: &str) -> Option<String> {
+        (**self).read_to_string(path)
+    }
+}
+
+/// The default manager: reads straight from disk, exactly like the
+/// `std::fs::read_to_string` calls this replaces.
+pub struct FsSourceManager;
+
+impl SourceManager for FsSourceManager {
+    fn read_to_string(&self, path: &str) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+}
+
+/// Maps paths to in-memory contents, with no filesystem access at all.
+/// What `matcha lsp` overlays each open buffer onto, keyed by the
+/// document's URI (the same string used as the diagnostic's file label).
+#[derive(Default)]
+pub struct InMemorySourceManager {
+    files: Mutex<HashMap<String, String>>,
+}
+
+impl InMemorySourceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, path: impl Into<String>, contents: impl Into<String>) {
+        self.files.lock().unwrap().insert(path.into(), contents.into());
+    }
+
+    pub fn remove(&self, path: &str) {
+        self.files.lock().unwrap().remove(path);
+    }
+}
+
+impl SourceManager for InMemorySourceManager {
+    fn read_to_string(&self, path: &str) -> Option<String> {
+        self.files.lock().unwrap().get(path).cloned()
+    }
+}
+
+static SOURCE_MANAGER: Mutex<Option<Box<dyn SourceManager>>> = Mutex::new(None);
+
+/// Installs `manager` as the crate-wide source of file contents for
+/// diagnostic snippets. Meant to be called once, near startup; the last
+/// call wins. Nothing installed falls back to [`FsSourceManager`].
+pub fn set_source_manager(manager: Box<dyn SourceManager>) {
+    *SOURCE_MANAGER.lock().unwrap() = Some(manager);
+}
+
+/// Reads `path` through the installed [`SourceManager`], or straight from
+/// disk if none has been installed.
+pub fn read_to_string(path: &str) -> Option<String> {
+    match SOURCE_MANAGER.lock().unwrap().as_ref() {
+        Some(manager) => manager.read_to_string(path),
+        None => FsSourceManager.read_to_string(path),
+    }
+}
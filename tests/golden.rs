@@ -0,0 +1,86 @@
+//! Golden-file integration tests: every `.mt` file under `tests/cases/` is
+//! compiled with [`matcha::compile_source`] and its diagnostics checked
+//! against `// ERROR: <code>` annotations on the offending line, rustc
+//! UI-test style. A file with no such annotations is expected to compile
+//! clean; one that reports a diagnostic without a matching annotation, or
+//! whose annotation never fires, fails the test with both lists printed
+//! so the mismatch is obvious without re-running `matcha check` by hand.
+//! A line that fires more than one diagnostic (e.g. a resolver error
+//! alongside an unrelated `E010`/`E011` lint warning) lists every code
+//! after the one `// ERROR:` marker, comma-separated.
+//!
+//! A case that also wants its parsed shape pinned down can check in a
+//! `<name>.mt.ast` next to it holding `{:#?}` of the resolved
+//! [`matcha::ast::Module`]; if that file exists, the test additionally
+//! diffs it against a freshly rendered dump. There's no `--bless` here --
+//! for a case this small, regenerating and reviewing the diff by hand
+//! (`matcha ast tests/cases/<name>.mt` in `--emit=ast` form, or just
+//! `cargo test -- --nocapture` to see the mismatch) is simpler than
+//! wiring up an update mode.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// An expected or actual diagnostic, identified by the 1-indexed source
+/// line it's attached to and its code -- enough to catch both a missing
+/// and a spurious diagnostic without being sensitive to message wording.
+type Expectation = (usize, String);
+
+fn expected_errors(source: &str) -> BTreeSet<Expectation> {
+    source
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            let codes = line.split("// ERROR:").nth(1).unwrap_or("");
+            codes
+                .split(',')
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(move |code| (index + 1, code.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn golden_cases() {
+    let cases_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases");
+    let mut failures = Vec::new();
+
+    for entry in fs::read_dir(&cases_dir).expect("tests/cases should exist") {
+        let path = entry.expect("readable tests/cases entry").path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("mt") {
+            continue;
+        }
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let source = fs::read_to_string(&path).expect("readable .mt case file");
+
+        let expected = expected_errors(&source);
+        let result = matcha::compile_source(&name, &source);
+        let actual: BTreeSet<Expectation> = result
+            .diagnostics
+            .iter()
+            .map(|(_, diagnostic)| (diagnostic.position.line, diagnostic.code.clone()))
+            .collect();
+
+        if expected != actual {
+            let missing: Vec<_> = expected.difference(&actual).collect();
+            let unexpected: Vec<_> = actual.difference(&expected).collect();
+            failures.push(format!(
+                "{}: missing {:?}, unexpected {:?}",
+                name, missing, unexpected
+            ));
+        }
+
+        let ast_golden = path.with_extension("mt.ast");
+        if let Ok(expected_ast) = fs::read_to_string(&ast_golden) {
+            let actual_ast = format!("{:#?}\n", result.module);
+            if actual_ast != expected_ast {
+                failures.push(format!("{}: AST dump doesn't match {}", name, ast_golden.display()));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "golden case mismatches:\n{}", failures.join("\n"));
+}